@@ -0,0 +1,57 @@
+use std::sync::Mutex;
+
+use pyo3::prelude::*;
+use temporal_sdk_core::protos::coresdk::activity_task::ActivityCancelReason;
+
+
+pub(crate) fn activity_cancel_reason_name(reason: i32) -> &'static str {
+    match ActivityCancelReason::from_i32(reason) {
+        Some(ActivityCancelReason::NotFound) => "not_found",
+        Some(ActivityCancelReason::Cancelled) => "cancelled",
+        None => "unknown",
+    }
+}
+
+
+// Cancellation state of a running activity, shared between the worker that gets the Cancel task and the activity
+// invocation itself; coroutine activities get their task cancelled, sync ones have to check activity.is_cancelled()
+#[derive(Default)]
+pub(crate) struct ActivityCancellation {
+    reason: Mutex<Option<&'static str>>,
+    // asyncio.Task of a coroutine activity once it got created on the worker's event loop
+    task: Mutex<Option<PyObject>>,
+}
+
+impl ActivityCancellation {
+    pub(crate) fn reason(&self) -> Option<&'static str> {
+        *self.reason.lock().unwrap()
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.reason().is_some()
+    }
+
+    fn cancel_task(py: Python, task: &PyObject) -> PyResult<()> {
+        let event_loop = task.call_method0(py, "get_loop")?;
+        event_loop.call_method1(py, "call_soon_threadsafe", (task.getattr(py, "cancel")?,))?;
+        Ok(())
+    }
+
+    pub(crate) fn cancel(&self, py: Python, reason: i32) -> PyResult<()> {
+        *self.reason.lock().unwrap() = Some(activity_cancel_reason_name(reason));
+        let task = self.task.lock().unwrap().as_ref().map(|task| task.clone_ref(py));
+        match task {
+            None => Ok(()),
+            Some(task) => ActivityCancellation::cancel_task(py, &task),
+        }
+    }
+
+    // Cancellation might have arrived while the task was still being scheduled
+    pub(crate) fn set_task(&self, py: Python, task: PyObject) -> PyResult<()> {
+        *self.task.lock().unwrap() = Some(task.clone_ref(py));
+        if self.is_cancelled() {
+            ActivityCancellation::cancel_task(py, &task)?;
+        }
+        Ok(())
+    }
+}
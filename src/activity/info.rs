@@ -0,0 +1,149 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::once_cell::GILOnceCell;
+use pyo3::types::PyBytes;
+use pyo3_chrono;
+
+use crate::activity::cancellation::ActivityCancellation;
+use crate::protos::coresdk::{
+    activity_task::WrappedStart,
+    common::{
+        WrappedPayload,
+        WrappedRetryPolicy,
+        WrappedWorkflowExecution,
+    },
+};
+use crate::utils::UtcDateTime;
+
+
+#[pyclass(name = "ActivityInfo")]
+#[derive(Clone)]
+pub struct WrappedActivityInfo {
+    pub task_token: Vec<u8>,
+    #[pyo3(get)]
+    pub task_queue: String,
+    #[pyo3(get)]
+    pub activity_id: String,
+    #[pyo3(get)]
+    pub activity_type: String,
+    #[pyo3(get)]
+    pub workflow_namespace: String,
+    #[pyo3(get)]
+    pub workflow_type: String,
+    #[pyo3(get)]
+    pub workflow_execution: Option<WrappedWorkflowExecution>,
+    #[pyo3(get)]
+    pub header_fields: HashMap<String, WrappedPayload>,
+    #[pyo3(get)]
+    pub heartbeat_details: Vec<WrappedPayload>,
+    #[pyo3(get)]
+    pub scheduled_time: Option<UtcDateTime>,
+    #[pyo3(get)]
+    pub current_attempt_scheduled_time: Option<UtcDateTime>,
+    #[pyo3(get)]
+    pub started_time: Option<UtcDateTime>,
+    #[pyo3(get)]
+    pub attempt: i32,
+    #[pyo3(get)]
+    pub schedule_to_close_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get)]
+    pub start_to_close_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get)]
+    pub heartbeat_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get)]
+    pub retry_policy: Option<WrappedRetryPolicy>,
+    pub cancellation: Arc<ActivityCancellation>,
+}
+
+#[pymethods]
+impl WrappedActivityInfo {
+    #[getter]
+    fn get_task_token<'p>(&self, py: Python<'p>) -> &'p PyBytes {
+        PyBytes::new(py, &self.task_token)
+    }
+}
+
+impl WrappedActivityInfo {
+    pub(crate) fn new(task_token: Vec<u8>,
+                      task_queue: String,
+                      activity_id: String,
+                      start: WrappedStart,
+                      cancellation: Arc<ActivityCancellation>) -> Self {
+        WrappedActivityInfo {
+            task_token,
+            task_queue,
+            activity_id,
+            activity_type: start.activity_type,
+            workflow_namespace: start.workflow_namespace,
+            workflow_type: start.workflow_type,
+            workflow_execution: start.workflow_execution,
+            header_fields: start.header_fields,
+            heartbeat_details: start.heartbeat_details,
+            scheduled_time: start.scheduled_time,
+            current_attempt_scheduled_time: start.current_attempt_scheduled_time,
+            started_time: start.started_time,
+            attempt: start.attempt,
+            schedule_to_close_timeout: start.schedule_to_close_timeout,
+            start_to_close_timeout: start.start_to_close_timeout,
+            heartbeat_timeout: start.heartbeat_timeout,
+            retry_policy: start.retry_policy,
+            cancellation,
+        }
+    }
+}
+
+
+static CURRENT_ACTIVITY_INFO: GILOnceCell<PyObject> = GILOnceCell::new();
+
+
+fn current_activity_info_var(py: Python) -> PyResult<&PyAny> {
+    if let Some(var) = CURRENT_ACTIVITY_INFO.get(py) {
+        return Ok(var.as_ref(py));
+    }
+    let var = py.import("contextvars")?.getattr("ContextVar")?.call1(("activity_info",))?;
+    let _ = CURRENT_ACTIVITY_INFO.set(py, var.into());
+    Ok(CURRENT_ACTIVITY_INFO.get(py).unwrap().as_ref(py))
+}
+
+
+// Context in which an activity invocation has to be run for activity.info() to return its info; coroutines pick it
+// up too as asyncio copies the caller's context into the tasks that it creates
+pub(crate) fn activity_context(py: Python, info: WrappedActivityInfo) -> PyResult<PyObject> {
+    let context = py.import("contextvars")?.call_method0("copy_context")?;
+    context.call_method1("run", (current_activity_info_var(py)?.getattr("set")?, info))?;
+    Ok(context.into())
+}
+
+
+fn current_activity_info(py: Python) -> PyResult<&PyAny> {
+    let info = current_activity_info_var(py)?.call_method1("get", (py.None(),))?;
+    if info.is_none() {
+        return Err(PyRuntimeError::new_err("Not running in an activity"));
+    }
+    Ok(info)
+}
+
+
+#[pyfunction(name = "info")]
+pub(crate) fn wrapped_info(py: Python) -> PyResult<PyObject> {
+    Ok(current_activity_info(py)?.into())
+}
+
+
+#[pyfunction(name = "is_cancelled")]
+pub(crate) fn wrapped_is_cancelled(py: Python) -> PyResult<bool> {
+    let info: PyRef<WrappedActivityInfo> = current_activity_info(py)?.extract()?;
+    Ok(info.cancellation.is_cancelled())
+}
+
+
+// "not_found" if the server doesn't know about the activity anymore, e.g. because it has timed out, "cancelled" if
+// the workflow has requested the cancellation, None while the activity isn't cancelled
+#[pyfunction(name = "cancellation_reason")]
+pub(crate) fn wrapped_cancellation_reason(py: Python) -> PyResult<Option<&'static str>> {
+    let info: PyRef<WrappedActivityInfo> = current_activity_info(py)?.extract()?;
+    Ok(info.cancellation.reason())
+}
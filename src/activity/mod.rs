@@ -0,0 +1,3 @@
+pub(crate) mod cancellation;
+pub(crate) mod info;
+pub(crate) mod registry;
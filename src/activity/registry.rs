@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::once_cell::GILOnceCell;
+use pyo3::types::PyDict;
+
+
+// Activity implementations registered with @activity.defn, keyed by activity type
+static REGISTERED_ACTIVITIES: GILOnceCell<Py<PyDict>> = GILOnceCell::new();
+
+
+fn registered_activities_dict(py: Python) -> &PyDict {
+    REGISTERED_ACTIVITIES.get_or_init(py, || PyDict::new(py).into()).as_ref(py)
+}
+
+
+pub(crate) fn registered_activities(py: Python) -> PyResult<HashMap<String, PyObject>> {
+    registered_activities_dict(py).extract()
+}
+
+
+fn register_activity(py: Python, function: PyObject, name: Option<String>) -> PyResult<PyObject> {
+    let activity_type = match name {
+        Some(name) => name,
+        None => function.getattr(py, "__name__")?.extract(py)?,
+    };
+
+    let activities = registered_activities_dict(py);
+    if let Some(registered_function) = activities.get_item(activity_type.as_str()) {
+        if !registered_function.is(function.as_ref(py)) {
+            return Err(PyValueError::new_err(format!(
+                "Activity type {} is already registered",
+                activity_type
+            )));
+        }
+    }
+    activities.set_item(activity_type, function.clone_ref(py))?;
+
+    Ok(function)
+}
+
+
+#[pyclass]
+struct ActivityDefn {
+    name: Option<String>,
+}
+
+#[pymethods]
+impl ActivityDefn {
+    #[call]
+    fn __call__(&self, py: Python, function: PyObject) -> PyResult<PyObject> {
+        register_activity(py, function, self.name.clone())
+    }
+}
+
+
+// Works both as a bare @activity.defn and as @activity.defn(name="...")
+#[pyfunction(name = "defn")]
+#[args(function = "None", "*", name = "None")]
+pub(crate) fn wrapped_defn(py: Python, function: Option<PyObject>, name: Option<String>) -> PyResult<PyObject> {
+    match function {
+        Some(function) => register_activity(py, function, name),
+        None => Ok(Py::new(py, ActivityDefn { name })?.into_py(py)),
+    }
+}
@@ -0,0 +1,340 @@
+use std::convert::TryFrom;
+
+use pyo3::prelude::*;
+use pyo3_asyncio;
+use pyo3_chrono;
+use temporal_sdk_core::{
+    ServerGatewayOptions,
+    protos::{
+        coresdk::common::RetryPolicy,
+        temporal::api::{
+            common::v1::{
+                Payload as ApiPayload,
+                Payloads,
+                RetryPolicy as ApiRetryPolicy,
+                WorkflowExecution as ApiWorkflowExecution,
+                WorkflowType,
+            },
+            enums::v1::{
+                TaskQueueKind,
+                WorkflowExecutionStatus,
+            },
+            query::v1::WorkflowQuery,
+            taskqueue::v1::TaskQueue,
+            workflowservice::v1::{
+                workflow_service_client::WorkflowServiceClient,
+                StartWorkflowExecutionRequest,
+                SignalWorkflowExecutionRequest,
+                QueryWorkflowRequest,
+                RequestCancelWorkflowExecutionRequest,
+                TerminateWorkflowExecutionRequest,
+            },
+        },
+    },
+};
+use tonic::transport::Channel;
+use uuid::Uuid;
+
+use crate::converter::data_converter::WrappedDataConverter;
+use crate::errors::{
+    ClientError,
+    tonic_status_err,
+};
+use crate::pollers::gateway::{
+    WrappedServerGatewayOptions,
+    connect_channel,
+};
+use crate::protos::coresdk::common::{
+    WrappedPayload,
+    WrappedRetryPolicy,
+    WrappedWorkflowExecution,
+};
+use crate::utils::pyo3_chrono_duration_to_prost_duration;
+
+
+fn wrapped_payloads_to_api_payloads(payloads: Vec<WrappedPayload>) -> Option<Payloads> {
+    if payloads.is_empty() {
+        return None;
+    }
+    Some(Payloads {
+        payloads: payloads.into_iter().map(|x| ApiPayload {
+            metadata: x.metadata,
+            data: x.data,
+        }).collect(),
+    })
+}
+
+
+fn api_payloads_to_wrapped_payloads(payloads: Option<Payloads>) -> Vec<WrappedPayload> {
+    match payloads {
+        None => Vec::new(),
+        Some(payloads) => payloads.payloads.into_iter().map(|x| WrappedPayload {
+            metadata: x.metadata,
+            data: x.data,
+        }).collect(),
+    }
+}
+
+
+fn wrapped_retry_policy_to_api_retry_policy(retry_policy: WrappedRetryPolicy) -> PyResult<ApiRetryPolicy> {
+    let retry_policy = RetryPolicy::try_from(retry_policy)?;
+    Ok(ApiRetryPolicy {
+        initial_interval: retry_policy.initial_interval,
+        backoff_coefficient: retry_policy.backoff_coefficient,
+        maximum_interval: retry_policy.maximum_interval,
+        maximum_attempts: retry_policy.maximum_attempts,
+        non_retryable_error_types: retry_policy.non_retryable_error_types,
+    })
+}
+
+
+fn tonic_status_to_client_error(status: tonic::Status) -> PyErr {
+    Python::with_gil(|py| tonic_status_err::<ClientError>(py, status.to_string(), &status, vec![]))
+}
+
+
+#[pyclass(name = "Client")]
+#[derive(Clone)]
+pub struct WrappedClient {
+    pub(crate) service: WorkflowServiceClient<Channel>,
+    #[pyo3(get)]
+    pub(crate) namespace: String,
+    #[pyo3(get)]
+    pub(crate) identity: String,
+    #[pyo3(get)]
+    pub(crate) data_converter: WrappedDataConverter,
+}
+
+#[pymethods]
+impl WrappedClient {
+    #[args(
+        arguments = "Vec::new()",
+        "*",
+        workflow_execution_timeout = "None",
+        workflow_run_timeout = "None",
+        workflow_task_timeout = "None",
+        retry_policy = "None"
+    )]
+    fn start_workflow<'p>(&self,
+                          py: Python<'p>,
+                          workflow_type: String,
+                          workflow_id: String,
+                          task_queue: String,
+                          arguments: Vec<PyObject>,
+                          workflow_execution_timeout: Option<pyo3_chrono::Duration>,
+                          workflow_run_timeout: Option<pyo3_chrono::Duration>,
+                          workflow_task_timeout: Option<pyo3_chrono::Duration>,
+                          retry_policy: Option<WrappedRetryPolicy>) -> PyResult<&'p PyAny> {
+        let client = self.clone();
+        let request = StartWorkflowExecutionRequest {
+            namespace: self.namespace.clone(),
+            workflow_id: workflow_id.clone(),
+            workflow_type: Some(WorkflowType {
+                name: workflow_type,
+            }),
+            task_queue: Some(TaskQueue {
+                name: task_queue,
+                kind: TaskQueueKind::Normal as i32,
+            }),
+            input: wrapped_payloads_to_api_payloads(self.data_converter.encode_all(py, arguments)?),
+            workflow_execution_timeout: pyo3_chrono_duration_to_prost_duration(workflow_execution_timeout)?,
+            workflow_run_timeout: pyo3_chrono_duration_to_prost_duration(workflow_run_timeout)?,
+            workflow_task_timeout: pyo3_chrono_duration_to_prost_duration(workflow_task_timeout)?,
+            identity: self.identity.clone(),
+            request_id: Uuid::new_v4().to_string(),
+            retry_policy: match retry_policy {
+                None => None,
+                Some(retry_policy) => Some(wrapped_retry_policy_to_api_retry_policy(retry_policy)?),
+            },
+            ..Default::default()
+        };
+
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            let mut service = client.service.clone();
+            match service.start_workflow_execution(request).await {
+                Err(status) => Err(tonic_status_to_client_error(status)),
+                Ok(response) => {
+                    Python::with_gil(|py| {
+                        let handle = WrappedWorkflowHandle {
+                            client,
+                            execution: WrappedWorkflowExecution {
+                                workflow_id,
+                                run_id: response.into_inner().run_id,
+                            },
+                        };
+                        Ok(handle.into_py(py))
+                    })
+                }
+            }
+        })
+    }
+
+    #[args(run_id = "String::new()")]
+    fn get_workflow_handle(&self, workflow_id: String, run_id: String) -> WrappedWorkflowHandle {
+        WrappedWorkflowHandle {
+            client: self.clone(),
+            execution: WrappedWorkflowExecution {
+                workflow_id,
+                run_id,
+            },
+        }
+    }
+}
+
+
+#[pyclass(name = "WorkflowHandle")]
+#[derive(Clone)]
+pub struct WrappedWorkflowHandle {
+    pub(crate) client: WrappedClient,
+    #[pyo3(get)]
+    pub(crate) execution: WrappedWorkflowExecution,
+}
+
+impl WrappedWorkflowHandle {
+    fn api_workflow_execution(&self) -> Option<ApiWorkflowExecution> {
+        Some(ApiWorkflowExecution {
+            workflow_id: self.execution.workflow_id.clone(),
+            run_id: self.execution.run_id.clone(),
+        })
+    }
+}
+
+#[pymethods]
+impl WrappedWorkflowHandle {
+    #[args(arguments = "Vec::new()")]
+    fn signal<'p>(&self, py: Python<'p>, signal_name: String, arguments: Vec<PyObject>) -> PyResult<&'p PyAny> {
+        let mut service = self.client.service.clone();
+        let request = SignalWorkflowExecutionRequest {
+            namespace: self.client.namespace.clone(),
+            workflow_execution: self.api_workflow_execution(),
+            signal_name,
+            input: wrapped_payloads_to_api_payloads(self.client.data_converter.encode_all(py, arguments)?),
+            identity: self.client.identity.clone(),
+            request_id: Uuid::new_v4().to_string(),
+            ..Default::default()
+        };
+
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            match service.signal_workflow_execution(request).await {
+                Err(status) => Err(tonic_status_to_client_error(status)),
+                Ok(_) => {
+                    Python::with_gil(|py| Ok(py.None()))
+                }
+            }
+        })
+    }
+
+    // Returns the query handler's response, None if the handler hasn't returned anything
+    #[args(arguments = "Vec::new()")]
+    fn query<'p>(&self, py: Python<'p>, query_type: String, arguments: Vec<PyObject>) -> PyResult<&'p PyAny> {
+        let mut service = self.client.service.clone();
+        let data_converter = self.client.data_converter.clone();
+        let request = QueryWorkflowRequest {
+            namespace: self.client.namespace.clone(),
+            execution: self.api_workflow_execution(),
+            query: Some(WorkflowQuery {
+                query_type,
+                query_args: wrapped_payloads_to_api_payloads(data_converter.encode_all(py, arguments)?),
+            }),
+            ..Default::default()
+        };
+
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            match service.query_workflow(request).await {
+                Err(status) => Err(tonic_status_to_client_error(status)),
+                Ok(response) => {
+                    let response = response.into_inner();
+                    if let Some(query_rejected) = response.query_rejected {
+                        let status = match WorkflowExecutionStatus::from_i32(query_rejected.status) {
+                            Some(status) => format!("{:?}", status),
+                            None => query_rejected.status.to_string(),
+                        };
+                        return Err(ClientError::new_err(format!(
+                            "Query was rejected, workflow status: {}",
+                            status
+                        )));
+                    }
+                    Python::with_gil(|py| {
+                        match api_payloads_to_wrapped_payloads(response.query_result).into_iter().next() {
+                            None => Ok(py.None()),
+                            Some(response) => data_converter.decode(py, response),
+                        }
+                    })
+                }
+            }
+        })
+    }
+
+    fn cancel<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let mut service = self.client.service.clone();
+        let request = RequestCancelWorkflowExecutionRequest {
+            namespace: self.client.namespace.clone(),
+            workflow_execution: self.api_workflow_execution(),
+            identity: self.client.identity.clone(),
+            request_id: Uuid::new_v4().to_string(),
+            ..Default::default()
+        };
+
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            match service.request_cancel_workflow_execution(request).await {
+                Err(status) => Err(tonic_status_to_client_error(status)),
+                Ok(_) => {
+                    Python::with_gil(|py| Ok(py.None()))
+                }
+            }
+        })
+    }
+
+    #[args(reason = "String::new()", details = "Vec::new()")]
+    fn terminate<'p>(&self, py: Python<'p>, reason: String, details: Vec<PyObject>) -> PyResult<&'p PyAny> {
+        let mut service = self.client.service.clone();
+        let request = TerminateWorkflowExecutionRequest {
+            namespace: self.client.namespace.clone(),
+            workflow_execution: self.api_workflow_execution(),
+            reason,
+            details: wrapped_payloads_to_api_payloads(self.client.data_converter.encode_all(py, details)?),
+            identity: self.client.identity.clone(),
+            ..Default::default()
+        };
+
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            match service.terminate_workflow_execution(request).await {
+                Err(status) => Err(tonic_status_to_client_error(status)),
+                Ok(_) => {
+                    Python::with_gil(|py| Ok(py.None()))
+                }
+            }
+        })
+    }
+}
+
+
+#[pyfunction(name = "connect")]
+#[args("*", data_converter = "None")]
+pub(crate) fn wrapped_connect(py: Python,
+                              opts: WrappedServerGatewayOptions,
+                              data_converter: Option<WrappedDataConverter>) -> PyResult<&PyAny> {
+    let gateway_opts = ServerGatewayOptions::try_from(opts)?;
+    let data_converter = match data_converter {
+        None => WrappedDataConverter::new_default(py)?,
+        Some(data_converter) => data_converter,
+    };
+    let current_loop = pyo3_asyncio::get_running_loop(py)?;
+    pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+        let service = WorkflowServiceClient::new(connect_channel(&gateway_opts).await?);
+        Python::with_gil(|py| {
+            let wrapped_client = WrappedClient {
+                service,
+                namespace: gateway_opts.namespace,
+                identity: gateway_opts.identity,
+                data_converter,
+            };
+            Ok(wrapped_client.into_py(py))
+        })
+    })
+}
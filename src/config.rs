@@ -0,0 +1,456 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::Duration as StdDuration;
+
+use pyo3::prelude::*;
+use pyo3_chrono;
+use temporal_sdk_core::Url;
+use toml::Value as TomlValue;
+
+use crate::WrappedCoreInitOptions;
+use crate::errors::{
+    ConfigError,
+    new_err_with_attributes,
+};
+use crate::logging::{
+    DEFAULT_LOG_LEVEL,
+    parse_log_level,
+};
+use crate::pollers::gateway::{
+    WrappedServerGatewayOptions,
+    WrappedTlsConfig,
+};
+use crate::tls_reload::default_tls_reload_interval;
+use crate::utils::std_duration_to_pyo3_chrono_duration;
+use crate::worker::config::WrappedWorkerConfig;
+
+
+// Read when load_config() doesn't get a path of its own
+const CONFIG_FILE_ENV_VAR: &str = "TEMPORAL_CONFIG_FILE";
+
+const DEFAULT_NAMESPACE: &str = "default";
+const DEFAULT_LONG_POLL_TIMEOUT: StdDuration = StdDuration::from_secs(60);
+
+// Every key that the TOML file can have, with the environment variable that overrides it being the key without its
+// section, e.g. "gateway.tls.client_cert" -> TEMPORAL_TLS_CLIENT_CERT
+const KEYS: &[&str] = &[
+    "gateway.target_url",
+    "gateway.namespace",
+    "gateway.identity",
+    "gateway.worker_binary_id",
+    "gateway.long_poll_timeout",
+    "gateway.tls.server_root_ca_cert",
+    "gateway.tls.domain",
+    "gateway.tls.client_cert",
+    "gateway.tls.client_private_key",
+    "core.max_cached_workflows",
+    "core.log_level",
+    "core.tls_reload_interval",
+    "worker.task_queue",
+    "worker.max_outstanding_workflow_tasks",
+    "worker.max_outstanding_activities",
+    "worker.max_concurrent_wft_polls",
+    "worker.nonsticky_to_sticky_poll_ratio",
+    "worker.max_concurrent_at_polls",
+    "worker.no_remote_activities",
+    "worker.sticky_queue_schedule_to_start_timeout",
+];
+
+
+fn env_var_name(key: &str) -> String {
+    let (_section, name) = key.split_once('.').unwrap_or(("", key));
+    format!("TEMPORAL_{}", name.replace('.', "_").to_uppercase())
+}
+
+
+fn config_error(py: Python, key: Option<&str>, source: Option<&str>, message: String) -> PyErr {
+    let location = match (key, source) {
+        (Some(key), Some(source)) => format!("{} (from {})", key, source),
+        (Some(key), None) => String::from(key),
+        (None, Some(source)) => String::from(source),
+        (None, None) => String::from("config"),
+    };
+    new_err_with_attributes::<ConfigError>(py, format!("{}: {}", location, message), vec![
+        ("key", key.into_py(py)),
+        ("source", source.into_py(py)),
+    ])
+}
+
+
+// StdDuration::from_secs_f64() panics on anything that isn't a finite number of seconds that fits in a u64
+fn seconds_to_std_duration(seconds: f64) -> Result<StdDuration, String> {
+    if !seconds.is_finite() || seconds < 0.0 || seconds >= u64::MAX as f64 {
+        return Err(format!("{} seconds is not a valid duration", seconds));
+    }
+    Ok(StdDuration::from_secs_f64(seconds))
+}
+
+
+// "1h30m", "10s", "250ms", "1.5d"
+pub(crate) fn parse_duration(value: &str) -> Result<StdDuration, String> {
+    let invalid = || format!("\"{}\" is not a duration such as \"10s\", \"5m\" or \"1h30m\"", value);
+
+    let mut total = 0f64;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+    while !rest.is_empty() {
+        let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).ok_or_else(invalid)?;
+        let number: f64 = rest[..number_len].parse().map_err(|_| invalid())?;
+        rest = &rest[number_len..];
+        let unit_len = rest.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(rest.len());
+        let seconds_per_unit = match &rest[..unit_len] {
+            "ms" => 0.001,
+            "s" => 1.0,
+            "m" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            _ => return Err(invalid()),
+        };
+        total += number * seconds_per_unit;
+        rest = &rest[unit_len..];
+    }
+    seconds_to_std_duration(total).map_err(|_| format!("\"{}\" is too long a duration", value))
+}
+
+
+enum RawValue {
+    Toml(TomlValue),
+    Env(String),
+}
+
+
+struct Setting {
+    raw: RawValue,
+    // Config file's path or the environment variable's name
+    source: String,
+}
+
+
+struct Settings {
+    values: HashMap<&'static str, Setting>,
+}
+
+impl Settings {
+    fn load(py: Python, path: Option<&str>, environ: &HashMap<String, String>) -> PyResult<Self> {
+        let mut values = HashMap::new();
+
+        if let Some(path) = path {
+            let contents = fs::read_to_string(path).map_err(|err| {
+                config_error(py, None, Some(path), format!("unable to read config file: {}", err))
+            })?;
+            let table = contents.parse::<TomlValue>().map_err(|err| {
+                config_error(py, None, Some(path), format!("invalid TOML: {}", err))
+            })?;
+            let mut flattened = Vec::new();
+            flatten_toml(String::new(), table, &mut flattened);
+            for (key, value) in flattened {
+                let known_key = match KEYS.iter().find(|known| **known == key) {
+                    None => return Err(config_error(py, Some(&key), Some(path), String::from("unknown key"))),
+                    Some(known_key) => *known_key,
+                };
+                values.insert(known_key, Setting {
+                    raw: RawValue::Toml(value),
+                    source: String::from(path),
+                });
+            }
+        }
+
+        for key in KEYS {
+            let env_var = env_var_name(key);
+            if let Some(value) = environ.get(&env_var) {
+                values.insert(*key, Setting {
+                    raw: RawValue::Env(value.clone()),
+                    source: env_var,
+                });
+            }
+        }
+
+        Ok(Settings { values })
+    }
+
+    fn has_section(&self, section: &str) -> bool {
+        self.values.keys().any(|key| key.starts_with(&format!("{}.", section)))
+    }
+
+    // Value of the key converted by one of the parsers, the parser's error message pointing at wherever it came from
+    fn get<T>(&self,
+              py: Python,
+              key: &'static str,
+              parse: fn(&RawValue) -> Result<T, String>) -> PyResult<Option<T>> {
+        match self.values.get(key) {
+            None => Ok(None),
+            Some(setting) => match parse(&setting.raw) {
+                Ok(value) => Ok(Some(value)),
+                Err(message) => Err(config_error(py, Some(key), Some(&setting.source), message)),
+            },
+        }
+    }
+
+    fn require<T>(&self,
+                  py: Python,
+                  key: &'static str,
+                  parse: fn(&RawValue) -> Result<T, String>) -> PyResult<T> {
+        match self.get(py, key, parse)? {
+            Some(value) => Ok(value),
+            None => Err(config_error(py, Some(key), None, format!(
+                "missing, set it in the config file or the {} environment variable",
+                env_var_name(key)
+            ))),
+        }
+    }
+}
+
+
+fn flatten_toml(prefix: String, value: TomlValue, flattened: &mut Vec<(String, TomlValue)>) {
+    match value {
+        TomlValue::Table(table) => {
+            for (name, value) in table {
+                let key = if prefix.is_empty() { name } else { format!("{}.{}", prefix, name) };
+                flatten_toml(key, value, flattened);
+            }
+        }
+        value => flattened.push((prefix, value)),
+    }
+}
+
+
+fn parse_string(raw: &RawValue) -> Result<String, String> {
+    match raw {
+        RawValue::Toml(TomlValue::String(value)) | RawValue::Env(value) => Ok(value.clone()),
+        RawValue::Toml(value) => Err(format!("expected a string, got {}", value)),
+    }
+}
+
+
+fn parse_url(raw: &RawValue) -> Result<String, String> {
+    let url = parse_string(raw)?;
+    match Url::parse(&url) {
+        Ok(_) => Ok(url),
+        Err(err) => Err(format!("\"{}\" is not a valid URL: {}", url, err)),
+    }
+}
+
+
+fn parse_log_level_name(raw: &RawValue) -> Result<String, String> {
+    let log_level = parse_string(raw)?;
+    match parse_log_level(&log_level) {
+        Ok(_) => Ok(log_level),
+        Err(err) => Err(err.to_string()),
+    }
+}
+
+
+fn parse_usize(raw: &RawValue) -> Result<usize, String> {
+    match raw {
+        RawValue::Toml(TomlValue::Integer(value)) if *value >= 0 => Ok(*value as usize),
+        RawValue::Env(value) => value.trim().parse().map_err(|_| {
+            format!("expected a non-negative integer, got \"{}\"", value)
+        }),
+        RawValue::Toml(value) => Err(format!("expected a non-negative integer, got {}", value)),
+    }
+}
+
+
+fn parse_f32(raw: &RawValue) -> Result<f32, String> {
+    match raw {
+        RawValue::Toml(TomlValue::Float(value)) => Ok(*value as f32),
+        RawValue::Toml(TomlValue::Integer(value)) => Ok(*value as f32),
+        RawValue::Env(value) => value.trim().parse().map_err(|_| format!("expected a number, got \"{}\"", value)),
+        RawValue::Toml(value) => Err(format!("expected a number, got {}", value)),
+    }
+}
+
+
+fn parse_bool(raw: &RawValue) -> Result<bool, String> {
+    match raw {
+        RawValue::Toml(TomlValue::Boolean(value)) => Ok(*value),
+        RawValue::Env(value) => match value.trim().to_lowercase().as_str() {
+            "true" | "yes" | "1" => Ok(true),
+            "false" | "no" | "0" => Ok(false),
+            _ => Err(format!("expected true or false, got \"{}\"", value)),
+        },
+        RawValue::Toml(value) => Err(format!("expected true or false, got {}", value)),
+    }
+}
+
+
+// Plain numbers are seconds
+fn parse_std_duration(raw: &RawValue) -> Result<StdDuration, String> {
+    match raw {
+        RawValue::Toml(TomlValue::String(value)) | RawValue::Env(value) => match value.trim().parse::<f64>() {
+            Ok(seconds) if seconds >= 0.0 => seconds_to_std_duration(seconds),
+            _ => parse_duration(value),
+        },
+        RawValue::Toml(TomlValue::Integer(seconds)) if *seconds >= 0 => Ok(StdDuration::from_secs(*seconds as u64)),
+        RawValue::Toml(TomlValue::Float(seconds)) if *seconds >= 0.0 => seconds_to_std_duration(*seconds),
+        RawValue::Toml(value) => Err(format!("expected a duration such as \"10s\", got {}", value)),
+    }
+}
+
+
+fn duration(py: Python, key: &'static str, duration: StdDuration) -> PyResult<pyo3_chrono::Duration> {
+    std_duration_to_pyo3_chrono_duration(duration).map_err(|err| {
+        config_error(py, Some(key), None, err.to_string())
+    })
+}
+
+
+// Same "<pid>@<hostname>" identity that Temporal's other SDKs default to
+fn default_identity(py: Python) -> PyResult<String> {
+    let hostname: String = py.import("socket")?.call_method0("gethostname")?.extract()?;
+    Ok(format!("{}@{}", std::process::id(), hostname))
+}
+
+
+fn load_tls_config(py: Python, settings: &Settings) -> PyResult<Option<WrappedTlsConfig>> {
+    if !settings.has_section("gateway.tls") {
+        return Ok(None);
+    }
+    // Files get read and validated right away, errors naming the key and the file
+    Ok(Some(WrappedTlsConfig::load(
+        py,
+        "gateway.tls.",
+        settings.get(py, "gateway.tls.server_root_ca_cert", parse_string)?.as_deref(),
+        settings.get(py, "gateway.tls.domain", parse_string)?,
+        settings.get(py, "gateway.tls.client_cert", parse_string)?.as_deref(),
+        settings.get(py, "gateway.tls.client_private_key", parse_string)?.as_deref(),
+    )?))
+}
+
+
+fn load_gateway_opts(py: Python, settings: &Settings) -> PyResult<WrappedServerGatewayOptions> {
+    let long_poll_timeout = settings.get(py, "gateway.long_poll_timeout", parse_std_duration)?
+        .unwrap_or(DEFAULT_LONG_POLL_TIMEOUT);
+    let identity = match settings.get(py, "gateway.identity", parse_string)? {
+        Some(identity) => identity,
+        None => default_identity(py)?,
+    };
+    Ok(WrappedServerGatewayOptions::new(
+        settings.require(py, "gateway.target_url", parse_url)?,
+        settings.get(py, "gateway.namespace", parse_string)?.unwrap_or_else(|| String::from(DEFAULT_NAMESPACE)),
+        identity,
+        settings.get(py, "gateway.worker_binary_id", parse_string)?.unwrap_or_default(),
+        duration(py, "gateway.long_poll_timeout", long_poll_timeout)?,
+        load_tls_config(py, settings)?,
+    ))
+}
+
+
+fn load_worker_config(py: Python, settings: &Settings) -> PyResult<Option<WrappedWorkerConfig>> {
+    if !settings.has_section("worker") {
+        return Ok(None);
+    }
+    let sticky_queue_schedule_to_start_timeout = match settings.get(
+        py, "worker.sticky_queue_schedule_to_start_timeout", parse_std_duration,
+    )? {
+        None => None,
+        Some(timeout) => Some(duration(py, "worker.sticky_queue_schedule_to_start_timeout", timeout)?),
+    };
+    // Keys that aren't set get WorkerConfig's defaults
+    Ok(Some(WrappedWorkerConfig::new(
+        py,
+        settings.require(py, "worker.task_queue", parse_string)?,
+        settings.get(py, "worker.max_outstanding_workflow_tasks", parse_usize)?,
+        settings.get(py, "worker.max_outstanding_activities", parse_usize)?,
+        settings.get(py, "worker.max_concurrent_wft_polls", parse_usize)?,
+        settings.get(py, "worker.nonsticky_to_sticky_poll_ratio", parse_f32)?,
+        settings.get(py, "worker.max_concurrent_at_polls", parse_usize)?,
+        settings.get(py, "worker.no_remote_activities", parse_bool)?,
+        sticky_queue_schedule_to_start_timeout,
+    )?))
+}
+
+
+#[pyclass(name = "Config")]
+#[derive(Clone)]
+pub(crate) struct WrappedConfig {
+    #[pyo3(get)]
+    gateway_opts: WrappedServerGatewayOptions,
+    #[pyo3(get)]
+    core_init_opts: WrappedCoreInitOptions,
+    // None unless the config sets any of the "worker" keys
+    #[pyo3(get)]
+    worker_config: Option<WrappedWorkerConfig>,
+}
+
+
+// Environment variables override the file's values; "environ" defaults to the process' environment
+#[pyfunction(name = "load_config")]
+#[args(path = "None", "*", environ = "None")]
+pub(crate) fn wrapped_load_config(py: Python,
+                                  path: Option<String>,
+                                  environ: Option<HashMap<String, String>>) -> PyResult<WrappedConfig> {
+    let environ = environ.unwrap_or_else(|| std::env::vars().collect());
+    let path = path.or_else(|| environ.get(CONFIG_FILE_ENV_VAR).cloned());
+    let settings = Settings::load(py, path.as_deref(), &environ)?;
+
+    let gateway_opts = load_gateway_opts(py, &settings)?;
+    // Zero turns reloading the TLS files off
+    let tls_reload_interval = match settings.get(py, "core.tls_reload_interval", parse_std_duration)? {
+        None => default_tls_reload_interval(),
+        Some(interval) if interval.as_nanos() == 0 => None,
+        Some(interval) => Some(duration(py, "core.tls_reload_interval", interval)?),
+    };
+    let core_init_opts = WrappedCoreInitOptions::new(
+        gateway_opts.clone(),
+        settings.require(py, "core.max_cached_workflows", parse_usize)?,
+        settings.get(py, "core.log_level", parse_log_level_name)?.unwrap_or_else(|| String::from(DEFAULT_LOG_LEVEL)),
+        tls_reload_interval,
+    )?;
+
+    Ok(WrappedConfig {
+        gateway_opts,
+        core_init_opts,
+        worker_config: load_worker_config(py, &settings)?,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("10s"), Ok(StdDuration::from_secs(10)));
+        assert_eq!(parse_duration("5m"), Ok(StdDuration::from_secs(300)));
+        assert_eq!(parse_duration("1h30m"), Ok(StdDuration::from_secs(5400)));
+        assert_eq!(parse_duration("250ms"), Ok(StdDuration::from_millis(250)));
+        assert_eq!(parse_duration("1.5d"), Ok(StdDuration::from_secs(129600)));
+        assert_eq!(parse_duration(" 2h "), Ok(StdDuration::from_secs(7200)));
+    }
+
+    #[test]
+    fn rejects_invalid_durations() {
+        for value in ["", "10", "5x", "h", "-5s", "inf", "nan", "1h 30m"] {
+            assert!(parse_duration(value).is_err(), "{:?} should be rejected", value);
+        }
+    }
+
+    #[test]
+    fn rejects_durations_too_long_for_std_duration() {
+        assert!(parse_duration("99999999999999999999d").is_err());
+    }
+
+    #[test]
+    fn parses_plain_numbers_as_seconds() {
+        assert_eq!(parse_std_duration(&RawValue::Env(String::from("90"))), Ok(StdDuration::from_secs(90)));
+        assert_eq!(parse_std_duration(&RawValue::Env(String::from("0.5"))), Ok(StdDuration::from_millis(500)));
+        assert_eq!(parse_std_duration(&RawValue::Toml(TomlValue::Integer(30))), Ok(StdDuration::from_secs(30)));
+        assert_eq!(parse_std_duration(&RawValue::Toml(TomlValue::Float(1.5))), Ok(StdDuration::from_millis(1500)));
+        assert_eq!(parse_std_duration(&RawValue::Env(String::from("1m"))), Ok(StdDuration::from_secs(60)));
+    }
+
+    #[test]
+    fn rejects_non_finite_and_huge_seconds() {
+        for value in ["inf", "Infinity", "1e30", "-5"] {
+            assert!(parse_std_duration(&RawValue::Env(String::from(value))).is_err(), "{:?} should be rejected", value);
+        }
+        assert!(parse_std_duration(&RawValue::Toml(TomlValue::Float(f64::INFINITY))).is_err());
+        assert!(parse_std_duration(&RawValue::Toml(TomlValue::Float(f64::NAN))).is_err());
+        assert!(parse_std_duration(&RawValue::Toml(TomlValue::Float(1e30))).is_err());
+        assert!(parse_std_duration(&RawValue::Toml(TomlValue::Integer(-1))).is_err());
+    }
+}
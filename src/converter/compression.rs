@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use flate2::{
+    Compression,
+    read::GzDecoder,
+    write::GzEncoder,
+};
+use prost::Message;
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use temporal_sdk_core::protos::coresdk::common::Payload;
+
+use crate::converter::payload_converters::METADATA_ENCODING;
+use crate::protos::coresdk::common::WrappedPayload;
+
+
+const ENCODING_ZSTD: &[u8] = b"binary/zstd";
+const ENCODING_GZIP: &[u8] = b"binary/gzip";
+
+// Smaller payloads don't get any smaller when compressed
+const DEFAULT_THRESHOLD: usize = 4096;
+
+// Way more than the server lets a payload be, while keeping a small payload from history from decompressing into
+// gigabytes
+const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 64 * 1024 * 1024;
+
+
+fn io_error_to_py_err(err: std::io::Error) -> PyErr {
+    PyValueError::new_err(format!(
+        "{}",
+        err.to_string()
+    ))
+}
+
+
+#[derive(Clone, Copy)]
+enum CompressionAlgorithm {
+    Zstd,
+    Gzip,
+}
+
+impl CompressionAlgorithm {
+    fn encoding(&self) -> &'static [u8] {
+        match self {
+            CompressionAlgorithm::Zstd => ENCODING_ZSTD,
+            CompressionAlgorithm::Gzip => ENCODING_GZIP,
+        }
+    }
+
+    fn compress(&self, data: &[u8], level: Option<i32>) -> PyResult<Vec<u8>> {
+        match self {
+            CompressionAlgorithm::Zstd => {
+                zstd::encode_all(data, level.unwrap_or(zstd::DEFAULT_COMPRESSION_LEVEL)).map_err(io_error_to_py_err)
+            }
+            CompressionAlgorithm::Gzip => {
+                let compression = match level {
+                    None => Compression::default(),
+                    Some(level) => Compression::new(level as u32),
+                };
+                let mut encoder = GzEncoder::new(Vec::new(), compression);
+                encoder.write_all(data).map_err(io_error_to_py_err)?;
+                encoder.finish().map_err(io_error_to_py_err)
+            }
+        }
+    }
+
+    fn decompress(&self, data: &[u8], max_size: usize) -> PyResult<Vec<u8>> {
+        let decoder: Box<dyn Read + '_> = match self {
+            CompressionAlgorithm::Zstd => Box::new(zstd::stream::read::Decoder::new(data).map_err(io_error_to_py_err)?),
+            CompressionAlgorithm::Gzip => Box::new(GzDecoder::new(data)),
+        };
+        // One byte more than allowed is enough to tell that the payload is too big
+        let mut decompressed = Vec::new();
+        decoder.take(max_size as u64 + 1).read_to_end(&mut decompressed).map_err(io_error_to_py_err)?;
+        if decompressed.len() > max_size {
+            return Err(PyValueError::new_err(format!(
+                "Payload decompresses to more than the maximum of {} bytes",
+                max_size
+            )));
+        }
+        Ok(decompressed)
+    }
+}
+
+
+// Compresses the whole encoded payload, metadata included, so that its original encoding survives the round trip;
+// payloads compressed with either algorithm get decompressed regardless of which one the codec is set to
+#[pyclass(name = "CompressionCodec")]
+#[derive(Clone)]
+pub struct WrappedCompressionCodec {
+    algorithm: CompressionAlgorithm,
+    #[pyo3(get)]
+    threshold: usize,
+    #[pyo3(get)]
+    level: Option<i32>,
+    #[pyo3(get)]
+    max_decompressed_size: usize,
+}
+
+#[pymethods]
+impl WrappedCompressionCodec {
+    #[new]
+    #[args(
+        algorithm = "\"zstd\"",
+        "*",
+        threshold = "DEFAULT_THRESHOLD",
+        level = "None",
+        max_decompressed_size = "DEFAULT_MAX_DECOMPRESSED_SIZE"
+    )]
+    fn new(algorithm: &str, threshold: usize, level: Option<i32>, max_decompressed_size: usize) -> PyResult<Self> {
+        let algorithm = match algorithm {
+            "zstd" => CompressionAlgorithm::Zstd,
+            "gzip" => CompressionAlgorithm::Gzip,
+            _ => return Err(PyValueError::new_err(format!(
+                "Unsupported compression algorithm {}, expected either \"zstd\" or \"gzip\"",
+                algorithm
+            ))),
+        };
+        Ok(WrappedCompressionCodec {
+            algorithm,
+            threshold,
+            level,
+            max_decompressed_size,
+        })
+    }
+
+    #[getter]
+    fn get_algorithm(&self) -> &'static str {
+        match self.algorithm {
+            CompressionAlgorithm::Zstd => "zstd",
+            CompressionAlgorithm::Gzip => "gzip",
+        }
+    }
+
+    fn encode(&self, payload: WrappedPayload) -> PyResult<WrappedPayload> {
+        if payload.data.len() < self.threshold {
+            return Ok(payload);
+        }
+
+        let compressed = self.algorithm.compress(&Payload::from(&payload).encode_to_vec(), self.level)?;
+        if compressed.len() >= payload.data.len() {
+            return Ok(payload);
+        }
+
+        let mut metadata = HashMap::new();
+        metadata.insert(String::from(METADATA_ENCODING), self.algorithm.encoding().to_vec());
+        Ok(WrappedPayload {
+            metadata,
+            data: compressed,
+        })
+    }
+
+    fn decode(&self, payload: WrappedPayload) -> PyResult<WrappedPayload> {
+        let algorithm = match payload.metadata.get(METADATA_ENCODING).map(|encoding| encoding.as_slice()) {
+            Some(ENCODING_ZSTD) => CompressionAlgorithm::Zstd,
+            Some(ENCODING_GZIP) => CompressionAlgorithm::Gzip,
+            _ => return Ok(payload),
+        };
+
+        let decompressed = algorithm.decompress(&payload.data, self.max_decompressed_size)?;
+        match Payload::decode(decompressed.as_slice()) {
+            Ok(original_payload) => Ok(WrappedPayload::from(original_payload)),
+            Err(err) => Err(PyValueError::new_err(format!(
+                "Decompressed payload is not valid: {}",
+                err.to_string()
+            ))),
+        }
+    }
+}
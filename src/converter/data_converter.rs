@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyTuple;
+
+use crate::converter::payload_converters::{
+    METADATA_ENCODING,
+    default_payload_converters,
+};
+use crate::protos::coresdk::common::WrappedPayload;
+
+
+// Payload converters' "encoding" might be either bytes or str
+fn converter_encoding(py: Python, converter: &PyObject) -> PyResult<Vec<u8>> {
+    let encoding = converter.getattr(py, "encoding")?;
+    match encoding.extract::<String>(py) {
+        Ok(encoding) => Ok(encoding.into_bytes()),
+        Err(_) => encoding.extract::<Vec<u8>>(py),
+    }
+}
+
+
+// Chain of payload converters: values get encoded by the first converter that accepts them, payloads get decoded by
+// the converter for their "encoding" metadata; encoded payloads then go through the codecs in order on their way out
+// and in reverse order on their way in
+#[pyclass(name = "DataConverter")]
+#[derive(Clone)]
+pub struct WrappedDataConverter {
+    converters: Vec<PyObject>,
+    converters_by_encoding: HashMap<Vec<u8>, PyObject>,
+    codecs: Vec<PyObject>,
+}
+
+impl WrappedDataConverter {
+    pub(crate) fn new_default(py: Python) -> PyResult<Self> {
+        WrappedDataConverter::new(py, None, None)
+    }
+
+    fn encode_with_codecs(&self, py: Python, mut payload: WrappedPayload) -> PyResult<WrappedPayload> {
+        for codec in &self.codecs {
+            payload = codec.call_method1(py, "encode", (payload,))?.extract(py)?;
+        }
+        Ok(payload)
+    }
+
+    fn decode_with_codecs(&self, py: Python, mut payload: WrappedPayload) -> PyResult<WrappedPayload> {
+        for codec in self.codecs.iter().rev() {
+            payload = codec.call_method1(py, "decode", (payload,))?.extract(py)?;
+        }
+        Ok(payload)
+    }
+
+    // Payloads that the caller has built itself skip the converters but still go through the codecs
+    pub(crate) fn encode(&self, py: Python, value: &PyAny) -> PyResult<WrappedPayload> {
+        if let Ok(payload) = value.extract::<WrappedPayload>() {
+            return self.encode_with_codecs(py, payload);
+        }
+        for converter in &self.converters {
+            let payload = converter.call_method1(py, "to_payload", (value,))?;
+            if !payload.is_none(py) {
+                return self.encode_with_codecs(py, payload.extract(py)?);
+            }
+        }
+        Err(PyValueError::new_err(format!(
+            "None of the payload converters can convert value of type {}",
+            value.get_type().name()?
+        )))
+    }
+
+    pub(crate) fn encode_all(&self, py: Python, values: Vec<PyObject>) -> PyResult<Vec<WrappedPayload>> {
+        values.iter().map(|value| self.encode(py, value.as_ref(py))).collect()
+    }
+
+    pub(crate) fn decode(&self, py: Python, payload: WrappedPayload) -> PyResult<PyObject> {
+        let payload = self.decode_with_codecs(py, payload)?;
+        let encoding = match payload.metadata.get(METADATA_ENCODING) {
+            None => return Err(PyValueError::new_err("Payload doesn't have its encoding set")),
+            Some(encoding) => encoding.clone(),
+        };
+        match self.converters_by_encoding.get(&encoding) {
+            None => Err(PyValueError::new_err(format!(
+                "No payload converter for encoding {}",
+                String::from_utf8_lossy(&encoding)
+            ))),
+            Some(converter) => converter.call_method1(py, "from_payload", (payload,)),
+        }
+    }
+
+    pub(crate) fn decode_all(&self, py: Python, payloads: Vec<WrappedPayload>) -> PyResult<Vec<PyObject>> {
+        payloads.into_iter().map(|payload| self.decode(py, payload)).collect()
+    }
+
+    pub(crate) fn decode_to_tuple<'p>(&self, py: Python<'p>, payloads: Vec<WrappedPayload>) -> PyResult<&'p PyTuple> {
+        Ok(PyTuple::new(py, self.decode_all(py, payloads)?))
+    }
+}
+
+#[pymethods]
+impl WrappedDataConverter {
+    // Custom converters are objects with an "encoding", a to_payload() that returns None for values that it doesn't
+    // handle, and a from_payload(); custom codecs are objects with encode() and decode() that both take and return a
+    // Payload, passing through the payloads that they don't handle
+    #[new]
+    #[args(converters = "None", codecs = "None")]
+    fn new(py: Python, converters: Option<Vec<PyObject>>, codecs: Option<Vec<PyObject>>) -> PyResult<Self> {
+        let converters = match converters {
+            None => default_payload_converters(py)?,
+            Some(converters) => converters,
+        };
+        let mut converters_by_encoding = HashMap::new();
+        for converter in &converters {
+            converters_by_encoding.insert(converter_encoding(py, converter)?, converter.clone_ref(py));
+        }
+        Ok(WrappedDataConverter {
+            converters,
+            converters_by_encoding,
+            codecs: codecs.unwrap_or_default(),
+        })
+    }
+
+    #[getter]
+    fn get_converters(&self, py: Python) -> Vec<PyObject> {
+        self.converters.iter().map(|converter| converter.clone_ref(py)).collect()
+    }
+
+    #[getter]
+    fn get_codecs(&self, py: Python) -> Vec<PyObject> {
+        self.codecs.iter().map(|codec| codec.clone_ref(py)).collect()
+    }
+
+    fn to_payload(&self, py: Python, value: &PyAny) -> PyResult<WrappedPayload> {
+        self.encode(py, value)
+    }
+
+    fn to_payloads(&self, py: Python, values: Vec<PyObject>) -> PyResult<Vec<WrappedPayload>> {
+        self.encode_all(py, values)
+    }
+
+    fn from_payload(&self, py: Python, payload: WrappedPayload) -> PyResult<PyObject> {
+        self.decode(py, payload)
+    }
+
+    fn from_payloads(&self, py: Python, payloads: Vec<WrappedPayload>) -> PyResult<Vec<PyObject>> {
+        self.decode_all(py, payloads)
+    }
+}
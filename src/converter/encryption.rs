@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use aes_gcm::{
+    Aes256Gcm,
+    Key,
+    Nonce,
+    aead::{Aead, NewAead},
+};
+use prost::Message;
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyDict;
+use temporal_sdk_core::protos::coresdk::common::Payload;
+
+use crate::converter::payload_converters::METADATA_ENCODING;
+use crate::errors::{
+    UnknownEncryptionKeyError,
+    new_err_with_attributes,
+};
+use crate::protos::coresdk::common::WrappedPayload;
+
+
+const METADATA_ENCRYPTION_KEY_ID: &str = "encryption-key-id";
+
+const ENCODING_ENCRYPTED: &[u8] = b"binary/encrypted";
+
+const KEY_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+
+
+// Encrypts the whole encoded payload, metadata included, with AES-256-GCM; the encrypted payload's data is the random
+// nonce followed by the ciphertext, and the id of the key that it has been encrypted with goes into its metadata so
+// that payloads encrypted before a key rotation can still be decrypted afterwards
+#[pyclass(name = "EncryptionCodec")]
+#[derive(Clone)]
+pub struct WrappedEncryptionCodec {
+    #[pyo3(get)]
+    key_id: String,
+    #[pyo3(get)]
+    key_provider: PyObject,
+}
+
+impl WrappedEncryptionCodec {
+    // Key providers are either dicts of key ids to keys or callables that take a key id and return its key, or None
+    // if they don't know about it
+    fn key(&self, py: Python, key_id: &str) -> PyResult<Aes256Gcm> {
+        let key = match self.key_provider.as_ref(py).downcast::<PyDict>() {
+            Ok(keys) => keys.get_item(key_id).map(|key| key.into()),
+            Err(_) => {
+                let key = self.key_provider.call1(py, (key_id,))?;
+                if key.is_none(py) { None } else { Some(key) }
+            }
+        };
+        let key: Vec<u8> = match key {
+            None => return Err(new_err_with_attributes::<UnknownEncryptionKeyError>(py, format!(
+                "Encryption key {} is unknown to the key provider",
+                key_id
+            ), vec![
+                ("key_id", key_id.into_py(py)),
+            ])),
+            Some(key) => key.extract(py)?,
+        };
+        if key.len() != KEY_LENGTH {
+            return Err(PyValueError::new_err(format!(
+                "Encryption key {} is {} bytes long, expected {} bytes",
+                key_id,
+                key.len(),
+                KEY_LENGTH
+            )));
+        }
+        Ok(Aes256Gcm::new(Key::from_slice(&key)))
+    }
+}
+
+#[pymethods]
+impl WrappedEncryptionCodec {
+    // Payloads get encrypted with the key_id key, so rotating the key means passing a new key_id along with a key
+    // provider that still knows about the old ones
+    #[new]
+    fn new(key_id: String, key_provider: PyObject) -> Self {
+        WrappedEncryptionCodec {
+            key_id,
+            key_provider,
+        }
+    }
+
+    fn encode(&self, py: Python, payload: WrappedPayload) -> PyResult<WrappedPayload> {
+        let cipher = self.key(py, &self.key_id)?;
+        let nonce: [u8; NONCE_LENGTH] = rand::random();
+        let ciphertext = match cipher.encrypt(Nonce::from_slice(&nonce), Payload::from(&payload).encode_to_vec().as_slice()) {
+            Ok(ciphertext) => ciphertext,
+            Err(_) => return Err(PyValueError::new_err("Unable to encrypt payload")),
+        };
+
+        let mut data = nonce.to_vec();
+        data.extend(ciphertext);
+        let mut metadata = HashMap::new();
+        metadata.insert(String::from(METADATA_ENCODING), ENCODING_ENCRYPTED.to_vec());
+        metadata.insert(String::from(METADATA_ENCRYPTION_KEY_ID), self.key_id.clone().into_bytes());
+        Ok(WrappedPayload {
+            metadata,
+            data,
+        })
+    }
+
+    fn decode(&self, py: Python, payload: WrappedPayload) -> PyResult<WrappedPayload> {
+        if payload.metadata.get(METADATA_ENCODING).map(|encoding| encoding.as_slice()) != Some(ENCODING_ENCRYPTED) {
+            return Ok(payload);
+        }
+
+        let key_id = match payload.metadata.get(METADATA_ENCRYPTION_KEY_ID) {
+            None => return Err(PyValueError::new_err("Encrypted payload doesn't have its encryption key id set")),
+            Some(key_id) => String::from_utf8_lossy(key_id).to_string(),
+        };
+        if payload.data.len() < NONCE_LENGTH {
+            return Err(PyValueError::new_err("Encrypted payload is too short to contain a nonce"));
+        }
+
+        let cipher = self.key(py, &key_id)?;
+        let (nonce, ciphertext) = payload.data.split_at(NONCE_LENGTH);
+        let decrypted = match cipher.decrypt(Nonce::from_slice(nonce), ciphertext) {
+            Ok(decrypted) => decrypted,
+            Err(_) => return Err(PyValueError::new_err(format!(
+                "Unable to decrypt payload with encryption key {}, either the key is wrong or the payload has been \
+                tampered with",
+                key_id
+            ))),
+        };
+        match Payload::decode(decrypted.as_slice()) {
+            Ok(original_payload) => Ok(WrappedPayload::from(original_payload)),
+            Err(err) => Err(PyValueError::new_err(format!(
+                "Decrypted payload is not valid: {}",
+                err.to_string()
+            ))),
+        }
+    }
+}
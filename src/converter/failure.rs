@@ -0,0 +1,105 @@
+use pyo3::prelude::*;
+use pyo3::types::{
+    IntoPyDict,
+    PyType,
+};
+use temporal_sdk_core::protos::coresdk::common::UserCodeFailure;
+
+use crate::errors::{
+    ApplicationError,
+    NonRetryableError,
+};
+use crate::protos::coresdk::common::WrappedUserCodeFailure;
+
+
+const FAILURE_SOURCE: &str = "pytemporalio";
+
+// Exceptions might end up being their own causes
+const MAX_CAUSE_DEPTH: usize = 32;
+
+
+fn is_instance(py: Python, value: &PyAny, class: &PyType) -> PyResult<bool> {
+    py.import("builtins")?.call_method1("isinstance", (value, class))?.is_true()
+}
+
+
+// ApplicationErrors that have been rebuilt from a failure keep the original failure's type
+fn exception_type(py: Python, exception: &PyAny) -> PyResult<String> {
+    if is_instance(py, exception, py.get_type::<ApplicationError>())? {
+        let failure_type = exception.getattr("type")?;
+        if !failure_type.is_none() {
+            return failure_type.extract();
+        }
+    }
+    exception.get_type().name().map(String::from)
+}
+
+
+fn exception_to_failure(py: Python, exception: &PyAny, depth: usize) -> PyResult<WrappedUserCodeFailure> {
+    let stack_trace: Vec<String> = py.import("traceback")?.call_method(
+        "format_exception",
+        (exception.get_type(), exception, exception.getattr("__traceback__")?),
+        Some([("chain", false)].into_py_dict(py)),
+    )?.extract()?;
+    let non_retryable = match exception.getattr("non_retryable") {
+        Ok(non_retryable) => non_retryable.is_true()?,
+        Err(_) => false,
+    };
+    let cause = exception.getattr("__cause__")?;
+    Ok(WrappedUserCodeFailure {
+        message: exception.str()?.to_string(),
+        r#type: exception_type(py, exception)?,
+        source: FAILURE_SOURCE.to_string(),
+        stack_trace: stack_trace.concat(),
+        non_retryable: non_retryable || is_instance(py, exception, py.get_type::<NonRetryableError>())?,
+        cause: if cause.is_none() || depth >= MAX_CAUSE_DEPTH {
+            None
+        } else {
+            Some(Box::new(exception_to_failure(py, cause, depth + 1)?))
+        },
+    })
+}
+
+
+// Falls back to a failure with just the message if the exception itself is too broken to be inspected
+pub(crate) fn py_err_to_user_code_failure(err: &PyErr) -> UserCodeFailure {
+    Python::with_gil(|py| {
+        match exception_to_failure(py, err.instance(py), 0) {
+            Ok(failure) => UserCodeFailure::from(failure),
+            Err(_) => UserCodeFailure {
+                message: err.pvalue(py).to_string(),
+                r#type: err.ptype(py).name().unwrap_or("Exception").to_string(),
+                source: FAILURE_SOURCE.to_string(),
+                stack_trace: String::new(),
+                non_retryable: false,
+                cause: None,
+            },
+        }
+    })
+}
+
+
+// ApplicationError with the failure's details as its attributes and its cause chain rebuilt from the failure's causes
+pub(crate) fn user_code_failure_to_exception(py: Python, failure: WrappedUserCodeFailure) -> PyResult<PyObject> {
+    let exception: PyObject = ApplicationError::new_err(failure.message).instance(py).into();
+    exception.setattr(py, "type", failure.r#type)?;
+    exception.setattr(py, "source", failure.source)?;
+    exception.setattr(py, "stack_trace", failure.stack_trace)?;
+    exception.setattr(py, "non_retryable", failure.non_retryable)?;
+    if let Some(cause) = failure.cause {
+        exception.setattr(py, "__cause__", user_code_failure_to_exception(py, *cause)?)?;
+    }
+    Ok(exception)
+}
+
+
+#[pyfunction(name = "exception_to_failure")]
+pub(crate) fn wrapped_exception_to_failure(py: Python, exception: &PyAny) -> PyResult<WrappedUserCodeFailure> {
+    exception_to_failure(py, exception, 0)
+}
+
+
+#[pyfunction(name = "failure_to_exception")]
+pub(crate) fn wrapped_failure_to_exception(py: Python, failure: WrappedUserCodeFailure) -> PyResult<PyObject> {
+    user_code_failure_to_exception(py, failure)
+}
@@ -0,0 +1,5 @@
+pub(crate) mod compression;
+pub(crate) mod data_converter;
+pub(crate) mod encryption;
+pub(crate) mod failure;
+pub(crate) mod payload_converters;
@@ -0,0 +1,245 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{
+    PyTypeError,
+    PyValueError,
+};
+use pyo3::types::{
+    PyBytes,
+    PyDict,
+};
+
+use crate::protos::coresdk::common::WrappedPayload;
+
+
+pub(crate) const METADATA_ENCODING: &str = "encoding";
+const METADATA_MESSAGE_TYPE: &str = "messageType";
+
+const ENCODING_BINARY_NULL: &[u8] = b"binary/null";
+const ENCODING_BINARY_PLAIN: &[u8] = b"binary/plain";
+const ENCODING_JSON_PLAIN: &[u8] = b"json/plain";
+const ENCODING_JSON_PROTOBUF: &[u8] = b"json/protobuf";
+const ENCODING_BINARY_PROTOBUF: &[u8] = b"binary/protobuf";
+
+
+fn encoded_payload(encoding: &[u8], data: Vec<u8>) -> WrappedPayload {
+    let mut metadata = HashMap::new();
+    metadata.insert(String::from(METADATA_ENCODING), encoding.to_vec());
+    WrappedPayload {
+        metadata,
+        data,
+    }
+}
+
+
+// None if the "protobuf" package isn't installed, in which case there can't be any protobuf messages to convert
+fn protobuf_message_class(py: Python) -> Option<&PyAny> {
+    py.import("google.protobuf.message").and_then(|module| module.getattr("Message")).ok()
+}
+
+
+fn is_protobuf_message(py: Python, value: &PyAny) -> PyResult<bool> {
+    match protobuf_message_class(py) {
+        None => Ok(false),
+        Some(message_class) => py.import("builtins")?.call_method1("isinstance", (value, message_class))?.is_true(),
+    }
+}
+
+
+fn protobuf_payload(encoding: &[u8], value: &PyAny, data: Vec<u8>) -> PyResult<WrappedPayload> {
+    let message_type: String = value.getattr("DESCRIPTOR")?.getattr("full_name")?.extract()?;
+    let mut payload = encoded_payload(encoding, data);
+    payload.metadata.insert(String::from(METADATA_MESSAGE_TYPE), message_type.into_bytes());
+    Ok(payload)
+}
+
+
+// Fresh instance of the message class that the payload was encoded from; the class has to be imported somewhere for
+// protobuf's symbol database to know about it
+fn new_protobuf_message<'p>(py: Python<'p>, payload: &WrappedPayload) -> PyResult<&'p PyAny> {
+    let message_type = match payload.metadata.get(METADATA_MESSAGE_TYPE) {
+        None => return Err(PyValueError::new_err("Protobuf payload doesn't have its message type set")),
+        Some(message_type) => String::from_utf8_lossy(message_type).to_string(),
+    };
+    let symbol_database = py.import("google.protobuf.symbol_database")?.call_method0("Default")?;
+    let message_class = symbol_database.call_method1("GetSymbol", (message_type,))?;
+    message_class.call0()
+}
+
+
+#[pyclass(name = "BinaryNullPayloadConverter")]
+#[derive(Clone)]
+pub struct WrappedBinaryNullPayloadConverter {}
+
+#[pymethods]
+impl WrappedBinaryNullPayloadConverter {
+    #[new]
+    fn new() -> Self {
+        WrappedBinaryNullPayloadConverter {}
+    }
+
+    #[classattr]
+    fn encoding() -> &'static [u8] {
+        ENCODING_BINARY_NULL
+    }
+
+    fn to_payload(&self, value: &PyAny) -> Option<WrappedPayload> {
+        if value.is_none() {
+            Some(encoded_payload(ENCODING_BINARY_NULL, Vec::new()))
+        } else {
+            None
+        }
+    }
+
+    fn from_payload(&self, py: Python, _payload: WrappedPayload) -> PyObject {
+        py.None()
+    }
+}
+
+
+#[pyclass(name = "BinaryPlainPayloadConverter")]
+#[derive(Clone)]
+pub struct WrappedBinaryPlainPayloadConverter {}
+
+#[pymethods]
+impl WrappedBinaryPlainPayloadConverter {
+    #[new]
+    fn new() -> Self {
+        WrappedBinaryPlainPayloadConverter {}
+    }
+
+    #[classattr]
+    fn encoding() -> &'static [u8] {
+        ENCODING_BINARY_PLAIN
+    }
+
+    // Takes bytes and anything else that exposes its bytes through the buffer protocol, e.g. bytearray and memoryview
+    fn to_payload(&self, py: Python, value: &PyAny) -> PyResult<Option<WrappedPayload>> {
+        if let Ok(bytes) = value.downcast::<PyBytes>() {
+            return Ok(Some(encoded_payload(ENCODING_BINARY_PLAIN, bytes.as_bytes().to_vec())));
+        }
+        match PyBuffer::<u8>::get(value) {
+            Ok(buffer) => Ok(Some(encoded_payload(ENCODING_BINARY_PLAIN, buffer.to_vec(py)?))),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn from_payload<'p>(&self, py: Python<'p>, payload: WrappedPayload) -> &'p PyBytes {
+        PyBytes::new(py, &payload.data)
+    }
+}
+
+
+#[pyclass(name = "JsonPlainPayloadConverter")]
+#[derive(Clone)]
+pub struct WrappedJsonPlainPayloadConverter {}
+
+#[pymethods]
+impl WrappedJsonPlainPayloadConverter {
+    #[new]
+    fn new() -> Self {
+        WrappedJsonPlainPayloadConverter {}
+    }
+
+    #[classattr]
+    fn encoding() -> &'static [u8] {
+        ENCODING_JSON_PLAIN
+    }
+
+    // Takes anything that the "json" module can serialize, leaving the rest to the converters after it in the chain;
+    // NaN and infinities aren't valid JSON that the other SDKs could parse, so floats like that get left to them too
+    fn to_payload(&self, py: Python, value: &PyAny) -> PyResult<Option<WrappedPayload>> {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("allow_nan", false)?;
+        let json = match py.import("json")?.call_method("dumps", (value,), Some(kwargs)) {
+            Ok(json) => json,
+            Err(err) if err.is_instance::<PyTypeError>(py) || err.is_instance::<PyValueError>(py) => return Ok(None),
+            Err(err) => return Err(err),
+        };
+        let json: String = json.extract()?;
+        Ok(Some(encoded_payload(ENCODING_JSON_PLAIN, json.into_bytes())))
+    }
+
+    fn from_payload(&self, py: Python, payload: WrappedPayload) -> PyResult<PyObject> {
+        Ok(py.import("json")?.call_method1("loads", (PyBytes::new(py, &payload.data),))?.into())
+    }
+}
+
+
+#[pyclass(name = "JsonProtobufPayloadConverter")]
+#[derive(Clone)]
+pub struct WrappedJsonProtobufPayloadConverter {}
+
+#[pymethods]
+impl WrappedJsonProtobufPayloadConverter {
+    #[new]
+    fn new() -> Self {
+        WrappedJsonProtobufPayloadConverter {}
+    }
+
+    #[classattr]
+    fn encoding() -> &'static [u8] {
+        ENCODING_JSON_PROTOBUF
+    }
+
+    fn to_payload(&self, py: Python, value: &PyAny) -> PyResult<Option<WrappedPayload>> {
+        if !is_protobuf_message(py, value)? {
+            return Ok(None);
+        }
+        let json: String = py.import("google.protobuf.json_format")?.call_method1("MessageToJson", (value,))?.extract()?;
+        Ok(Some(protobuf_payload(ENCODING_JSON_PROTOBUF, value, json.into_bytes())?))
+    }
+
+    fn from_payload(&self, py: Python, payload: WrappedPayload) -> PyResult<PyObject> {
+        let message = new_protobuf_message(py, &payload)?;
+        let json = String::from_utf8_lossy(&payload.data).to_string();
+        py.import("google.protobuf.json_format")?.call_method1("Parse", (json, message))?;
+        Ok(message.into())
+    }
+}
+
+
+#[pyclass(name = "BinaryProtobufPayloadConverter")]
+#[derive(Clone)]
+pub struct WrappedBinaryProtobufPayloadConverter {}
+
+#[pymethods]
+impl WrappedBinaryProtobufPayloadConverter {
+    #[new]
+    fn new() -> Self {
+        WrappedBinaryProtobufPayloadConverter {}
+    }
+
+    #[classattr]
+    fn encoding() -> &'static [u8] {
+        ENCODING_BINARY_PROTOBUF
+    }
+
+    fn to_payload(&self, py: Python, value: &PyAny) -> PyResult<Option<WrappedPayload>> {
+        if !is_protobuf_message(py, value)? {
+            return Ok(None);
+        }
+        let data: Vec<u8> = value.call_method0("SerializeToString")?.extract()?;
+        Ok(Some(protobuf_payload(ENCODING_BINARY_PROTOBUF, value, data)?))
+    }
+
+    fn from_payload(&self, py: Python, payload: WrappedPayload) -> PyResult<PyObject> {
+        let message = new_protobuf_message(py, &payload)?;
+        message.call_method1("ParseFromString", (PyBytes::new(py, &payload.data),))?;
+        Ok(message.into())
+    }
+}
+
+
+// Same order as in the other Temporal SDKs
+pub(crate) fn default_payload_converters(py: Python) -> PyResult<Vec<PyObject>> {
+    Ok(vec![
+        Py::new(py, WrappedBinaryNullPayloadConverter {})?.into_py(py),
+        Py::new(py, WrappedBinaryPlainPayloadConverter {})?.into_py(py),
+        Py::new(py, WrappedJsonProtobufPayloadConverter {})?.into_py(py),
+        Py::new(py, WrappedBinaryProtobufPayloadConverter {})?.into_py(py),
+        Py::new(py, WrappedJsonPlainPayloadConverter {})?.into_py(py),
+    ])
+}
@@ -1,12 +1,251 @@
 use pyo3::create_exception;
+use pyo3::prelude::*;
+use pyo3::type_object::PyTypeObject;
+use pyo3::types::PyBytes;
+use temporal_sdk_core::errors::{
+    CompleteActivityError as CoreCompleteActivityError,
+    CompleteWfError as CoreCompleteWfError,
+    CoreInitError as CoreCoreInitError,
+    PollActivityError as CorePollActivityError,
+    PollWfError as CorePollWfError,
+    WorkerRegistrationError as CoreWorkerRegistrationError,
+};
+
+create_exception!(pytemporalio, CoreInitError, pyo3::exceptions::PyOSError);
+
+create_exception!(pytemporalio, ConfigError, pyo3::exceptions::PyValueError);
 
 create_exception!(pytemporalio, WorkerRegistrationError, pyo3::exceptions::PyException);
 create_exception!(pytemporalio, WorkerAlreadyRegisteredForQueue, WorkerRegistrationError);
 
 create_exception!(pytemporalio, PollWfError, pyo3::exceptions::PyException);
+create_exception!(pytemporalio, PollWfNoWorkerForQueue, PollWfError);
+create_exception!(pytemporalio, PollWfTonicError, PollWfError);
+create_exception!(pytemporalio, PollWfBadPollResponseFromServer, PollWfError);
 
 create_exception!(pytemporalio, PollActivityError, pyo3::exceptions::PyException);
+create_exception!(pytemporalio, PollActivityNoWorkerForQueue, PollActivityError);
+create_exception!(pytemporalio, PollActivityTonicError, PollActivityError);
 
 create_exception!(pytemporalio, CompleteWfError, pyo3::exceptions::PyException);
+create_exception!(pytemporalio, MalformedWorkflowCompletion, CompleteWfError);
+create_exception!(pytemporalio, WorkflowUpdateError, CompleteWfError);
+create_exception!(pytemporalio, CompleteWfNoWorkerForQueue, CompleteWfError);
+create_exception!(pytemporalio, CompleteWfTonicError, CompleteWfError);
 
 create_exception!(pytemporalio, CompleteActivityError, pyo3::exceptions::PyException);
+create_exception!(pytemporalio, MalformedActivityCompletion, CompleteActivityError);
+create_exception!(pytemporalio, CompleteActivityNoWorkerForQueue, CompleteActivityError);
+create_exception!(pytemporalio, CompleteActivityTonicError, CompleteActivityError);
+
+create_exception!(pytemporalio, ShutdownError, pyo3::exceptions::PyException);
+
+create_exception!(pytemporalio, ClientError, pyo3::exceptions::PyException);
+create_exception!(pytemporalio, ClientConnectionError, ClientError);
+
+create_exception!(pytemporalio, ActivityError, pyo3::exceptions::PyException);
+
+create_exception!(pytemporalio, ApplicationError, pyo3::exceptions::PyException);
+create_exception!(pytemporalio, NonRetryableError, ApplicationError);
+
+create_exception!(pytemporalio, NondeterminismError, pyo3::exceptions::PyException);
+
+create_exception!(pytemporalio, UnknownEncryptionKeyError, pyo3::exceptions::PyException);
+
+create_exception!(pytemporalio, WorkflowFailureError, pyo3::exceptions::PyException);
+create_exception!(pytemporalio, WorkflowCancelledError, WorkflowFailureError);
+
+
+// Exception of type T with its structured details set as attributes of the exception instance
+pub(crate) fn new_err_with_attributes<T: PyTypeObject>(py: Python,
+                                                       message: String,
+                                                       attributes: Vec<(&str, PyObject)>) -> PyErr {
+    let err = PyErr::new::<T, _>(message);
+    for (name, value) in attributes {
+        if let Err(setattr_err) = err.instance(py).setattr(name, value) {
+            return setattr_err;
+        }
+    }
+    err
+}
+
+
+// gRPC status code as an integer (e.g. 5 for NOT_FOUND) plus the status' message
+fn tonic_status_attributes(py: Python, status: &tonic::Status) -> Vec<(&'static str, PyObject)> {
+    vec![
+        ("code", (status.code() as i32).into_py(py)),
+        ("details", status.message().into_py(py)),
+    ]
+}
+
+
+pub(crate) fn tonic_status_err<T: PyTypeObject>(py: Python,
+                                                message: String,
+                                                status: &tonic::Status,
+                                                mut attributes: Vec<(&str, PyObject)>) -> PyErr {
+    attributes.extend(tonic_status_attributes(py, status));
+    new_err_with_attributes::<T>(py, message, attributes)
+}
+
+
+pub(crate) fn core_init_error(py: Python, err: CoreCoreInitError) -> PyErr {
+    new_err_with_attributes::<CoreInitError>(py, err.to_string(), vec![])
+}
+
+
+pub(crate) fn worker_registration_error(py: Python, err: CoreWorkerRegistrationError) -> PyErr {
+    match err {
+        CoreWorkerRegistrationError::WorkerAlreadyRegisteredForQueue(ref task_queue) => {
+            new_err_with_attributes::<WorkerAlreadyRegisteredForQueue>(py, err.to_string(), vec![
+                ("task_queue", task_queue.as_str().into_py(py)),
+            ])
+        }
+    }
+}
+
+
+// Shutdowns get reported as ShutdownError no matter where they've happened
+pub(crate) fn shutdown_error(py: Python, message: String, task_queue: &str) -> PyErr {
+    new_err_with_attributes::<ShutdownError>(py, message, vec![
+        ("task_queue", task_queue.into_py(py)),
+    ])
+}
+
+
+pub(crate) fn poll_wf_error(py: Python, err: CorePollWfError, task_queue: &str) -> PyErr {
+    let task_queue_attribute = ("task_queue", task_queue.into_py(py));
+    match err {
+        CorePollWfError::ShutDown => shutdown_error(py, format!(
+            "Core is shut down, no more workflow tasks for task queue {}",
+            task_queue
+        ), task_queue),
+        CorePollWfError::NoWorkerForQueue(_) => {
+            new_err_with_attributes::<PollWfNoWorkerForQueue>(py, err.to_string(), vec![task_queue_attribute])
+        }
+        CorePollWfError::TonicError(ref status) => {
+            tonic_status_err::<PollWfTonicError>(py, err.to_string(), status, vec![task_queue_attribute])
+        }
+        CorePollWfError::BadPollResponseFromServer(_) => {
+            new_err_with_attributes::<PollWfBadPollResponseFromServer>(py, err.to_string(), vec![task_queue_attribute])
+        }
+        // Workflow task that core has tried to complete on its own
+        CorePollWfError::AutocompleteError(complete_err) => complete_wf_error(py, complete_err, None),
+    }
+}
+
+
+pub(crate) fn poll_activity_error(py: Python, err: CorePollActivityError, task_queue: &str) -> PyErr {
+    let task_queue_attribute = ("task_queue", task_queue.into_py(py));
+    match err {
+        CorePollActivityError::ShutDown => shutdown_error(py, format!(
+            "Core is shut down, no more activity tasks for task queue {}",
+            task_queue
+        ), task_queue),
+        CorePollActivityError::NoWorkerForQueue(_) => {
+            new_err_with_attributes::<PollActivityNoWorkerForQueue>(py, err.to_string(), vec![task_queue_attribute])
+        }
+        CorePollActivityError::TonicError(ref status) => {
+            tonic_status_err::<PollActivityTonicError>(py, err.to_string(), status, vec![task_queue_attribute])
+        }
+    }
+}
+
+
+// Run ID is None for completions that core has made on its own, in which case the error has it
+pub(crate) fn complete_wf_error(py: Python, err: CoreCompleteWfError, run_id: Option<&str>) -> PyErr {
+    let run_id_attribute = ("run_id", run_id.into_py(py));
+    match err {
+        CoreCompleteWfError::MalformedWorkflowCompletion { ref reason, ref run_id } => {
+            new_err_with_attributes::<MalformedWorkflowCompletion>(py, err.to_string(), vec![
+                ("run_id", run_id.as_str().into_py(py)),
+                ("reason", reason.as_str().into_py(py)),
+            ])
+        }
+        CoreCompleteWfError::WorkflowUpdateError { ref run_id, .. } => {
+            new_err_with_attributes::<WorkflowUpdateError>(py, err.to_string(), vec![
+                ("run_id", run_id.as_str().into_py(py)),
+            ])
+        }
+        CoreCompleteWfError::NoWorkerForQueue(ref task_queue) => {
+            new_err_with_attributes::<CompleteWfNoWorkerForQueue>(py, err.to_string(), vec![
+                run_id_attribute,
+                ("task_queue", task_queue.as_str().into_py(py)),
+            ])
+        }
+        CoreCompleteWfError::TonicError(ref status) => {
+            tonic_status_err::<CompleteWfTonicError>(py, err.to_string(), status, vec![run_id_attribute])
+        }
+    }
+}
+
+
+pub(crate) fn complete_activity_error(py: Python, err: CoreCompleteActivityError, task_token: &[u8]) -> PyErr {
+    let task_token_attribute = ("task_token", PyBytes::new(py, task_token).into_py(py));
+    match err {
+        CoreCompleteActivityError::MalformedActivityCompletion { ref reason, .. } => {
+            new_err_with_attributes::<MalformedActivityCompletion>(py, err.to_string(), vec![
+                task_token_attribute,
+                ("reason", reason.as_str().into_py(py)),
+            ])
+        }
+        CoreCompleteActivityError::NoWorkerForQueue(ref task_queue) => {
+            new_err_with_attributes::<CompleteActivityNoWorkerForQueue>(py, err.to_string(), vec![
+                task_token_attribute,
+                ("task_queue", task_queue.as_str().into_py(py)),
+            ])
+        }
+        CoreCompleteActivityError::TonicError(ref status) => {
+            tonic_status_err::<CompleteActivityTonicError>(py, err.to_string(), status, vec![task_token_attribute])
+        }
+    }
+}
+
+
+// Every exception class has all of its possible attributes, set to None on the class for the instances which don't
+// have them
+pub(crate) fn add_errors(py: Python, module: &PyModule) -> PyResult<()> {
+    let errors: Vec<(&str, &pyo3::types::PyType, Vec<&str>)> = vec![
+        ("CoreInitError", py.get_type::<CoreInitError>(), vec![]),
+        ("ConfigError", py.get_type::<ConfigError>(), vec!["key", "source", "invalid_fields"]),
+        ("WorkerRegistrationError", py.get_type::<WorkerRegistrationError>(), vec!["task_queue"]),
+        ("WorkerAlreadyRegisteredForQueue", py.get_type::<WorkerAlreadyRegisteredForQueue>(), vec![]),
+        ("PollWfError", py.get_type::<PollWfError>(), vec!["task_queue"]),
+        ("PollWfNoWorkerForQueue", py.get_type::<PollWfNoWorkerForQueue>(), vec![]),
+        ("PollWfTonicError", py.get_type::<PollWfTonicError>(), vec!["code", "details"]),
+        ("PollWfBadPollResponseFromServer", py.get_type::<PollWfBadPollResponseFromServer>(), vec![]),
+        ("PollActivityError", py.get_type::<PollActivityError>(), vec!["task_queue"]),
+        ("PollActivityNoWorkerForQueue", py.get_type::<PollActivityNoWorkerForQueue>(), vec![]),
+        ("PollActivityTonicError", py.get_type::<PollActivityTonicError>(), vec!["code", "details"]),
+        ("CompleteWfError", py.get_type::<CompleteWfError>(), vec!["run_id"]),
+        ("MalformedWorkflowCompletion", py.get_type::<MalformedWorkflowCompletion>(), vec!["reason"]),
+        ("WorkflowUpdateError", py.get_type::<WorkflowUpdateError>(), vec![]),
+        ("CompleteWfNoWorkerForQueue", py.get_type::<CompleteWfNoWorkerForQueue>(), vec!["task_queue"]),
+        ("CompleteWfTonicError", py.get_type::<CompleteWfTonicError>(), vec!["code", "details"]),
+        ("CompleteActivityError", py.get_type::<CompleteActivityError>(), vec!["task_token"]),
+        ("MalformedActivityCompletion", py.get_type::<MalformedActivityCompletion>(), vec!["reason"]),
+        ("CompleteActivityNoWorkerForQueue", py.get_type::<CompleteActivityNoWorkerForQueue>(), vec!["task_queue"]),
+        ("CompleteActivityTonicError", py.get_type::<CompleteActivityTonicError>(), vec!["code", "details"]),
+        ("ShutdownError", py.get_type::<ShutdownError>(), vec!["task_queue"]),
+        ("ClientError", py.get_type::<ClientError>(), vec!["code", "details"]),
+        ("ClientConnectionError", py.get_type::<ClientConnectionError>(), vec![]),
+        ("ActivityError", py.get_type::<ActivityError>(), vec!["activity_id"]),
+        ("ApplicationError", py.get_type::<ApplicationError>(), vec!["type", "source", "stack_trace"]),
+        ("NonRetryableError", py.get_type::<NonRetryableError>(), vec![]),
+        ("NondeterminismError", py.get_type::<NondeterminismError>(), vec!["workflow_task", "event_id", "expected_commands", "actual_commands"]),
+        ("UnknownEncryptionKeyError", py.get_type::<UnknownEncryptionKeyError>(), vec!["key_id"]),
+        ("WorkflowFailureError", py.get_type::<WorkflowFailureError>(), vec!["workflow_id"]),
+        ("WorkflowCancelledError", py.get_type::<WorkflowCancelledError>(), vec![]),
+    ];
+    for (name, error_type, attributes) in errors {
+        for attribute in attributes {
+            error_type.setattr(attribute, py.None())?;
+        }
+        module.add(name, error_type)?;
+    }
+
+    // Raising NonRetryableError, or any other exception with a truthy "non_retryable", fails the activity or
+    // workflow for good
+    py.get_type::<ApplicationError>().setattr("non_retryable", false)?;
+    py.get_type::<NonRetryableError>().setattr("non_retryable", true)?;
+    Ok(())
+}
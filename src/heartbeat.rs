@@ -0,0 +1,143 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration as StdDuration, Instant};
+
+use prost_types::Duration as ProstDuration;
+use pyo3_asyncio;
+use temporal_sdk_core::{
+    Core,
+    protos::coresdk::ActivityHeartbeat,
+};
+
+
+// Same as in the other Temporal SDKs: heartbeat at most every 80% of the heartbeat timeout, or every 30 seconds if
+// the activity doesn't have a heartbeat timeout
+const HEARTBEAT_TIMEOUT_FRACTION: f64 = 0.8;
+const DEFAULT_HEARTBEAT_INTERVAL: StdDuration = StdDuration::from_secs(30);
+
+
+struct ThrottledActivity {
+    task_queue: String,
+    interval: StdDuration,
+    last_sent: Option<Instant>,
+    // Latest heartbeat that hasn't been sent yet because the previous one got sent less than an interval ago
+    pending: Option<ActivityHeartbeat>,
+    flush_scheduled: bool,
+}
+
+
+pub(crate) fn heartbeat_interval(heartbeat_timeout: Option<ProstDuration>) -> StdDuration {
+    match heartbeat_timeout.and_then(|timeout| StdDuration::try_from(timeout).ok()) {
+        Some(timeout) if timeout > StdDuration::from_secs(0) => timeout.mul_f64(HEARTBEAT_TIMEOUT_FRACTION),
+        _ => DEFAULT_HEARTBEAT_INTERVAL,
+    }
+}
+
+
+// Coalesces heartbeats of running activities so that a tight loop in an activity doesn't flood the server; activities
+// get tracked by their task token from the moment they get polled until they get completed or their worker gets shut
+// down
+pub(crate) struct HeartbeatThrottler {
+    core: Arc<dyn Core>,
+    activities: Mutex<HashMap<Vec<u8>, ThrottledActivity>>,
+}
+
+impl HeartbeatThrottler {
+    pub(crate) fn new(core: Arc<dyn Core>) -> Self {
+        HeartbeatThrottler {
+            core,
+            activities: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn track(&self, task_queue: &str, task_token: Vec<u8>, interval: StdDuration) {
+        self.activities.lock().unwrap().insert(task_token, ThrottledActivity {
+            task_queue: String::from(task_queue),
+            interval,
+            last_sent: None,
+            pending: None,
+            flush_scheduled: false,
+        });
+    }
+
+    pub(crate) fn interval(&self, task_token: &[u8]) -> Option<StdDuration> {
+        self.activities.lock().unwrap().get(task_token).map(|activity| activity.interval)
+    }
+
+    pub(crate) fn record(self: &Arc<Self>, heartbeat: ActivityHeartbeat) {
+        let mut activities = self.activities.lock().unwrap();
+
+        // Activities that this Core hasn't polled itself don't get throttled
+        let activity = match activities.get_mut(&heartbeat.task_token) {
+            None => {
+                drop(activities);
+                self.core.record_activity_heartbeat(heartbeat);
+                return;
+            }
+            Some(activity) => activity,
+        };
+
+        let now = Instant::now();
+        let next_send = match activity.last_sent {
+            None => now,
+            Some(last_sent) => last_sent + activity.interval,
+        };
+        if next_send <= now {
+            activity.last_sent = Some(now);
+            activity.pending = None;
+            drop(activities);
+            self.core.record_activity_heartbeat(heartbeat);
+            return;
+        }
+
+        let task_token = heartbeat.task_token.clone();
+        activity.pending = Some(heartbeat);
+        if activity.flush_scheduled {
+            return;
+        }
+        activity.flush_scheduled = true;
+        drop(activities);
+
+        let throttler = self.clone();
+        pyo3_asyncio::tokio::get_runtime().spawn(async move {
+            tokio::time::sleep(next_send - now).await;
+            throttler.send_pending(&task_token);
+        });
+    }
+
+    fn send_pending(&self, task_token: &[u8]) {
+        let pending = match self.activities.lock().unwrap().get_mut(task_token) {
+            None => None,
+            Some(activity) => {
+                activity.flush_scheduled = false;
+                if activity.pending.is_some() {
+                    activity.last_sent = Some(Instant::now());
+                }
+                activity.pending.take()
+            }
+        };
+        if let Some(heartbeat) = pending {
+            self.core.record_activity_heartbeat(heartbeat);
+        }
+    }
+
+    // Sends out the heartbeat that's still being held back and stops tracking the activity, to be called right before
+    // the activity gets completed
+    pub(crate) fn flush(&self, task_token: &[u8]) {
+        let activity = self.activities.lock().unwrap().remove(task_token);
+        if let Some(heartbeat) = activity.and_then(|activity| activity.pending) {
+            self.core.record_activity_heartbeat(heartbeat);
+        }
+    }
+
+    // Stops tracking the activities that never got completed once their worker is shut down, the heartbeats that are
+    // still being held back for them don't matter anymore
+    pub(crate) fn forget_task_queue(&self, task_queue: &str) {
+        self.activities.lock().unwrap().retain(|_, activity| activity.task_queue != task_queue);
+    }
+
+    pub(crate) fn forget_all(&self) {
+        self.activities.lock().unwrap().clear();
+    }
+}
@@ -1,35 +1,110 @@
 use std::convert::TryFrom;
 use std::sync::Arc;
+use std::time::Instant;
 
 use pyo3::prelude::*;
 use pyo3::wrap_pyfunction;
-use pyo3::exceptions::PyOSError;
+use pyo3::exceptions::{
+    PyOSError,
+    PyTimeoutError,
+    PyValueError,
+};
 use pyo3_asyncio;
+use pyo3_chrono;
 use temporal_sdk_core::{
     init,
     Core,
     CoreInitOptions,
     ServerGatewayOptions,
     WorkerConfig,
+    errors::{
+        PollActivityError,
+        PollWfError,
+    },
     protos::coresdk::{
         ActivityTaskCompletion,
         ActivityHeartbeat,
-        workflow_completion::WfActivationCompletion,
+        activity_result::{
+            ActivityResult,
+            activity_result,
+        },
+        activity_task::{
+            ActivityTask,
+            activity_task,
+        },
+        workflow_activation::WfActivation,
+        workflow_completion::{
+            WfActivationCompletion,
+            wf_activation_completion,
+        },
     },
 };
 
+mod activity;
+mod client;
+mod config;
+mod converter;
 mod errors;
+mod heartbeat;
+mod logging;
+mod metrics;
 mod pollers;
 mod protos;
+mod shutdown;
+mod testing;
+mod tls_reload;
+mod trace_context;
 mod utils;
 mod worker;
+mod workflow;
+
+use activity::{
+    info::{
+        WrappedActivityInfo,
+        wrapped_cancellation_reason,
+        wrapped_info,
+        wrapped_is_cancelled,
+    },
+    registry::wrapped_defn,
+};
+
+use client::workflow::{
+    WrappedClient,
+    WrappedWorkflowHandle,
+    wrapped_connect,
+};
+
+use config::{
+    WrappedConfig,
+    wrapped_load_config,
+};
+
+use converter::{
+    compression::WrappedCompressionCodec,
+    data_converter::WrappedDataConverter,
+    encryption::WrappedEncryptionCodec,
+    failure::{
+        wrapped_exception_to_failure,
+        wrapped_failure_to_exception,
+    },
+    payload_converters::{
+        WrappedBinaryNullPayloadConverter,
+        WrappedBinaryPlainPayloadConverter,
+        WrappedBinaryProtobufPayloadConverter,
+        WrappedJsonPlainPayloadConverter,
+        WrappedJsonProtobufPayloadConverter,
+    },
+};
 
 use errors::{
-    WorkerRegistrationError,
-    PollWfError,
-    PollActivityError,
-    CompleteWfError,
-    CompleteActivityError,
+    add_errors,
+    complete_activity_error,
+    complete_wf_error,
+    core_init_error,
+    poll_activity_error,
+    poll_wf_error,
+    shutdown_error,
+    worker_registration_error,
 };
 
 use pollers::{
@@ -38,6 +113,7 @@ use pollers::{
         WrappedClientTlsConfig,
         WrappedTlsConfig,
     },
+    tls::TlsFiles,
 };
 
 use protos::{
@@ -46,14 +122,14 @@ use protos::{
         WrappedActivityHeartbeat,
         activity_result::{
             WrappedActivityResult,
-            WrappedStatus,
+            WrappedStatusBase,
             WrappedSuccess,
             WrappedCancelation,
             WrappedFailure,
         },
         activity_task::{
             WrappedActivityTask,
-            WrappedVariant as WrappedActivityTaskVariant,
+            WrappedVariantBase as WrappedActivityTaskVariantBase,
             WrappedStart,
             WrappedCancel,
         },
@@ -73,7 +149,8 @@ use protos::{
             WrappedCancelWorkflow,
             WrappedSignalWorkflow,
             WrappedResolveActivity,
-            WrappedVariant as WrappedWorkflowActivationVariant,
+            WrappedRemoveFromCache,
+            WrappedVariantBase as WrappedWorkflowActivationVariantBase,
         },
         workflow_commands::{
             WrappedStartTimer,
@@ -81,165 +158,462 @@ use protos::{
             WrappedScheduleActivity,
             WrappedRequestCancelActivity,
             WrappedQuerySuccess,
-            WrappedQueryResultVariant,
+            WrappedQueryFailed,
+            WrappedQueryResultVariantBase,
             WrappedQueryResult,
             WrappedCompleteWorkflowExecution,
             WrappedFailWorkflowExecution,
             WrappedContinueAsNewWorkflowExecution,
             WrappedCancelWorkflowExecution,
-            WrappedVariant as WrappedWorkflowCommandsVariant,
+            WrappedVariantBase as WrappedWorkflowCommandsVariantBase,
             WrappedWorkflowCommand,
         },
         workflow_completion::{
             WrappedSuccess as WrappedWorkflowCompletionSuccess,
             WrappedFailure as WrappedWorkflowCompletionFailure,
-            WrappedStatus as WrappedWorkflowCompletionStatus,
+            WrappedStatusBase as WrappedWorkflowCompletionStatusBase,
             WrappedWfActivationCompletion,
         },
     },
 };
 
+use heartbeat::{
+    HeartbeatThrottler,
+    heartbeat_interval,
+};
+use logging::{
+    DEFAULT_LOG_LEVEL,
+    init_logging,
+    parse_log_level,
+};
+use metrics::{
+    Metrics,
+    PollOutcome,
+    serve_metrics,
+};
+use shutdown::ShutdownTracker;
+use testing::environment::WrappedWorkflowEnvironment;
+use testing::mock_core::WrappedMockCore;
+use tls_reload::{
+    ReloadingCore,
+    default_tls_reload_interval,
+    watch_tls_files,
+};
+use utils::{
+    pyo3_chrono_duration_to_std_duration,
+    std_duration_to_pyo3_chrono_duration,
+};
 use worker::config::WrappedWorkerConfig;
+use worker::runner::WrappedWorker;
+use workflow::event_loop::{
+    WrappedHandle,
+    WrappedWorkflowEventLoop,
+};
+use workflow::replayer::WrappedReplayer;
 
 
 #[pyclass(name = "CoreInitOptions")]
 #[derive(Clone)]
 struct WrappedCoreInitOptions {
     pub(crate) internal: CoreInitOptions,
+    // Lowest level of sdk-core's tracing events that get forwarded to the "pytemporalio.core" loggers
+    #[pyo3(get)]
+    pub(crate) log_level: String,
+    // Set when the gateway's TLS config got read from files, which then get checked for changes every
+    // tls_reload_interval, None turning that off
+    pub(crate) tls_files: Option<TlsFiles>,
+    #[pyo3(get)]
+    pub(crate) tls_reload_interval: Option<pyo3_chrono::Duration>,
 }
 
 #[pymethods]
 impl WrappedCoreInitOptions {
     // FIXME set default value of max_cached_workflows here
     #[new]
-    fn new(gateway_opts: WrappedServerGatewayOptions, max_cached_workflows: usize) -> PyResult<Self> {
+    #[args("*", log_level = "String::from(DEFAULT_LOG_LEVEL)", tls_reload_interval = "default_tls_reload_interval()")]
+    fn new(gateway_opts: WrappedServerGatewayOptions,
+           max_cached_workflows: usize,
+           log_level: String,
+           tls_reload_interval: Option<pyo3_chrono::Duration>) -> PyResult<Self> {
+        parse_log_level(&log_level)?;
+        if let Some(ref interval) = tls_reload_interval {
+            if interval.0 <= chrono::Duration::zero() {
+                return Err(PyValueError::new_err(format!(
+                    "tls_reload_interval must be positive, got {}",
+                    interval.0
+                )));
+            }
+        }
         Ok(WrappedCoreInitOptions {
+            tls_files: gateway_opts.tls_files(),
             internal: CoreInitOptions {
                 gateway_opts: ServerGatewayOptions::try_from(gateway_opts)?,
                 max_cached_workflows,
-            }
+            },
+            log_level,
+            tls_reload_interval,
         })
     }
 }
 
 
-#[pyclass(name = "Core")]
+#[pyclass(name = "Core", subclass)]
 #[derive(Clone)]
 struct WrappedCore {
     // FIXME rename to something more sensible
     pub(crate) internal: Arc<dyn Core>,
+    pub(crate) shutdown: Arc<ShutdownTracker>,
+    pub(crate) heartbeats: Arc<HeartbeatThrottler>,
+    pub(crate) metrics: Arc<Metrics>,
+    // Deadline for shutdown() when no explicit timeout gets passed, e.g. when leaving "async with"
+    #[pyo3(get, set)]
+    pub(crate) shutdown_timeout: Option<pyo3_chrono::Duration>,
+}
+
+// Rust-side counterparts of the Python methods so that the worker's run loop can use them without going through
+// the Python interpreter
+impl WrappedCore {
+    pub(crate) fn new(internal: Arc<dyn Core>, namespace: String) -> Self {
+        WrappedCore {
+            internal: internal.clone(),
+            shutdown: Arc::new(ShutdownTracker::default()),
+            heartbeats: Arc::new(HeartbeatThrottler::new(internal)),
+            metrics: Arc::new(Metrics::new(namespace)),
+            shutdown_timeout: None,
+        }
+    }
+
+    pub(crate) async fn register(&self, config: WorkerConfig) -> PyResult<()> {
+        let task_queue = config.task_queue.clone();
+        let max_outstanding_workflow_tasks = config.max_outstanding_workflow_tasks;
+        let max_outstanding_activities = config.max_outstanding_activities;
+        match self.internal.register_worker(config).await {
+            Err(err) => Err(Python::with_gil(|py| worker_registration_error(py, err))),
+            Ok(()) => {
+                self.metrics.worker_registered(&task_queue, max_outstanding_workflow_tasks, max_outstanding_activities);
+                Ok(())
+            }
+        }
+    }
+
+    pub(crate) async fn poll_wf_activation(&self, task_queue: &str) -> PyResult<WfActivation> {
+        if self.shutdown.is_shut_down(task_queue) {
+            return Err(Python::with_gil(|py| shutdown_error(py, format!(
+                "Task queue {} is shut down",
+                task_queue
+            ), task_queue)));
+        }
+        let poll_started = Instant::now();
+        let result = tokio::select! {
+            result = self.internal.poll_workflow_task(task_queue) => result,
+            _ = self.shutdown.wait_for_shutdown(task_queue) => {
+                self.metrics.workflow_task_polled(task_queue, poll_started.elapsed(), PollOutcome::Shutdown);
+                return Err(Python::with_gil(|py| shutdown_error(py, format!(
+                    "Task queue {} got shut down while polling",
+                    task_queue
+                ), task_queue)));
+            }
+        };
+        match result {
+            Err(err) => {
+                let outcome = match err {
+                    PollWfError::ShutDown => PollOutcome::Shutdown,
+                    _ => PollOutcome::Error,
+                };
+                self.metrics.workflow_task_polled(task_queue, poll_started.elapsed(), outcome);
+                Err(Python::with_gil(|py| poll_wf_error(py, err, task_queue)))
+            }
+            Ok(wf_activation) => {
+                self.metrics.workflow_task_polled(task_queue, poll_started.elapsed(), PollOutcome::Task);
+                self.metrics.workflow_activation_started(task_queue, &wf_activation.run_id);
+                Ok(wf_activation)
+            }
+        }
+    }
+
+    pub(crate) async fn poll_activity(&self, task_queue: &str) -> PyResult<ActivityTask> {
+        if self.shutdown.is_shut_down(task_queue) {
+            return Err(Python::with_gil(|py| shutdown_error(py, format!(
+                "Task queue {} is shut down",
+                task_queue
+            ), task_queue)));
+        }
+        let poll_started = Instant::now();
+        let result = tokio::select! {
+            result = self.internal.poll_activity_task(task_queue) => result,
+            _ = self.shutdown.wait_for_shutdown(task_queue) => {
+                self.metrics.activity_task_polled(task_queue, poll_started.elapsed(), PollOutcome::Shutdown);
+                return Err(Python::with_gil(|py| shutdown_error(py, format!(
+                    "Task queue {} got shut down while polling",
+                    task_queue
+                ), task_queue)));
+            }
+        };
+        match result {
+            Err(err) => {
+                let outcome = match err {
+                    PollActivityError::ShutDown => PollOutcome::Shutdown,
+                    _ => PollOutcome::Error,
+                };
+                self.metrics.activity_task_polled(task_queue, poll_started.elapsed(), outcome);
+                Err(Python::with_gil(|py| poll_activity_error(py, err, task_queue)))
+            }
+            Ok(activity_task) => {
+                self.metrics.activity_task_polled(task_queue, poll_started.elapsed(), PollOutcome::Task);
+                // Cancellations are about activities that are in flight already
+                if let Some(activity_task::Variant::Start(start)) = &activity_task.variant {
+                    self.metrics.activity_started(task_queue, &activity_task.task_token);
+                    self.heartbeats.track(
+                        task_queue,
+                        activity_task.task_token.clone(),
+                        heartbeat_interval(start.heartbeat_timeout.clone()),
+                    );
+                }
+                Ok(activity_task)
+            }
+        }
+    }
+
+    pub(crate) async fn complete_wf_activation(&self, completion: WfActivationCompletion) -> PyResult<()> {
+        let _in_flight = self.shutdown.start_completion();
+        let run_id = completion.run_id.clone();
+        let failed = matches!(completion.status, Some(wf_activation_completion::Status::Failed(_)));
+        let result = self.internal.complete_workflow_task(completion).await;
+        self.metrics.workflow_activation_completed(&run_id, failed, result.is_err());
+        match result {
+            Err(err) => Err(Python::with_gil(|py| complete_wf_error(py, err, Some(run_id.as_str())))),
+            Ok(()) => Ok(()),
+        }
+    }
+
+    pub(crate) async fn complete_activity(&self, completion: ActivityTaskCompletion) -> PyResult<()> {
+        let _in_flight = self.shutdown.start_completion();
+        self.heartbeats.flush(&completion.task_token);
+        let task_token = completion.task_token.clone();
+        let failed = matches!(
+            completion.result,
+            Some(ActivityResult { status: Some(activity_result::Status::Failed(_)) })
+        );
+        let result = self.internal.complete_activity_task(completion).await;
+        self.metrics.activity_completed(&task_token, failed, result.is_err());
+        match result {
+            Err(err) => Err(Python::with_gil(|py| complete_activity_error(py, err, &task_token))),
+            Ok(()) => Ok(()),
+        }
+    }
 }
 
 #[pymethods]
 impl WrappedCore {
     fn register_worker<'p>(&self, py: Python<'p>, config: WrappedWorkerConfig) -> PyResult<&'p PyAny> {
         let worker_config = WorkerConfig::try_from(config)?;
-        let internal = self.internal.clone();
+        let core = self.clone();
         let current_loop = pyo3_asyncio::get_running_loop(py)?;
         pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
-            match internal.register_worker(worker_config).await {
-                Err(err) => Err(WorkerRegistrationError::new_err(format!(
-                    "{}",
-                    err.to_string()
-                ))),
-                Ok(()) => {
-                    Python::with_gil(|py| Ok(py.None()))
-                }
-            }
+            core.register(worker_config).await?;
+            Python::with_gil(|py| Ok(py.None()))
         })
     }
 
     fn poll_workflow_task<'p>(&self, py: Python<'p>, task_queue: String) -> PyResult<&'p PyAny> {
-        let internal = self.internal.clone();
+        let core = self.clone();
         let current_loop = pyo3_asyncio::get_running_loop(py)?;
         pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
-            match internal.poll_workflow_task(task_queue.as_str()).await {
-                Err(err) => Err(PollWfError::new_err(format!(
-                    "{}",
-                    err.to_string()
-                ))),
-                Ok(wf_activation) => {
-                    Python::with_gil(|py| {
-                        let wrapped_wf_activation = WrappedWfActivation::from(wf_activation);
-                        Ok(wrapped_wf_activation.into_py(py))
-                    })
-                }
-            }
+            let wf_activation = core.poll_wf_activation(task_queue.as_str()).await?;
+            Python::with_gil(|py| {
+                let wrapped_wf_activation = WrappedWfActivation::try_from(wf_activation)?;
+                Ok(wrapped_wf_activation.into_py(py))
+            })
         })
     }
 
     fn poll_activity_task<'p>(&self, py: Python<'p>, task_queue: String) -> PyResult<&'p PyAny> {
-        let internal = self.internal.clone();
+        let core = self.clone();
         let current_loop = pyo3_asyncio::get_running_loop(py)?;
         pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
-            match internal.poll_activity_task(task_queue.as_str()).await {
-                Err(err) => Err(PollActivityError::new_err(format!(
-                    "{}",
-                    err.to_string()
-                ))),
-                Ok(activity_task) => {
-                    Python::with_gil(|py| {
-                        let wrapped_activity_task = WrappedActivityTask::try_from(activity_task)?;
-                        Ok(wrapped_activity_task.into_py(py))
-                    })
-                }
-            }
+            let activity_task = core.poll_activity(task_queue.as_str()).await?;
+            Python::with_gil(|py| {
+                let wrapped_activity_task = WrappedActivityTask::try_from(activity_task)?;
+                Ok(wrapped_activity_task.into_py(py))
+            })
         })
     }
 
     fn complete_activity_task<'p>(&self, py: Python<'p>, completion: WrappedActivityTaskCompletion) -> PyResult<&'p PyAny> {
-        let internal = self.internal.clone();
+        let core = self.clone();
         let current_loop = pyo3_asyncio::get_running_loop(py)?;
         pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
-            match internal.complete_activity_task(ActivityTaskCompletion::try_from(completion)?).await {
-                Err(err) => Err(CompleteActivityError::new_err(format!(
-                    "{}",
+            core.complete_activity(ActivityTaskCompletion::try_from(completion)?).await?;
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    }
+
+    fn complete_workflow_task<'p>(&self, py: Python<'p>, completion: WrappedWfActivationCompletion) -> PyResult<&'p PyAny> {
+        let core = self.clone();
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            core.complete_wf_activation(WfActivationCompletion::try_from(completion)?).await?;
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    }
+
+    fn record_activity_heartbeat(&self, details: WrappedActivityHeartbeat) {
+        self.heartbeats.record(ActivityHeartbeat::from(details))
+    }
+
+    // Minimum time between two heartbeats of the activity that actually get sent to the server, None if the activity
+    // isn't running
+    fn heartbeat_interval(&self, task_token: Vec<u8>) -> PyResult<Option<pyo3_chrono::Duration>> {
+        match self.heartbeats.interval(&task_token) {
+            None => Ok(None),
+            Some(interval) => Ok(Some(std_duration_to_pyo3_chrono_duration(interval)?)),
+        }
+    }
+
+    fn request_workflow_eviction(&self, run_id: String) {
+        self.internal.request_workflow_eviction(run_id.as_str())
+    }
+
+    // Snapshot of the worker metrics as a dict of task queues to their counters, gauges and histograms
+    fn metrics(&self, py: Python) -> PyResult<PyObject> {
+        self.metrics.to_py(py)
+    }
+
+    // Serves the worker metrics in Prometheus' text format until Core gets shut down; resolves to the address that is
+    // being listened on, which is where to look when binding to port 0
+    #[args(address = "String::from(\"127.0.0.1:9464\")")]
+    fn serve_metrics<'p>(&self, py: Python<'p>, address: String) -> PyResult<&'p PyAny> {
+        let metrics = self.metrics.clone();
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            let listener = match tokio::net::TcpListener::bind(address.as_str()).await {
+                Err(err) => return Err(PyOSError::new_err(format!(
+                    "Unable to listen for metrics scrapes on {}: {}",
+                    address,
                     err.to_string()
                 ))),
-                Ok(()) => {
-                    Python::with_gil(|py| Ok(py.None()))
+                Ok(listener) => listener,
+            };
+            let local_address = match listener.local_addr() {
+                Err(_) => address,
+                Ok(local_address) => local_address.to_string(),
+            };
+            metrics.set_server(tokio::spawn(serve_metrics(listener, metrics.clone())));
+            Python::with_gil(|py| Ok(local_address.into_py(py)))
+        })
+    }
+
+    #[args(timeout = "None")]
+    fn shutdown<'p>(&self, py: Python<'p>, timeout: Option<pyo3_chrono::Duration>) -> PyResult<&'p PyAny> {
+        let internal = self.internal.clone();
+        let shutdown = self.shutdown.clone();
+        let heartbeats = self.heartbeats.clone();
+        let timeout = match timeout.or(self.shutdown_timeout.clone()) {
+            None => None,
+            Some(timeout) => Some(pyo3_chrono_duration_to_std_duration(timeout)?),
+        };
+
+        // Stop new polls and wake up the pending ones right away, then let the outstanding tasks finish
+        shutdown.shut_down_core();
+        self.metrics.stop_server();
+
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            let shutdown_future = async {
+                internal.shutdown().await;
+                shutdown.wait_for_completions().await;
+            };
+            let result = match timeout {
+                None => {
+                    shutdown_future.await;
+                    Ok(())
                 }
-            }
+                Some(timeout) => tokio::time::timeout(timeout, shutdown_future).await.map_err(|_| {
+                    PyTimeoutError::new_err(format!("Core didn't shut down in {:?}", timeout))
+                }),
+            };
+            // Activities that are still running at this point won't get completed anymore
+            heartbeats.forget_all();
+            result?;
+            Python::with_gil(|py| Ok(py.None()))
         })
     }
 
-    fn complete_workflow_task<'p>(&self, py: Python<'p>, completion: WrappedWfActivationCompletion) -> PyResult<&'p PyAny> {
+    #[args(timeout = "None")]
+    fn shutdown_worker<'p>(&self, py: Python<'p>, task_queue: String, timeout: Option<pyo3_chrono::Duration>) -> PyResult<&'p PyAny> {
         let internal = self.internal.clone();
+        let shutdown = self.shutdown.clone();
+        let heartbeats = self.heartbeats.clone();
+        let timeout = match timeout.or(self.shutdown_timeout.clone()) {
+            None => None,
+            Some(timeout) => Some(pyo3_chrono_duration_to_std_duration(timeout)?),
+        };
+
+        shutdown.shut_down_task_queue(task_queue.as_str());
+
         let current_loop = pyo3_asyncio::get_running_loop(py)?;
         pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
-            match internal.complete_workflow_task(WfActivationCompletion::try_from(completion)?).await {
-                Err(err) => Err(CompleteWfError::new_err(format!(
-                    "{}",
-                    err.to_string()
-                ))),
-                Ok(()) => {
-                    Python::with_gil(|py| Ok(py.None()))
+            let result = match timeout {
+                None => {
+                    internal.shutdown_worker(task_queue.as_str()).await;
+                    Ok(())
                 }
-            }
+                Some(timeout) => {
+                    tokio::time::timeout(timeout, internal.shutdown_worker(task_queue.as_str())).await.map_err(|_| {
+                        PyTimeoutError::new_err(format!(
+                            "Worker for task queue {} didn't shut down in {:?}",
+                            task_queue,
+                            timeout
+                        ))
+                    })
+                }
+            };
+            heartbeats.forget_task_queue(task_queue.as_str());
+            result?;
+            Python::with_gil(|py| Ok(py.None()))
         })
     }
 
-    fn record_activity_heartbeat(&self, details: WrappedActivityHeartbeat) {
-        self.internal.record_activity_heartbeat(ActivityHeartbeat::from(details))
+    fn __aenter__<'p>(slf: PyRef<'p, Self>, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let core: Py<WrappedCore> = slf.into();
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            Python::with_gil(|py| Ok(core.into_py(py)))
+        })
     }
 
-    fn request_workflow_eviction(&self, run_id: String) {
-        self.internal.request_workflow_eviction(run_id.as_str())
+    fn __aexit__<'p>(&self,
+                     py: Python<'p>,
+                     _exc_type: &PyAny,
+                     _exc_value: &PyAny,
+                     _traceback: &PyAny) -> PyResult<&'p PyAny> {
+        self.shutdown(py, None)
     }
 }
 
 #[pyfunction(name = "init")]
 fn wrapped_init(py: Python, opts: WrappedCoreInitOptions) -> PyResult<&PyAny> {
+    init_logging(&opts.log_level)?;
+    let watched_tls_files = match (opts.tls_files.clone(), opts.tls_reload_interval.clone()) {
+        (Some(tls_files), Some(interval)) => Some((tls_files, pyo3_chrono_duration_to_std_duration(interval)?)),
+        _ => None,
+    };
     let current_loop = pyo3_asyncio::get_running_loop(py)?;
     pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
-        match init(opts.internal).await {
-            Err(err) => return Err(PyOSError::new_err(format!(
-                "{}",
-                err.to_string()
-            ))),
+        let namespace = opts.internal.gateway_opts.namespace.clone();
+        match init(opts.internal.clone()).await {
+            Err(err) => return Err(Python::with_gil(|py| core_init_error(py, err))),
             Ok(initialized_core) => {
+                let core: Arc<dyn Core> = match watched_tls_files {
+                    None => Arc::new(initialized_core),
+                    Some((tls_files, interval)) => {
+                        let reloading_core = Arc::new(ReloadingCore::new(opts.internal, Arc::new(initialized_core)));
+                        tokio::spawn(watch_tls_files(Arc::downgrade(&reloading_core), tls_files, interval));
+                        reloading_core
+                    }
+                };
                 Python::with_gil(|py| {
-                    let wrapped_core = WrappedCore { internal: Arc::new(initialized_core) };
+                    let wrapped_core = WrappedCore::new(core, namespace);
                     Ok(wrapped_core.into_py(py))
                 })
             }
@@ -253,11 +627,39 @@ pub fn pytemporalio(py: Python<'_>, root_module: &PyModule) -> PyResult<()> {
     root_module.add_function(wrap_pyfunction!(wrapped_init, root_module)?)?;
     root_module.add_class::<WrappedCore>()?;
     root_module.add_class::<WrappedCoreInitOptions>()?;
+    root_module.add_function(wrap_pyfunction!(wrapped_load_config, root_module)?)?;
+    root_module.add_class::<WrappedConfig>()?;
 
     let errors_module = PyModule::new(py, "errors")?;
     root_module.add_submodule(errors_module)?;
-    errors_module.add("WorkerRegistrationError", py.get_type::<WorkerRegistrationError>())?;
-    errors_module.add("PollWfError", py.get_type::<PollWfError>())?;
+    add_errors(py, errors_module)?;
+
+    let activity_module = PyModule::new(py, "activity")?;
+    root_module.add_submodule(activity_module)?;
+    activity_module.add_function(wrap_pyfunction!(wrapped_defn, activity_module)?)?;
+    activity_module.add_function(wrap_pyfunction!(wrapped_info, activity_module)?)?;
+    activity_module.add_function(wrap_pyfunction!(wrapped_is_cancelled, activity_module)?)?;
+    activity_module.add_function(wrap_pyfunction!(wrapped_cancellation_reason, activity_module)?)?;
+    activity_module.add_class::<WrappedActivityInfo>()?;
+
+    let client_module = PyModule::new(py, "client")?;
+    root_module.add_submodule(client_module)?;
+    client_module.add_function(wrap_pyfunction!(wrapped_connect, client_module)?)?;
+    client_module.add_class::<WrappedClient>()?;
+    client_module.add_class::<WrappedWorkflowHandle>()?;
+
+    let converter_module = PyModule::new(py, "converter")?;
+    root_module.add_submodule(converter_module)?;
+    converter_module.add_class::<WrappedDataConverter>()?;
+    converter_module.add_class::<WrappedBinaryNullPayloadConverter>()?;
+    converter_module.add_class::<WrappedBinaryPlainPayloadConverter>()?;
+    converter_module.add_class::<WrappedJsonPlainPayloadConverter>()?;
+    converter_module.add_class::<WrappedJsonProtobufPayloadConverter>()?;
+    converter_module.add_class::<WrappedBinaryProtobufPayloadConverter>()?;
+    converter_module.add_class::<WrappedCompressionCodec>()?;
+    converter_module.add_class::<WrappedEncryptionCodec>()?;
+    converter_module.add_function(wrap_pyfunction!(wrapped_exception_to_failure, converter_module)?)?;
+    converter_module.add_function(wrap_pyfunction!(wrapped_failure_to_exception, converter_module)?)?;
 
     let pollers_module = PyModule::new(py, "pollers")?;
     root_module.add_submodule(pollers_module)?;
@@ -277,7 +679,7 @@ pub fn pytemporalio(py: Python<'_>, root_module: &PyModule) -> PyResult<()> {
     let protos_activity_result_module = PyModule::new(py, "activity_result")?;
     protos_module.add_submodule(protos_activity_result_module)?;
     protos_activity_result_module.add_class::<WrappedActivityResult>()?;
-    protos_activity_result_module.add_class::<WrappedStatus>()?;
+    protos_activity_result_module.add_class::<WrappedStatusBase>()?;
     protos_activity_result_module.add_class::<WrappedSuccess>()?;
     protos_activity_result_module.add_class::<WrappedCancelation>()?;
     protos_activity_result_module.add_class::<WrappedFailure>()?;
@@ -285,7 +687,7 @@ pub fn pytemporalio(py: Python<'_>, root_module: &PyModule) -> PyResult<()> {
     let protos_activity_task_module = PyModule::new(py, "activity_task")?;
     protos_module.add_submodule(protos_activity_task_module)?;
     protos_activity_task_module.add_class::<WrappedActivityTask>()?;
-    protos_activity_task_module.add_class::<WrappedActivityTaskVariant>()?;
+    protos_activity_task_module.add_class::<WrappedActivityTaskVariantBase>()?;
     protos_activity_task_module.add_class::<WrappedStart>()?;
     protos_activity_task_module.add_class::<WrappedCancel>()?;
 
@@ -307,7 +709,8 @@ pub fn pytemporalio(py: Python<'_>, root_module: &PyModule) -> PyResult<()> {
     protos_workflow_activation_module.add_class::<WrappedCancelWorkflow>()?;
     protos_workflow_activation_module.add_class::<WrappedSignalWorkflow>()?;
     protos_workflow_activation_module.add_class::<WrappedResolveActivity>()?;
-    protos_workflow_activation_module.add_class::<WrappedWorkflowActivationVariant>()?;
+    protos_workflow_activation_module.add_class::<WrappedRemoveFromCache>()?;
+    protos_workflow_activation_module.add_class::<WrappedWorkflowActivationVariantBase>()?;
 
     let protos_workflow_commands_module = PyModule::new(py, "workflow_commands")?;
     protos_module.add_submodule(protos_workflow_commands_module)?;
@@ -316,28 +719,41 @@ pub fn pytemporalio(py: Python<'_>, root_module: &PyModule) -> PyResult<()> {
     protos_workflow_commands_module.add_class::<WrappedScheduleActivity>()?;
     protos_workflow_commands_module.add_class::<WrappedRequestCancelActivity>()?;
     protos_workflow_commands_module.add_class::<WrappedQuerySuccess>()?;
-    protos_workflow_commands_module.add_class::<WrappedQueryResultVariant>()?;
+    protos_workflow_commands_module.add_class::<WrappedQueryFailed>()?;
+    protos_workflow_commands_module.add_class::<WrappedQueryResultVariantBase>()?;
     protos_workflow_commands_module.add_class::<WrappedQueryResult>()?;
     protos_workflow_commands_module.add_class::<WrappedCompleteWorkflowExecution>()?;
     protos_workflow_commands_module.add_class::<WrappedFailWorkflowExecution>()?;
     protos_workflow_commands_module.add_class::<WrappedContinueAsNewWorkflowExecution>()?;
     protos_workflow_commands_module.add_class::<WrappedCancelWorkflowExecution>()?;
-    protos_workflow_commands_module.add_class::<WrappedWorkflowCommandsVariant>()?;
+    protos_workflow_commands_module.add_class::<WrappedWorkflowCommandsVariantBase>()?;
     protos_workflow_commands_module.add_class::<WrappedWorkflowCommand>()?;
 
     let protos_workflow_completion_module = PyModule::new(py, "workflow_completion")?;
     protos_module.add_submodule(protos_workflow_completion_module)?;
     protos_workflow_completion_module.add_class::<WrappedWorkflowCompletionSuccess>()?;
     protos_workflow_completion_module.add_class::<WrappedWorkflowCompletionFailure>()?;
-    protos_workflow_completion_module.add_class::<WrappedWorkflowCompletionStatus>()?;
+    protos_workflow_completion_module.add_class::<WrappedWorkflowCompletionStatusBase>()?;
     protos_workflow_completion_module.add_class::<WrappedWfActivationCompletion>()?;
 
+    let testing_module = PyModule::new(py, "testing")?;
+    root_module.add_submodule(testing_module)?;
+    testing_module.add_class::<WrappedMockCore>()?;
+    testing_module.add_class::<WrappedWorkflowEnvironment>()?;
+
     let worker_module = PyModule::new(py, "worker")?;
     root_module.add_submodule(worker_module)?;
+    worker_module.add_class::<WrappedWorker>()?;
 
     let worker_config_module = PyModule::new(py, "config")?;
     worker_module.add_submodule(worker_config_module)?;
     worker_config_module.add_class::<WrappedWorkerConfig>()?;
 
+    let workflow_module = PyModule::new(py, "workflow")?;
+    root_module.add_submodule(workflow_module)?;
+    workflow_module.add_class::<WrappedWorkflowEventLoop>()?;
+    workflow_module.add_class::<WrappedHandle>()?;
+    workflow_module.add_class::<WrappedReplayer>()?;
+
     Ok(())
 }
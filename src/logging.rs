@@ -0,0 +1,254 @@
+use std::fmt;
+use std::sync::{Mutex, Once};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::{
+    IntoPyDict,
+    PyDict,
+};
+use tracing::{
+    Event,
+    Id,
+    Level,
+    Metadata,
+    Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Record},
+    subscriber::Interest,
+};
+use tracing_subscriber::{
+    Registry,
+    layer::{Context, Layer, SubscriberExt},
+    registry::LookupSpan,
+};
+
+
+// Every target gets its own logger under this one, e.g. "pytemporalio.core.temporal_sdk_core.pollers"
+const ROOT_LOGGER: &str = "pytemporalio.core";
+
+pub(crate) const DEFAULT_LOG_LEVEL: &str = "info";
+
+// Python's logging doesn't have a level below DEBUG, so TRACE gets one of its own
+const PYTHON_TRACE: u8 = 5;
+const PYTHON_DEBUG: u8 = 10;
+const PYTHON_INFO: u8 = 20;
+const PYTHON_WARNING: u8 = 30;
+const PYTHON_ERROR: u8 = 40;
+const PYTHON_OFF: u8 = u8::MAX;
+
+// Lowest Python level that gets forwarded; it's checked on every event rather than cached by tracing's callsites so
+// that a later init() can change it
+static LEVEL_FILTER: AtomicU8 = AtomicU8::new(PYTHON_INFO);
+
+static INSTALL_SUBSCRIBER: Once = Once::new();
+
+
+fn python_level(level: &Level) -> u8 {
+    match *level {
+        Level::TRACE => PYTHON_TRACE,
+        Level::DEBUG => PYTHON_DEBUG,
+        Level::INFO => PYTHON_INFO,
+        Level::WARN => PYTHON_WARNING,
+        Level::ERROR => PYTHON_ERROR,
+    }
+}
+
+
+pub(crate) fn parse_log_level(log_level: &str) -> PyResult<u8> {
+    match log_level.to_lowercase().as_str() {
+        "trace" => Ok(PYTHON_TRACE),
+        "debug" => Ok(PYTHON_DEBUG),
+        "info" => Ok(PYTHON_INFO),
+        "warn" | "warning" => Ok(PYTHON_WARNING),
+        "error" => Ok(PYTHON_ERROR),
+        "off" => Ok(PYTHON_OFF),
+        _ => Err(PyValueError::new_err(format!(
+            "Log level {} is not one of trace, debug, info, warning, error or off",
+            log_level
+        ))),
+    }
+}
+
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: Vec<(String, String)>,
+}
+
+impl FieldVisitor {
+    fn record(&mut self, field: &Field, value: String) {
+        if field.name() == "message" {
+            self.message = Some(value);
+        } else {
+            self.fields.push((String::from(field.name()), value));
+        }
+    }
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.record(field, String::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record(field, format!("{:?}", value));
+    }
+}
+
+
+// Fields of a span, kept in the span's extensions until an event inside of it needs them
+struct SpanFields(Vec<(String, String)>);
+
+
+struct LogRecord {
+    target: String,
+    level: u8,
+    message: String,
+    fields: Vec<(String, String)>,
+    // Outermost span first
+    spans: Vec<(String, Vec<(String, String)>)>,
+}
+
+
+fn format_fields(fields: &[(String, String)]) -> String {
+    fields.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join(" ")
+}
+
+
+// Message is formatted like tracing's own formatter does it, with the structured parts also passed in "extra" so that
+// handlers can pick them up as the record's tracing_target, tracing_fields and tracing_spans attributes
+fn log_record(py: Python, record: LogRecord) -> PyResult<()> {
+    let logger_name = format!("{}.{}", ROOT_LOGGER, record.target.replace("::", "."));
+    let logger = py.import("logging")?.call_method1("getLogger", (logger_name,))?;
+    if !logger.call_method1("isEnabledFor", (record.level,))?.is_true()? {
+        return Ok(());
+    }
+
+    let mut message = String::new();
+    for (name, fields) in &record.spans {
+        message.push_str(name);
+        if !fields.is_empty() {
+            message.push_str(&format!("{{{}}}", format_fields(fields)));
+        }
+        message.push_str(": ");
+    }
+    message.push_str(&record.message);
+    if !record.fields.is_empty() {
+        if !record.message.is_empty() {
+            message.push(' ');
+        }
+        message.push_str(&format_fields(&record.fields));
+    }
+
+    let spans: Vec<(String, &PyDict)> = record.spans.into_iter()
+        .map(|(name, fields)| (name, fields.into_py_dict(py)))
+        .collect();
+    let extra = PyDict::new(py);
+    extra.set_item("tracing_target", record.target)?;
+    extra.set_item("tracing_fields", record.fields.into_py_dict(py))?;
+    extra.set_item("tracing_spans", spans)?;
+    logger.call_method("log", (record.level, message), Some([("extra", extra)].into_py_dict(py)))?;
+    Ok(())
+}
+
+
+// Events get logged from a thread of their own so that emitting one never has to wait for the GIL, which whoever is
+// emitting it might be keeping another thread waiting for
+fn forward_records(receiver: Receiver<LogRecord>) {
+    for record in receiver {
+        Python::with_gil(|py| {
+            if let Err(err) = log_record(py, record) {
+                err.print(py);
+            }
+        });
+    }
+}
+
+
+struct PythonLoggingLayer {
+    sender: Mutex<Sender<LogRecord>>,
+}
+
+impl<S> Layer<S> for PythonLoggingLayer
+    where S: Subscriber + for<'a> LookupSpan<'a> {
+    fn register_callsite(&self, _metadata: &'static Metadata<'static>) -> Interest {
+        Interest::sometimes()
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        python_level(metadata.level()) >= LEVEL_FILTER.load(Ordering::Relaxed)
+    }
+
+    fn new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        attrs.record(&mut visitor);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(visitor.fields));
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        values.record(&mut visitor);
+        if let Some(span) = ctx.span(id) {
+            let mut extensions = span.extensions_mut();
+            match extensions.get_mut::<SpanFields>() {
+                Some(span_fields) => span_fields.0.extend(visitor.fields),
+                None => extensions.insert(SpanFields(visitor.fields)),
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let spans = match ctx.event_scope(event) {
+            None => Vec::new(),
+            Some(scope) => scope.from_root().map(|span| {
+                let fields = match span.extensions().get::<SpanFields>() {
+                    None => Vec::new(),
+                    Some(span_fields) => span_fields.0.clone(),
+                };
+                (String::from(span.name()), fields)
+            }).collect(),
+        };
+
+        let metadata = event.metadata();
+        let record = LogRecord {
+            target: String::from(metadata.target()),
+            level: python_level(metadata.level()),
+            message: visitor.message.unwrap_or_default(),
+            fields: visitor.fields,
+            spans,
+        };
+        // Forwarding thread only goes away together with the process
+        let _ = self.sender.lock().unwrap().send(record);
+    }
+}
+
+
+// Sets the level filter and, the first time around, installs the subscriber that forwards sdk-core's tracing events to
+// Python's logging; it has to happen before Core gets initialized for Core's own subscriber not to take its place
+pub(crate) fn init_logging(log_level: &str) -> PyResult<()> {
+    LEVEL_FILTER.store(parse_log_level(log_level)?, Ordering::Relaxed);
+    INSTALL_SUBSCRIBER.call_once(|| {
+        let (sender, receiver) = mpsc::channel();
+        let forwarder = thread::Builder::new()
+            .name(String::from("pytemporalio-logging"))
+            .spawn(move || forward_records(receiver));
+        if forwarder.is_err() {
+            return;
+        }
+        let subscriber = Registry::default().with(PythonLoggingLayer {
+            sender: Mutex::new(sender),
+        });
+        // Some other subscriber might have been installed already, in which case the events keep going there
+        let _ = tracing::subscriber::set_global_default(subscriber);
+    });
+    Ok(())
+}
@@ -0,0 +1,468 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+
+const METRIC_PREFIX: &str = "pytemporalio";
+
+// Prometheus' default buckets, stretched to cover long polls
+const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0,
+];
+
+const MAX_REQUEST_HEAD_LENGTH: usize = 8192;
+
+const MIN_ACCEPT_BACKOFF: Duration = Duration::from_millis(10);
+const MAX_ACCEPT_BACKOFF: Duration = Duration::from_secs(1);
+
+
+// How a poll has ended: with a task, with the task queue or Core getting shut down, or with an error
+pub(crate) enum PollOutcome {
+    Task,
+    Shutdown,
+    Error,
+}
+
+
+#[derive(Clone, Default)]
+struct PollCounters {
+    task: u64,
+    shutdown: u64,
+    error: u64,
+}
+
+impl PollCounters {
+    fn count(&mut self, outcome: PollOutcome) {
+        match outcome {
+            PollOutcome::Task => self.task += 1,
+            PollOutcome::Shutdown => self.shutdown += 1,
+            PollOutcome::Error => self.error += 1,
+        }
+    }
+
+    fn to_py(&self, py: Python) -> PyResult<PyObject> {
+        let counters = PyDict::new(py);
+        counters.set_item("task", self.task)?;
+        counters.set_item("shutdown", self.shutdown)?;
+        counters.set_item("error", self.error)?;
+        Ok(counters.into())
+    }
+}
+
+
+#[derive(Clone)]
+struct Histogram {
+    // Non-cumulative, one per bucket plus one for +Inf
+    bucket_counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Default for Histogram {
+    fn default() -> Self {
+        Histogram {
+            bucket_counts: vec![0; LATENCY_BUCKETS.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let bucket = LATENCY_BUCKETS.iter().position(|le| seconds <= *le).unwrap_or(LATENCY_BUCKETS.len());
+        self.bucket_counts[bucket] += 1;
+        self.sum += seconds;
+        self.count += 1;
+    }
+
+    // Cumulative counts paired with their upper bounds, like Prometheus has them
+    fn cumulative_buckets(&self) -> Vec<(String, u64)> {
+        let mut cumulative = 0;
+        self.bucket_counts.iter().enumerate().map(|(bucket, count)| {
+            cumulative += count;
+            let le = match LATENCY_BUCKETS.get(bucket) {
+                None => String::from("+Inf"),
+                Some(le) => le.to_string(),
+            };
+            (le, cumulative)
+        }).collect()
+    }
+
+    fn to_py(&self, py: Python) -> PyResult<PyObject> {
+        let histogram = PyDict::new(py);
+        histogram.set_item("count", self.count)?;
+        histogram.set_item("sum", self.sum)?;
+        histogram.set_item("buckets", self.cumulative_buckets())?;
+        Ok(histogram.into())
+    }
+}
+
+
+#[derive(Clone, Default)]
+struct TaskQueueMetrics {
+    workflow_task_polls: PollCounters,
+    workflow_task_poll_latency: Histogram,
+    workflow_activation_duration: Histogram,
+    workflow_activation_failures: u64,
+    workflow_completion_errors: u64,
+    outstanding_workflow_tasks: usize,
+    max_outstanding_workflow_tasks: Option<usize>,
+    activity_task_polls: PollCounters,
+    activity_task_poll_latency: Histogram,
+    activity_duration: Histogram,
+    activity_failures: u64,
+    activity_completion_errors: u64,
+    outstanding_activities: usize,
+    max_outstanding_activities: Option<usize>,
+}
+
+
+#[derive(Default)]
+struct MetricsRecord {
+    task_queues: BTreeMap<String, TaskQueueMetrics>,
+    // Task queue and poll time of the workflow activations (by run id) and activity tasks (by task token) that have
+    // been polled and haven't been completed yet
+    workflow_tasks_in_flight: HashMap<String, (String, Instant)>,
+    activities_in_flight: HashMap<Vec<u8>, (String, Instant)>,
+}
+
+impl MetricsRecord {
+    fn task_queue(&mut self, task_queue: &str) -> &mut TaskQueueMetrics {
+        self.task_queues.entry(String::from(task_queue)).or_insert_with(TaskQueueMetrics::default)
+    }
+}
+
+
+// Counters, gauges and histograms of what the Core wrapper does, all labelled by namespace and task queue
+pub(crate) struct Metrics {
+    namespace: String,
+    record: Mutex<MetricsRecord>,
+    server: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl Metrics {
+    pub(crate) fn new(namespace: String) -> Self {
+        Metrics {
+            namespace,
+            record: Mutex::new(MetricsRecord::default()),
+            server: Mutex::new(None),
+        }
+    }
+
+    pub(crate) fn worker_registered(&self,
+                                    task_queue: &str,
+                                    max_outstanding_workflow_tasks: usize,
+                                    max_outstanding_activities: usize) {
+        let mut record = self.record.lock().unwrap();
+        let metrics = record.task_queue(task_queue);
+        metrics.max_outstanding_workflow_tasks = Some(max_outstanding_workflow_tasks);
+        metrics.max_outstanding_activities = Some(max_outstanding_activities);
+    }
+
+    pub(crate) fn workflow_task_polled(&self, task_queue: &str, latency: Duration, outcome: PollOutcome) {
+        let mut record = self.record.lock().unwrap();
+        let metrics = record.task_queue(task_queue);
+        metrics.workflow_task_polls.count(outcome);
+        metrics.workflow_task_poll_latency.observe(latency);
+    }
+
+    pub(crate) fn workflow_activation_started(&self, task_queue: &str, run_id: &str) {
+        let mut record = self.record.lock().unwrap();
+        record.task_queue(task_queue);
+        record.workflow_tasks_in_flight.insert(String::from(run_id), (String::from(task_queue), Instant::now()));
+    }
+
+    pub(crate) fn workflow_activation_completed(&self, run_id: &str, failed: bool, completion_error: bool) {
+        let mut record = self.record.lock().unwrap();
+        let (task_queue, started) = match record.workflow_tasks_in_flight.remove(run_id) {
+            None => return,
+            Some(in_flight) => in_flight,
+        };
+        let metrics = record.task_queue(&task_queue);
+        metrics.workflow_activation_duration.observe(started.elapsed());
+        if failed {
+            metrics.workflow_activation_failures += 1;
+        }
+        if completion_error {
+            metrics.workflow_completion_errors += 1;
+        }
+    }
+
+    pub(crate) fn activity_task_polled(&self, task_queue: &str, latency: Duration, outcome: PollOutcome) {
+        let mut record = self.record.lock().unwrap();
+        let metrics = record.task_queue(task_queue);
+        metrics.activity_task_polls.count(outcome);
+        metrics.activity_task_poll_latency.observe(latency);
+    }
+
+    pub(crate) fn activity_started(&self, task_queue: &str, task_token: &[u8]) {
+        let mut record = self.record.lock().unwrap();
+        record.task_queue(task_queue);
+        record.activities_in_flight.insert(task_token.to_vec(), (String::from(task_queue), Instant::now()));
+    }
+
+    pub(crate) fn activity_completed(&self, task_token: &[u8], failed: bool, completion_error: bool) {
+        let mut record = self.record.lock().unwrap();
+        let (task_queue, started) = match record.activities_in_flight.remove(task_token) {
+            None => return,
+            Some(in_flight) => in_flight,
+        };
+        let metrics = record.task_queue(&task_queue);
+        metrics.activity_duration.observe(started.elapsed());
+        if failed {
+            metrics.activity_failures += 1;
+        }
+        if completion_error {
+            metrics.activity_completion_errors += 1;
+        }
+    }
+
+    // Outstanding tasks get counted from the ones in flight at the time of the snapshot
+    fn snapshot(&self) -> BTreeMap<String, TaskQueueMetrics> {
+        let record = self.record.lock().unwrap();
+        let mut task_queues = record.task_queues.clone();
+        for (task_queue, _) in record.workflow_tasks_in_flight.values() {
+            if let Some(metrics) = task_queues.get_mut(task_queue) {
+                metrics.outstanding_workflow_tasks += 1;
+            }
+        }
+        for (task_queue, _) in record.activities_in_flight.values() {
+            if let Some(metrics) = task_queues.get_mut(task_queue) {
+                metrics.outstanding_activities += 1;
+            }
+        }
+        task_queues
+    }
+
+    // Dict of task queues to their metrics, with histograms as dicts of count, sum and cumulative buckets
+    pub(crate) fn to_py(&self, py: Python) -> PyResult<PyObject> {
+        let task_queues = PyDict::new(py);
+        for (task_queue, metrics) in self.snapshot() {
+            let task_queue_metrics = PyDict::new(py);
+            task_queue_metrics.set_item("namespace", self.namespace.as_str())?;
+            task_queue_metrics.set_item("workflow_task_polls", metrics.workflow_task_polls.to_py(py)?)?;
+            task_queue_metrics.set_item("workflow_task_poll_latency", metrics.workflow_task_poll_latency.to_py(py)?)?;
+            task_queue_metrics.set_item("workflow_activation_duration", metrics.workflow_activation_duration.to_py(py)?)?;
+            task_queue_metrics.set_item("workflow_activation_failures", metrics.workflow_activation_failures)?;
+            task_queue_metrics.set_item("workflow_completion_errors", metrics.workflow_completion_errors)?;
+            task_queue_metrics.set_item("outstanding_workflow_tasks", metrics.outstanding_workflow_tasks)?;
+            task_queue_metrics.set_item("max_outstanding_workflow_tasks", metrics.max_outstanding_workflow_tasks)?;
+            task_queue_metrics.set_item("activity_task_polls", metrics.activity_task_polls.to_py(py)?)?;
+            task_queue_metrics.set_item("activity_task_poll_latency", metrics.activity_task_poll_latency.to_py(py)?)?;
+            task_queue_metrics.set_item("activity_duration", metrics.activity_duration.to_py(py)?)?;
+            task_queue_metrics.set_item("activity_failures", metrics.activity_failures)?;
+            task_queue_metrics.set_item("activity_completion_errors", metrics.activity_completion_errors)?;
+            task_queue_metrics.set_item("outstanding_activities", metrics.outstanding_activities)?;
+            task_queue_metrics.set_item("max_outstanding_activities", metrics.max_outstanding_activities)?;
+            task_queues.set_item(task_queue, task_queue_metrics)?;
+        }
+        Ok(task_queues.into())
+    }
+
+    pub(crate) fn to_prometheus(&self) -> String {
+        let snapshot = self.snapshot();
+        let mut exposition = PrometheusExposition {
+            namespace: &self.namespace,
+            text: String::new(),
+        };
+
+        exposition.counter("workflow_task_polls_total", "Workflow task polls by how they ended", &snapshot, |metrics| {
+            poll_samples(&metrics.workflow_task_polls)
+        });
+        exposition.histogram("workflow_task_poll_latency_seconds", "Time spent waiting for workflow tasks", &snapshot, |metrics| {
+            &metrics.workflow_task_poll_latency
+        });
+        exposition.histogram("workflow_activation_duration_seconds", "Time from polling workflow activations to completing them", &snapshot, |metrics| {
+            &metrics.workflow_activation_duration
+        });
+        exposition.counter("workflow_activation_failures_total", "Workflow activations completed as failed", &snapshot, |metrics| {
+            vec![(None, metrics.workflow_activation_failures as f64)]
+        });
+        exposition.counter("workflow_completion_errors_total", "Workflow activation completions rejected by Core", &snapshot, |metrics| {
+            vec![(None, metrics.workflow_completion_errors as f64)]
+        });
+        exposition.gauge("outstanding_workflow_tasks", "Workflow activations polled and not completed yet", &snapshot, |metrics| {
+            Some(metrics.outstanding_workflow_tasks as f64)
+        });
+        exposition.gauge("max_outstanding_workflow_tasks", "Worker's limit of outstanding workflow activations", &snapshot, |metrics| {
+            metrics.max_outstanding_workflow_tasks.map(|max| max as f64)
+        });
+        exposition.counter("activity_task_polls_total", "Activity task polls by how they ended", &snapshot, |metrics| {
+            poll_samples(&metrics.activity_task_polls)
+        });
+        exposition.histogram("activity_task_poll_latency_seconds", "Time spent waiting for activity tasks", &snapshot, |metrics| {
+            &metrics.activity_task_poll_latency
+        });
+        exposition.histogram("activity_duration_seconds", "Time from polling activity tasks to completing them", &snapshot, |metrics| {
+            &metrics.activity_duration
+        });
+        exposition.counter("activity_failures_total", "Activity tasks completed as failed", &snapshot, |metrics| {
+            vec![(None, metrics.activity_failures as f64)]
+        });
+        exposition.counter("activity_completion_errors_total", "Activity task completions rejected by Core", &snapshot, |metrics| {
+            vec![(None, metrics.activity_completion_errors as f64)]
+        });
+        exposition.gauge("outstanding_activities", "Activity tasks polled and not completed yet", &snapshot, |metrics| {
+            Some(metrics.outstanding_activities as f64)
+        });
+        exposition.gauge("max_outstanding_activities", "Worker's limit of outstanding activity tasks", &snapshot, |metrics| {
+            metrics.max_outstanding_activities.map(|max| max as f64)
+        });
+
+        exposition.text
+    }
+
+    // Replaces the endpoint that is being served already, if any
+    pub(crate) fn set_server(&self, server: JoinHandle<()>) {
+        if let Some(previous_server) = self.server.lock().unwrap().replace(server) {
+            previous_server.abort();
+        }
+    }
+
+    pub(crate) fn stop_server(&self) {
+        if let Some(server) = self.server.lock().unwrap().take() {
+            server.abort();
+        }
+    }
+}
+
+
+fn poll_samples(counters: &PollCounters) -> Vec<(Option<(&'static str, &'static str)>, f64)> {
+    vec![
+        (Some(("outcome", "task")), counters.task as f64),
+        (Some(("outcome", "shutdown")), counters.shutdown as f64),
+        (Some(("outcome", "error")), counters.error as f64),
+    ]
+}
+
+
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+
+struct PrometheusExposition<'a> {
+    namespace: &'a str,
+    text: String,
+}
+
+impl PrometheusExposition<'_> {
+    fn header(&mut self, name: &str, help: &str, metric_type: &str) {
+        let _ = writeln!(self.text, "# HELP {}_{} {}", METRIC_PREFIX, name, help);
+        let _ = writeln!(self.text, "# TYPE {}_{} {}", METRIC_PREFIX, name, metric_type);
+    }
+
+    fn sample(&mut self, name: &str, task_queue: &str, extra_label: Option<(&str, &str)>, value: f64) {
+        let _ = write!(
+            self.text,
+            "{}_{}{{namespace=\"{}\",task_queue=\"{}\"",
+            METRIC_PREFIX,
+            name,
+            escape_label_value(self.namespace),
+            escape_label_value(task_queue)
+        );
+        if let Some((label, label_value)) = extra_label {
+            let _ = write!(self.text, ",{}=\"{}\"", label, escape_label_value(label_value));
+        }
+        let _ = writeln!(self.text, "}} {}", value);
+    }
+
+    fn counter<F>(&mut self, name: &str, help: &str, snapshot: &BTreeMap<String, TaskQueueMetrics>, samples: F)
+        where F: Fn(&TaskQueueMetrics) -> Vec<(Option<(&'static str, &'static str)>, f64)> {
+        self.header(name, help, "counter");
+        for (task_queue, metrics) in snapshot {
+            for (extra_label, value) in samples(metrics) {
+                self.sample(name, task_queue, extra_label, value);
+            }
+        }
+    }
+
+    fn gauge<F>(&mut self, name: &str, help: &str, snapshot: &BTreeMap<String, TaskQueueMetrics>, value: F)
+        where F: Fn(&TaskQueueMetrics) -> Option<f64> {
+        self.header(name, help, "gauge");
+        for (task_queue, metrics) in snapshot {
+            if let Some(value) = value(metrics) {
+                self.sample(name, task_queue, None, value);
+            }
+        }
+    }
+
+    fn histogram<F>(&mut self, name: &str, help: &str, snapshot: &BTreeMap<String, TaskQueueMetrics>, histogram: F)
+        where F: Fn(&TaskQueueMetrics) -> &Histogram {
+        self.header(name, help, "histogram");
+        for (task_queue, metrics) in snapshot {
+            let histogram = histogram(metrics);
+            for (le, count) in histogram.cumulative_buckets() {
+                self.sample(&format!("{}_bucket", name), task_queue, Some(("le", le.as_str())), count as f64);
+            }
+            self.sample(&format!("{}_sum", name), task_queue, None, histogram.sum);
+            self.sample(&format!("{}_count", name), task_queue, None, histogram.count as f64);
+        }
+    }
+}
+
+
+// Just enough HTTP/1.1 for a scraper: every GET of /metrics gets the exposition, anything else a 404, and the
+// connection gets closed after each response
+pub(crate) async fn serve_metrics(listener: TcpListener, metrics: Arc<Metrics>) {
+    let mut accept_backoff = MIN_ACCEPT_BACKOFF;
+    loop {
+        let stream = match listener.accept().await {
+            // Errors like running out of file descriptors persist for a while, so wait for them to clear up rather
+            // than retrying right away
+            Err(err) => {
+                tracing::warn!("Unable to accept a metrics connection, retrying in {:?}: {}", accept_backoff, err);
+                tokio::time::sleep(accept_backoff).await;
+                accept_backoff = std::cmp::min(accept_backoff * 2, MAX_ACCEPT_BACKOFF);
+                continue;
+            }
+            Ok((stream, _)) => stream,
+        };
+        accept_backoff = MIN_ACCEPT_BACKOFF;
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            let _ = respond(stream, &metrics).await;
+        });
+    }
+}
+
+async fn respond(mut stream: TcpStream, metrics: &Metrics) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD_LENGTH {
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+
+    let request_line = String::from_utf8_lossy(&head).lines().next().unwrap_or("").to_string();
+    let mut request_parts = request_line.split_whitespace();
+    let method = request_parts.next().unwrap_or("");
+    let path = request_parts.next().unwrap_or("").split('?').next().unwrap_or("");
+
+    let (status, content_type, body) = if method == "GET" && (path == "/metrics" || path == "/") {
+        ("200 OK", "text/plain; version=0.0.4", metrics.to_prometheus())
+    } else {
+        ("404 Not Found", "text/plain", String::from("Not found\n"))
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
@@ -9,7 +9,18 @@ use temporal_sdk_core::{
     TlsConfig,
     Url,
 };
+use tonic::transport::{
+    Certificate,
+    Channel,
+    ClientTlsConfig as TonicClientTlsConfig,
+    Identity,
+};
 
+use crate::errors::ClientConnectionError;
+use crate::pollers::tls::{
+    TlsFiles,
+    read_client_tls_config,
+};
 use crate::utils::{
     pyo3_chrono_duration_to_std_duration,
     std_duration_to_pyo3_chrono_duration,
@@ -25,12 +36,30 @@ pub struct WrappedClientTlsConfig {
 #[pymethods]
 impl WrappedClientTlsConfig {
     #[new]
-    fn new(client_cert: Vec<u8>, client_private_key: Vec<u8>) -> Self {
+    pub(crate) fn new(client_cert: Vec<u8>, client_private_key: Vec<u8>) -> Self {
         WrappedClientTlsConfig {
             client_cert,
             client_private_key,
         }
     }
+
+    // Raises ConfigError naming the file, e.g. when the key isn't the certificate's or the certificate has expired
+    #[staticmethod]
+    fn from_files(py: Python, client_cert: &str, client_private_key: &str) -> PyResult<Self> {
+        WrappedClientTlsConfig::load(py, "", client_cert, client_private_key)
+    }
+}
+
+impl WrappedClientTlsConfig {
+    pub(crate) fn load(py: Python,
+                       key_prefix: &str,
+                       client_cert_path: &str,
+                       client_private_key_path: &str) -> PyResult<Self> {
+        let client_tls_config = read_client_tls_config(client_cert_path, client_private_key_path).map_err(|err| {
+            err.into_py_err(py, key_prefix)
+        })?;
+        Ok(WrappedClientTlsConfig::from(client_tls_config))
+    }
 }
 
 impl From<ClientTlsConfig> for WrappedClientTlsConfig {
@@ -58,20 +87,55 @@ pub struct WrappedTlsConfig {
     server_root_ca_cert: Option<Vec<u8>>,
     domain: Option<String>,
     client_tls_config: Option<WrappedClientTlsConfig>,
+    // Set when the config got read from files, which Core then watches for the certificates getting rotated
+    files: Option<TlsFiles>,
 }
 
 #[pymethods]
 impl WrappedTlsConfig {
     #[new]
-    fn new(server_root_ca_cert: Option<Vec<u8>>,
-           domain: Option<String>,
-           client_tls_config: Option<WrappedClientTlsConfig>) -> Self {
+    pub(crate) fn new(server_root_ca_cert: Option<Vec<u8>>,
+                      domain: Option<String>,
+                      client_tls_config: Option<WrappedClientTlsConfig>) -> Self {
         WrappedTlsConfig {
             server_root_ca_cert,
             domain,
             client_tls_config,
+            files: None,
         }
     }
+
+    // Paths of PEM files that get validated right away, raising ConfigError naming the file that's wrong
+    #[staticmethod]
+    #[args(server_root_ca_cert = "None", domain = "None", client_cert = "None", client_private_key = "None")]
+    fn from_files(py: Python,
+                  server_root_ca_cert: Option<&str>,
+                  domain: Option<String>,
+                  client_cert: Option<&str>,
+                  client_private_key: Option<&str>) -> PyResult<Self> {
+        WrappedTlsConfig::load(py, "", server_root_ca_cert, domain, client_cert, client_private_key)
+    }
+}
+
+impl WrappedTlsConfig {
+    pub(crate) fn load(py: Python,
+                       key_prefix: &str,
+                       server_root_ca_cert_path: Option<&str>,
+                       domain: Option<String>,
+                       client_cert_path: Option<&str>,
+                       client_private_key_path: Option<&str>) -> PyResult<Self> {
+        let files = TlsFiles {
+            server_root_ca_cert: server_root_ca_cert_path.map(String::from),
+            domain,
+            client_cert: client_cert_path.map(String::from),
+            client_private_key: client_private_key_path.map(String::from),
+        };
+        let tls_config = files.read().map_err(|err| err.into_py_err(py, key_prefix))?;
+        Ok(WrappedTlsConfig {
+            files: Some(files),
+            ..WrappedTlsConfig::from(tls_config)
+        })
+    }
 }
 
 
@@ -84,6 +148,7 @@ impl From<TlsConfig> for WrappedTlsConfig {
                 None => None,
                 Some(client_tls_config) => Some(WrappedClientTlsConfig::from(client_tls_config)),
             },
+            files: None,
         }
     }
 }
@@ -118,12 +183,12 @@ pub struct WrappedServerGatewayOptions {
 #[pymethods]
 impl WrappedServerGatewayOptions {
     #[new]
-    fn new(target_url: String,
-           namespace: String,
-           identity: String,
-           worker_binary_id: String,
-           long_poll_timeout: pyo3_chrono::Duration,
-           tls_cfg: Option<WrappedTlsConfig>) -> Self {
+    pub(crate) fn new(target_url: String,
+                      namespace: String,
+                      identity: String,
+                      worker_binary_id: String,
+                      long_poll_timeout: pyo3_chrono::Duration,
+                      tls_cfg: Option<WrappedTlsConfig>) -> Self {
         WrappedServerGatewayOptions {
             target_url,
             namespace,
@@ -136,6 +201,13 @@ impl WrappedServerGatewayOptions {
 }
 
 
+impl WrappedServerGatewayOptions {
+    pub(crate) fn tls_files(&self) -> Option<TlsFiles> {
+        self.tls_cfg.as_ref().and_then(|tls_cfg| tls_cfg.files.clone())
+    }
+}
+
+
 impl TryFrom<ServerGatewayOptions> for WrappedServerGatewayOptions {
     type Error = PyErr;
 
@@ -180,3 +252,40 @@ impl TryFrom<WrappedServerGatewayOptions> for ServerGatewayOptions {
         })
     }
 }
+
+
+fn tonic_tls_config(tls_cfg: &TlsConfig) -> TonicClientTlsConfig {
+    let mut tonic_tls_config = TonicClientTlsConfig::new();
+    if let Some(server_root_ca_cert) = &tls_cfg.server_root_ca_cert {
+        tonic_tls_config = tonic_tls_config.ca_certificate(Certificate::from_pem(server_root_ca_cert));
+    }
+    if let Some(domain) = &tls_cfg.domain {
+        tonic_tls_config = tonic_tls_config.domain_name(domain);
+    }
+    if let Some(client_tls_config) = &tls_cfg.client_tls_config {
+        tonic_tls_config = tonic_tls_config.identity(Identity::from_pem(
+            &client_tls_config.client_cert,
+            &client_tls_config.client_private_key,
+        ));
+    }
+    tonic_tls_config
+}
+
+
+// Channel to the server that the gateway options point to, with their TLS config if they have one
+pub(crate) async fn connect_channel(opts: &ServerGatewayOptions) -> PyResult<Channel> {
+    let mut endpoint = match Channel::from_shared(opts.target_url.to_string()) {
+        Ok(endpoint) => endpoint,
+        Err(err) => return Err(ClientConnectionError::new_err(err.to_string())),
+    };
+    if let Some(tls_cfg) = &opts.tls_cfg {
+        endpoint = match endpoint.tls_config(tonic_tls_config(tls_cfg)) {
+            Ok(endpoint) => endpoint,
+            Err(err) => return Err(ClientConnectionError::new_err(err.to_string())),
+        };
+    }
+    match endpoint.connect().await {
+        Ok(channel) => Ok(channel),
+        Err(err) => Err(ClientConnectionError::new_err(err.to_string())),
+    }
+}
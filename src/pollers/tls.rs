@@ -0,0 +1,371 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs;
+use std::net::IpAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use pem::Pem;
+use pyo3::prelude::*;
+use ring::signature::{
+    ECDSA_P256_SHA256_ASN1_SIGNING,
+    ECDSA_P384_SHA384_ASN1_SIGNING,
+    EcdsaKeyPair,
+    Ed25519KeyPair,
+    KeyPair,
+    RsaKeyPair,
+};
+use temporal_sdk_core::{
+    ClientTlsConfig,
+    TlsConfig,
+};
+use x509_parser::certificate::X509Certificate;
+use x509_parser::extensions::GeneralName;
+use x509_parser::parse_x509_certificate;
+
+use crate::errors::{
+    ConfigError,
+    new_err_with_attributes,
+};
+
+
+// Something wrong with one of the PEM files, "field" being the name of the argument or config key that pointed to it
+pub(crate) enum PemFileError {
+    Invalid {
+        field: &'static str,
+        path: String,
+        message: String,
+    },
+    // File that has to be given together with the one that was, e.g. the client certificate's private key
+    Missing {
+        field: &'static str,
+        given_field: &'static str,
+    },
+}
+
+impl PemFileError {
+    fn new(field: &'static str, path: &str, message: String) -> Self {
+        PemFileError::Invalid {
+            field,
+            path: String::from(path),
+            message,
+        }
+    }
+
+    // ConfigError with the field's name, prefixed with e.g. "gateway.tls.", as its key and the file as its source
+    pub(crate) fn into_py_err(self, py: Python, key_prefix: &str) -> PyErr {
+        match self {
+            PemFileError::Invalid { field, path, message } => {
+                let key = format!("{}{}", key_prefix, field);
+                new_err_with_attributes::<ConfigError>(py, format!("{} ({}): {}", key, path, message), vec![
+                    ("key", key.into_py(py)),
+                    ("source", path.into_py(py)),
+                ])
+            }
+            PemFileError::Missing { field, given_field } => {
+                let key = format!("{}{}", key_prefix, field);
+                new_err_with_attributes::<ConfigError>(py, format!(
+                    "{} is required together with {}{}",
+                    key,
+                    key_prefix,
+                    given_field
+                ), vec![
+                    ("key", key.into_py(py)),
+                ])
+            }
+        }
+    }
+}
+
+impl fmt::Display for PemFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PemFileError::Invalid { field, path, message } => write!(f, "{} ({}): {}", field, path, message),
+            PemFileError::Missing { field, given_field } => write!(f, "{} is required together with {}", field, given_field),
+        }
+    }
+}
+
+
+// Contents of the PEM file together with its parsed blocks
+fn read_pem_file(field: &'static str, path: &str) -> Result<(Vec<u8>, Vec<Pem>), PemFileError> {
+    let contents = fs::read(path).map_err(|err| {
+        PemFileError::new(field, path, format!("unable to read file: {}", err))
+    })?;
+    let blocks = pem::parse_many(&contents);
+    if blocks.is_empty() {
+        return Err(PemFileError::new(field, path, String::from("no PEM blocks found")));
+    }
+    Ok((contents, blocks))
+}
+
+
+fn certificates<'a>(field: &'static str,
+                    path: &str,
+                    blocks: &'a [Pem]) -> Result<Vec<X509Certificate<'a>>, PemFileError> {
+    let mut certificates = Vec::new();
+    for (index, block) in blocks.iter().enumerate() {
+        if block.tag != "CERTIFICATE" {
+            return Err(PemFileError::new(field, path, format!(
+                "PEM block #{} is a \"{}\" rather than a \"CERTIFICATE\"",
+                index + 1,
+                block.tag
+            )));
+        }
+        let (_, certificate) = parse_x509_certificate(&block.contents).map_err(|err| {
+            PemFileError::new(field, path, format!("certificate #{} is invalid: {}", index + 1, err))
+        })?;
+        certificates.push(certificate);
+    }
+    Ok(certificates)
+}
+
+
+fn check_validity_period(field: &'static str, path: &str, certificate: &X509Certificate) -> Result<(), PemFileError> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|now| now.as_secs() as i64).unwrap_or_default();
+    let validity = certificate.validity();
+    if now < validity.not_before.timestamp() {
+        return Err(PemFileError::new(field, path, format!(
+            "certificate for {} is not valid until {}",
+            certificate.subject(),
+            validity.not_before.to_rfc2822()
+        )));
+    }
+    if now > validity.not_after.timestamp() {
+        return Err(PemFileError::new(field, path, format!(
+            "certificate for {} expired on {}",
+            certificate.subject(),
+            validity.not_after.to_rfc2822()
+        )));
+    }
+    Ok(())
+}
+
+
+// "*.example.com" covers "a.example.com" but neither "example.com" nor "a.b.example.com"
+fn dns_name_matches(name: &str, domain: &str) -> bool {
+    let name = name.to_lowercase();
+    let domain = domain.to_lowercase();
+    match name.strip_prefix("*.") {
+        None => name == domain,
+        Some(parent) => match domain.split_once('.') {
+            None => false,
+            Some((label, rest)) => !label.is_empty() && rest == parent,
+        },
+    }
+}
+
+
+fn subject_alternative_names(certificate: &X509Certificate) -> Vec<String> {
+    let san = match certificate.tbs_certificate.subject_alternative_name() {
+        None => return Vec::new(),
+        Some((_critical, san)) => san,
+    };
+    san.general_names.iter().filter_map(|name| match name {
+        GeneralName::DNSName(dns_name) => Some(String::from(*dns_name)),
+        GeneralName::IPAddress(octets) => match octets.len() {
+            4 => Some(IpAddr::from(<[u8; 4]>::try_from(*octets).unwrap()).to_string()),
+            16 => Some(IpAddr::from(<[u8; 16]>::try_from(*octets).unwrap()).to_string()),
+            _ => None,
+        },
+        _ => None,
+    }).collect()
+}
+
+
+fn check_domain(field: &'static str,
+                path: &str,
+                certificate: &X509Certificate,
+                domain: &str) -> Result<(), PemFileError> {
+    let names = subject_alternative_names(certificate);
+    let matches = match domain.parse::<IpAddr>() {
+        Ok(ip) => names.iter().any(|name| name.parse::<IpAddr>().ok() == Some(ip)),
+        Err(_) => names.iter().any(|name| dns_name_matches(name, domain)),
+    };
+    if matches {
+        return Ok(());
+    }
+    Err(PemFileError::new(field, path, format!(
+        "certificate for {} doesn't cover domain {}, its subject alternative names are: {}",
+        certificate.subject(),
+        domain,
+        if names.is_empty() { String::from("(none)") } else { names.join(", ") }
+    )))
+}
+
+
+// Public key of the private key in the same encoding as it has in a certificate's SubjectPublicKeyInfo
+fn public_key_of_private_key(block: &Pem) -> Result<Vec<u8>, String> {
+    match block.tag.as_str() {
+        "RSA PRIVATE KEY" => match RsaKeyPair::from_der(&block.contents) {
+            Ok(key_pair) => Ok(key_pair.public_key().as_ref().to_vec()),
+            Err(err) => Err(format!("invalid RSA private key: {}", err)),
+        },
+        "PRIVATE KEY" => {
+            if let Ok(key_pair) = RsaKeyPair::from_pkcs8(&block.contents) {
+                return Ok(key_pair.public_key().as_ref().to_vec());
+            }
+            for algorithm in [&ECDSA_P256_SHA256_ASN1_SIGNING, &ECDSA_P384_SHA384_ASN1_SIGNING] {
+                if let Ok(key_pair) = EcdsaKeyPair::from_pkcs8(algorithm, &block.contents) {
+                    return Ok(key_pair.public_key().as_ref().to_vec());
+                }
+            }
+            match Ed25519KeyPair::from_pkcs8_maybe_unchecked(&block.contents) {
+                Ok(key_pair) => Ok(key_pair.public_key().as_ref().to_vec()),
+                Err(_) => Err(String::from(
+                    "unsupported or invalid PKCS#8 private key, expected an RSA (2048 bits or more), ECDSA P-256, \
+                    ECDSA P-384 or Ed25519 one"
+                )),
+            }
+        }
+        "EC PRIVATE KEY" => Err(String::from(
+            "SEC1 EC private keys aren't supported, convert it to PKCS#8 with \"openssl pkcs8 -topk8 -nocrypt\""
+        )),
+        "ENCRYPTED PRIVATE KEY" => Err(String::from("encrypted private keys aren't supported")),
+        tag => Err(format!("expected a private key, got a \"{}\" PEM block", tag)),
+    }
+}
+
+
+// Root CA certificate(s) to verify the server with; when the file holds the server's own certificate rather than a
+// CA's, the domain gets checked against its subject alternative names, which is the only way to catch a wrong domain
+// before connecting
+pub(crate) fn read_server_root_ca_cert(field: &'static str,
+                                       path: &str,
+                                       domain: Option<&str>) -> Result<Vec<u8>, PemFileError> {
+    let (contents, blocks) = read_pem_file(field, path)?;
+    let certificates = certificates(field, path, &blocks)?;
+    for certificate in &certificates {
+        check_validity_period(field, path, certificate)?;
+    }
+    if let (Some(domain), [certificate]) = (domain, certificates.as_slice()) {
+        let is_ca = match certificate.tbs_certificate.basic_constraints() {
+            None => false,
+            Some((_critical, basic_constraints)) => basic_constraints.ca,
+        };
+        if !is_ca {
+            check_domain(field, path, certificate, domain)?;
+        }
+    }
+    Ok(contents)
+}
+
+
+// Client certificate chain, leaf first, and its private key which has to be the leaf's
+pub(crate) fn read_client_tls_config(cert_path: &str, key_path: &str) -> Result<ClientTlsConfig, PemFileError> {
+    let cert_field = "client_cert";
+    let key_field = "client_private_key";
+    let (cert_contents, cert_blocks) = read_pem_file(cert_field, cert_path)?;
+    let certificates = certificates(cert_field, cert_path, &cert_blocks)?;
+    for certificate in &certificates {
+        check_validity_period(cert_field, cert_path, certificate)?;
+    }
+
+    let (key_contents, key_blocks) = read_pem_file(key_field, key_path)?;
+    let key_block = match key_blocks.as_slice() {
+        [key_block] => key_block,
+        _ => return Err(PemFileError::new(key_field, key_path, format!(
+            "expected a single private key, got {} PEM blocks",
+            key_blocks.len()
+        ))),
+    };
+    let public_key = public_key_of_private_key(key_block).map_err(|message| {
+        PemFileError::new(key_field, key_path, message)
+    })?;
+
+    let leaf = &certificates[0];
+    if leaf.public_key().subject_public_key.data != public_key.as_slice() {
+        return Err(PemFileError::new(key_field, key_path, format!(
+            "private key doesn't belong to the certificate for {} in {}",
+            leaf.subject(),
+            cert_path
+        )));
+    }
+
+    Ok(ClientTlsConfig {
+        client_cert: cert_contents,
+        client_private_key: key_contents,
+    })
+}
+
+
+// Files that a TLS config got read from, kept around so that the config can be read again once they change
+#[derive(Clone)]
+pub(crate) struct TlsFiles {
+    pub(crate) server_root_ca_cert: Option<String>,
+    pub(crate) domain: Option<String>,
+    pub(crate) client_cert: Option<String>,
+    pub(crate) client_private_key: Option<String>,
+}
+
+impl TlsFiles {
+    pub(crate) fn paths(&self) -> Vec<&str> {
+        vec![&self.server_root_ca_cert, &self.client_cert, &self.client_private_key].into_iter()
+            .filter_map(|path| path.as_deref())
+            .collect()
+    }
+
+    // Raw contents of every file, None for the ones that can't be read, to tell whether any of them has changed
+    pub(crate) fn contents(&self) -> Vec<Option<Vec<u8>>> {
+        self.paths().into_iter().map(|path| fs::read(path).ok()).collect()
+    }
+
+    pub(crate) fn read(&self) -> Result<TlsConfig, PemFileError> {
+        let server_root_ca_cert = match &self.server_root_ca_cert {
+            None => None,
+            Some(path) => Some(read_server_root_ca_cert("server_root_ca_cert", path, self.domain.as_deref())?),
+        };
+        // Client certificate and key are either both set or neither is
+        let client_tls_config = match (&self.client_cert, &self.client_private_key) {
+            (None, None) => None,
+            (Some(client_cert_path), Some(client_private_key_path)) => {
+                Some(read_client_tls_config(client_cert_path, client_private_key_path)?)
+            }
+            (Some(_), None) => return Err(PemFileError::Missing {
+                field: "client_private_key",
+                given_field: "client_cert",
+            }),
+            (None, Some(_)) => return Err(PemFileError::Missing {
+                field: "client_cert",
+                given_field: "client_private_key",
+            }),
+        };
+        Ok(TlsConfig {
+            server_root_ca_cert,
+            domain: self.domain.clone(),
+            client_tls_config,
+        })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_names_match_case_insensitively() {
+        assert!(dns_name_matches("example.com", "example.com"));
+        assert!(dns_name_matches("Example.COM", "example.com"));
+        assert!(dns_name_matches("example.com", "EXAMPLE.com"));
+        assert!(!dns_name_matches("example.com", "www.example.com"));
+        assert!(!dns_name_matches("www.example.com", "example.com"));
+    }
+
+    #[test]
+    fn wildcards_cover_a_single_label() {
+        assert!(dns_name_matches("*.example.com", "a.example.com"));
+        assert!(dns_name_matches("*.EXAMPLE.com", "A.example.COM"));
+        assert!(!dns_name_matches("*.example.com", "example.com"));
+        assert!(!dns_name_matches("*.example.com", "a.b.example.com"));
+        assert!(!dns_name_matches("*.example.com", ".example.com"));
+        assert!(!dns_name_matches("*.example.com", "a.example.org"));
+        assert!(!dns_name_matches("*.example.com", "localhost"));
+    }
+
+    #[test]
+    fn wildcards_only_count_as_the_whole_leftmost_label() {
+        assert!(!dns_name_matches("a*.example.com", "ab.example.com"));
+        assert!(!dns_name_matches("a.*.example.com", "a.b.example.com"));
+        assert!(dns_name_matches("a*.example.com", "a*.example.com"));
+    }
+}
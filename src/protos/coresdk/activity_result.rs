@@ -1,4 +1,6 @@
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyTuple;
 
 use temporal_sdk_core::protos::coresdk::{
     activity_result::{
@@ -19,19 +21,41 @@ use crate::protos::coresdk::common::{
     WrappedUserCodeFailure,
 };
 
-#[pyclass(name = "Cancelation")]
+#[pyclass(name = "Status", subclass)]
+#[derive(Clone)]
+pub struct WrappedStatusBase {}
+
+
+#[pyclass(name = "Cancelation", extends = WrappedStatusBase)]
 #[derive(Clone)]
 pub struct WrappedCancelation {
+    #[pyo3(get, set)]
     pub details: Option<WrappedPayload>,
 }
 
 #[pymethods]
 impl WrappedCancelation {
     #[new]
-    fn new(details: Option<WrappedPayload>) -> Self {
-        WrappedCancelation {
+    fn new(details: Option<WrappedPayload>) -> (Self, WrappedStatusBase) {
+        (WrappedCancelation {
             details,
-        }
+        }, WrappedStatusBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "canceled"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["details"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedCancelation {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedStatusBase {})).unwrap().into_py(py)
     }
 }
 
@@ -59,19 +83,36 @@ impl From<WrappedCancelation> for Cancelation {
 }
 
 
-#[pyclass(name = "Success")]
+#[pyclass(name = "Success", extends = WrappedStatusBase)]
 #[derive(Clone)]
 pub struct WrappedSuccess {
+    #[pyo3(get, set)]
     pub result: Option<WrappedPayload>,
 }
 
 #[pymethods]
 impl WrappedSuccess {
     #[new]
-    fn new(result: Option<WrappedPayload>) -> Self {
-        WrappedSuccess {
+    fn new(result: Option<WrappedPayload>) -> (Self, WrappedStatusBase) {
+        (WrappedSuccess {
             result,
-        }
+        }, WrappedStatusBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "completed"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["result"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedSuccess {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedStatusBase {})).unwrap().into_py(py)
     }
 }
 
@@ -100,19 +141,36 @@ impl From<WrappedSuccess> for Success {
 }
 
 
-#[pyclass(name = "Failure")]
+#[pyclass(name = "Failure", extends = WrappedStatusBase)]
 #[derive(Clone)]
 pub struct WrappedFailure {
+    #[pyo3(get, set)]
     pub failure: Option<WrappedUserCodeFailure>,
 }
 
 #[pymethods]
 impl WrappedFailure {
     #[new]
-    fn new(failure: Option<WrappedUserCodeFailure>) -> Self {
-        WrappedFailure {
+    fn new(failure: Option<WrappedUserCodeFailure>) -> (Self, WrappedStatusBase) {
+        (WrappedFailure {
             failure,
-        }
+        }, WrappedStatusBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "failed"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["failure"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedFailure {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedStatusBase {})).unwrap().into_py(py)
     }
 }
 
@@ -140,24 +198,36 @@ impl From<WrappedFailure> for Failure {
 }
 
 
-#[pyclass(name = "Status")]
 #[derive(Clone)]
-pub struct WrappedStatus {
-    pub completed: Option<WrappedSuccess>,
-    pub failed: Option<WrappedFailure>,
-    pub canceled: Option<WrappedCancelation>,
+pub enum WrappedStatus {
+    Completed(WrappedSuccess),
+    Failed(WrappedFailure),
+    Canceled(WrappedCancelation),
 }
 
-#[pymethods]
-impl WrappedStatus {
-    #[new]
-    fn new(completed: Option<WrappedSuccess>,
-           failed: Option<WrappedFailure>,
-           canceled: Option<WrappedCancelation>) -> Self {
-        WrappedStatus {
-            completed,
-            failed,
-            canceled,
+impl<'source> FromPyObject<'source> for WrappedStatus {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(completed) = ob.extract::<WrappedSuccess>() {
+            Ok(WrappedStatus::Completed(completed))
+        } else if let Ok(failed) = ob.extract::<WrappedFailure>() {
+            Ok(WrappedStatus::Failed(failed))
+        } else if let Ok(canceled) = ob.extract::<WrappedCancelation>() {
+            Ok(WrappedStatus::Canceled(canceled))
+        } else {
+            Err(PyValueError::new_err(format!(
+                "ActivityResult.status must be one of Success, Failure or Cancelation, got {}",
+                ob.get_type().name()?
+            )))
+        }
+    }
+}
+
+impl IntoPy<PyObject> for WrappedStatus {
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            WrappedStatus::Completed(completed) => completed.into_py(py),
+            WrappedStatus::Failed(failed) => failed.into_py(py),
+            WrappedStatus::Canceled(canceled) => canceled.into_py(py),
         }
     }
 }
@@ -167,25 +237,13 @@ impl From<activity_result::Status> for WrappedStatus {
     fn from(i: activity_result::Status) -> Self {
         match i {
             activity_result::Status::Completed(completed_status) => {
-                WrappedStatus {
-                    completed: Some(WrappedSuccess::from(completed_status)),
-                    failed: None,
-                    canceled: None,
-                }
+                WrappedStatus::Completed(WrappedSuccess::from(completed_status))
             }
             activity_result::Status::Failed(failed_status) => {
-                WrappedStatus {
-                    completed: None,
-                    failed: Some(WrappedFailure::from(failed_status)),
-                    canceled: None,
-                }
+                WrappedStatus::Failed(WrappedFailure::from(failed_status))
             }
             activity_result::Status::Canceled(canceled_status) => {
-                WrappedStatus {
-                    completed: None,
-                    failed: None,
-                    canceled: Some(WrappedCancelation::from(canceled_status)),
-                }
+                WrappedStatus::Canceled(WrappedCancelation::from(canceled_status))
             }
         }
     }
@@ -194,14 +252,16 @@ impl From<activity_result::Status> for WrappedStatus {
 
 impl From<WrappedStatus> for activity_result::Status {
     fn from(i: WrappedStatus) -> Self {
-        if let Some(completed) = i.completed {
-            activity_result::Status::Completed(Success::from(completed))
-        } else if let Some(failed) = i.failed {
-            activity_result::Status::Failed(Failure::from(failed))
-        } else if let Some(canceled) = i.canceled {
-            activity_result::Status::Canceled(Cancelation::from(canceled))
-        } else {
-            panic!("Only one of 'completed', 'failed' and 'canceled' must be set")
+        match i {
+            WrappedStatus::Completed(completed) => {
+                activity_result::Status::Completed(Success::from(completed))
+            }
+            WrappedStatus::Failed(failed) => {
+                activity_result::Status::Failed(Failure::from(failed))
+            }
+            WrappedStatus::Canceled(canceled) => {
+                activity_result::Status::Canceled(Cancelation::from(canceled))
+            }
         }
     }
 }
@@ -210,6 +270,7 @@ impl From<WrappedStatus> for activity_result::Status {
 #[pyclass(name = "ActivityResult")]
 #[derive(Clone)]
 pub struct WrappedActivityResult {
+    #[pyo3(get, set)]
     pub status: Option<WrappedStatus>,
 }
 
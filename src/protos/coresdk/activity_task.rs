@@ -2,6 +2,9 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyTuple;
+use pyo3::types::PyBytes;
 use pyo3_chrono;
 
 
@@ -29,30 +32,51 @@ use crate::utils::{
     hashmap_of_string_wrapped_payloads_to_hashmap_of_string_payloads,
     vec_of_payloads_to_vec_of_wrapped_payloads,
     vec_of_wrapped_payloads_to_vec_of_payloads,
-    prost_types_timestamp_to_u128,
-    u128_to_prost_types_timestamp,
+    UtcDateTime,
+    prost_types_timestamp_to_utc_datetime,
+    utc_datetime_to_prost_types_timestamp,
     prost_duration_to_pyo3_chrono_duration,
     pyo3_chrono_duration_to_prost_duration,
 };
 
 
-#[pyclass(name = "Start")]
+#[pyclass(name = "Variant", subclass)]
+#[derive(Clone)]
+pub struct WrappedVariantBase {}
+
+
+#[pyclass(name = "Start", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedStart {
+    #[pyo3(get, set)]
     pub workflow_namespace: String,
+    #[pyo3(get, set)]
     pub workflow_type: String,
+    #[pyo3(get, set)]
     pub workflow_execution: Option<WrappedWorkflowExecution>,
+    #[pyo3(get, set)]
     pub activity_type: String,
+    #[pyo3(get, set)]
     pub header_fields: HashMap<String, WrappedPayload>,
+    #[pyo3(get, set)]
     pub input: Vec<WrappedPayload>,
+    #[pyo3(get, set)]
     pub heartbeat_details: Vec<WrappedPayload>,
-    pub scheduled_time: Option<u128>,
-    pub current_attempt_scheduled_time: Option<u128>,
-    pub started_time: Option<u128>,
+    #[pyo3(get, set)]
+    pub scheduled_time: Option<UtcDateTime>,
+    #[pyo3(get, set)]
+    pub current_attempt_scheduled_time: Option<UtcDateTime>,
+    #[pyo3(get, set)]
+    pub started_time: Option<UtcDateTime>,
+    #[pyo3(get, set)]
     pub attempt: i32,
+    #[pyo3(get, set)]
     pub schedule_to_close_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub start_to_close_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub heartbeat_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub retry_policy: Option<WrappedRetryPolicy>,
 }
 
@@ -66,15 +90,15 @@ impl WrappedStart {
            header_fields: HashMap<String, WrappedPayload>,
            input: Vec<WrappedPayload>,
            heartbeat_details: Vec<WrappedPayload>,
-           scheduled_time: Option<u128>,
-           current_attempt_scheduled_time: Option<u128>,
-           started_time: Option<u128>,
+           scheduled_time: Option<UtcDateTime>,
+           current_attempt_scheduled_time: Option<UtcDateTime>,
+           started_time: Option<UtcDateTime>,
            attempt: i32,
            schedule_to_close_timeout: Option<pyo3_chrono::Duration>,
            start_to_close_timeout: Option<pyo3_chrono::Duration>,
            heartbeat_timeout: Option<pyo3_chrono::Duration>,
-           retry_policy: Option<WrappedRetryPolicy>) -> Self {
-        WrappedStart {
+           retry_policy: Option<WrappedRetryPolicy>) -> (Self, WrappedVariantBase) {
+        (WrappedStart {
             workflow_namespace,
             workflow_type,
             workflow_execution,
@@ -90,7 +114,23 @@ impl WrappedStart {
             start_to_close_timeout,
             heartbeat_timeout,
             retry_policy,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "start"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["workflow_namespace", "workflow_type", "workflow_execution", "activity_type", "header_fields", "input", "heartbeat_details", "scheduled_time", "current_attempt_scheduled_time", "started_time", "attempt", "schedule_to_close_timeout", "start_to_close_timeout", "heartbeat_timeout", "retry_policy"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedStart {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -110,9 +150,9 @@ impl TryFrom<Start> for WrappedStart {
             header_fields: hashmap_of_string_payloads_to_hashmap_of_string_wrapped_payloads(i.header_fields),
             input: vec_of_payloads_to_vec_of_wrapped_payloads(i.input),
             heartbeat_details: vec_of_payloads_to_vec_of_wrapped_payloads(i.heartbeat_details),
-            scheduled_time: prost_types_timestamp_to_u128(i.scheduled_time),
-            current_attempt_scheduled_time: prost_types_timestamp_to_u128(i.current_attempt_scheduled_time),
-            started_time: prost_types_timestamp_to_u128(i.started_time),
+            scheduled_time: prost_types_timestamp_to_utc_datetime(i.scheduled_time)?,
+            current_attempt_scheduled_time: prost_types_timestamp_to_utc_datetime(i.current_attempt_scheduled_time)?,
+            started_time: prost_types_timestamp_to_utc_datetime(i.started_time)?,
             attempt: i.attempt,
             schedule_to_close_timeout: prost_duration_to_pyo3_chrono_duration(i.schedule_to_close_timeout)?,
             start_to_close_timeout: prost_duration_to_pyo3_chrono_duration(i.start_to_close_timeout)?,
@@ -141,9 +181,9 @@ impl TryFrom<WrappedStart> for Start {
             header_fields: hashmap_of_string_wrapped_payloads_to_hashmap_of_string_payloads(i.header_fields),
             input: vec_of_wrapped_payloads_to_vec_of_payloads(i.input),
             heartbeat_details: vec_of_wrapped_payloads_to_vec_of_payloads(i.heartbeat_details),
-            scheduled_time: u128_to_prost_types_timestamp(i.scheduled_time),
-            current_attempt_scheduled_time: u128_to_prost_types_timestamp(i.current_attempt_scheduled_time),
-            started_time: u128_to_prost_types_timestamp(i.started_time),
+            scheduled_time: utc_datetime_to_prost_types_timestamp(i.scheduled_time),
+            current_attempt_scheduled_time: utc_datetime_to_prost_types_timestamp(i.current_attempt_scheduled_time),
+            started_time: utc_datetime_to_prost_types_timestamp(i.started_time),
             attempt: i.attempt,
             schedule_to_close_timeout: pyo3_chrono_duration_to_prost_duration(i.schedule_to_close_timeout)?,
             start_to_close_timeout: pyo3_chrono_duration_to_prost_duration(i.start_to_close_timeout)?,
@@ -156,20 +196,37 @@ impl TryFrom<WrappedStart> for Start {
     }
 }
 
-#[pyclass(name = "Cancel")]
+#[pyclass(name = "Cancel", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedCancel {
     // FIXME ActivityCancelReason
+    #[pyo3(get, set)]
     pub reason: i32,
 }
 
 #[pymethods]
 impl WrappedCancel {
     #[new]
-    fn new(reason: i32) -> Self {
-        WrappedCancel {
+    fn new(reason: i32) -> (Self, WrappedVariantBase) {
+        (WrappedCancel {
             reason,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "cancel"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["reason"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedCancel {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -192,25 +249,35 @@ impl From<WrappedCancel> for Cancel {
 }
 
 
-#[pyclass(name = "Variant")]
 #[derive(Clone)]
-pub struct WrappedVariant {
-    pub start: Option<WrappedStart>,
-    pub cancel: Option<WrappedCancel>,
+pub enum WrappedVariant {
+    Start(WrappedStart),
+    Cancel(WrappedCancel),
 }
 
-#[pymethods]
-impl WrappedVariant {
-    #[new]
-    fn new(start: Option<WrappedStart>,
-           cancel: Option<WrappedCancel>) -> Self {
-        WrappedVariant {
-            start,
-            cancel,
+impl<'source> FromPyObject<'source> for WrappedVariant {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(start) = ob.extract::<WrappedStart>() {
+            Ok(WrappedVariant::Start(start))
+        } else if let Ok(cancel) = ob.extract::<WrappedCancel>() {
+            Ok(WrappedVariant::Cancel(cancel))
+        } else {
+            Err(PyValueError::new_err(format!(
+                "ActivityTask.variant must be one of Start or Cancel, got {}",
+                ob.get_type().name()?
+            )))
         }
     }
 }
 
+impl IntoPy<PyObject> for WrappedVariant {
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            WrappedVariant::Start(start) => start.into_py(py),
+            WrappedVariant::Cancel(cancel) => cancel.into_py(py),
+        }
+    }
+}
 
 impl TryFrom<activity_task::Variant> for WrappedVariant {
     type Error = PyErr;
@@ -218,16 +285,10 @@ impl TryFrom<activity_task::Variant> for WrappedVariant {
     fn try_from(i: activity_task::Variant) -> Result<Self, Self::Error> {
         Ok(match i {
             activity_task::Variant::Start(task) => {
-                WrappedVariant {
-                    start: Some(WrappedStart::try_from(task)?),
-                    cancel: None,
-                }
+                WrappedVariant::Start(WrappedStart::try_from(task)?)
             }
             activity_task::Variant::Cancel(task) => {
-                WrappedVariant {
-                    start: None,
-                    cancel: Some(WrappedCancel::from(task)),
-                }
+                WrappedVariant::Cancel(WrappedCancel::from(task))
             }
         })
     }
@@ -237,15 +298,14 @@ impl TryFrom<WrappedVariant> for activity_task::Variant {
     type Error = PyErr;
 
     fn try_from(i: WrappedVariant) -> Result<Self, Self::Error> {
-        Ok(
-            if let Some(start) = i.start {
+        Ok(match i {
+            WrappedVariant::Start(start) => {
                 activity_task::Variant::Start(Start::try_from(start)?)
-            } else if let Some(cancel) = i.cancel {
-                activity_task::Variant::Cancel(Cancel::try_from(cancel)?)
-            } else {
-                panic!("Only one of 'start' and 'cancel' must be set")
             }
-        )
+            WrappedVariant::Cancel(cancel) => {
+                activity_task::Variant::Cancel(Cancel::from(cancel))
+            }
+        })
     }
 }
 
@@ -254,7 +314,9 @@ impl TryFrom<WrappedVariant> for activity_task::Variant {
 #[derive(Clone)]
 pub struct WrappedActivityTask {
     pub task_token: Vec<u8>,
+    #[pyo3(get, set)]
     pub activity_id: String,
+    #[pyo3(get, set)]
     pub variant: Option<WrappedVariant>,
 }
 
@@ -270,6 +332,16 @@ impl WrappedActivityTask {
             variant,
         }
     }
+
+    #[getter]
+    fn get_task_token<'p>(&self, py: Python<'p>) -> &'p PyBytes {
+        PyBytes::new(py, &self.task_token)
+    }
+
+    #[setter]
+    fn set_task_token(&mut self, task_token: Vec<u8>) {
+        self.task_token = task_token;
+    }
 }
 
 
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::convert::TryFrom;
 
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
 use pyo3_chrono;
 
 use temporal_sdk_core::protos::coresdk::common::{
@@ -20,10 +21,15 @@ use crate::utils::{
 #[pyclass(name = "UserCodeFailure")]
 #[derive(Clone)]
 pub struct WrappedUserCodeFailure {
+    #[pyo3(get, set)]
     pub message: String,
+    #[pyo3(get, set)]
     pub r#type: String,
+    #[pyo3(get, set)]
     pub source: String,
+    #[pyo3(get, set)]
     pub stack_trace: String,
+    #[pyo3(get, set)]
     pub non_retryable: bool,
     pub cause: Option<Box<WrappedUserCodeFailure>>,
 }
@@ -49,6 +55,22 @@ impl WrappedUserCodeFailure {
             },
         }
     }
+
+    #[getter]
+    fn get_cause(&self) -> Option<WrappedUserCodeFailure> {
+        match &self.cause {
+            None => None,
+            Some(cause) => Some(*cause.clone()),
+        }
+    }
+
+    #[setter]
+    fn set_cause(&mut self, cause: Option<WrappedUserCodeFailure>) {
+        self.cause = match cause {
+            None => None,
+            Some(cause) => Some(Box::new(cause)),
+        };
+    }
 }
 
 
@@ -102,6 +124,29 @@ impl WrappedPayload {
             data,
         }
     }
+
+    #[getter]
+    fn get_metadata<'p>(&self, py: Python<'p>) -> HashMap<String, &'p PyBytes> {
+        self.metadata.iter().map(|(k, v)| (
+            String::from(k),
+            PyBytes::new(py, v)
+        )).collect()
+    }
+
+    #[setter]
+    fn set_metadata(&mut self, metadata: HashMap<String, Vec<u8>>) {
+        self.metadata = metadata;
+    }
+
+    #[getter]
+    fn get_data<'p>(&self, py: Python<'p>) -> &'p PyBytes {
+        PyBytes::new(py, &self.data)
+    }
+
+    #[setter]
+    fn set_data(&mut self, data: Vec<u8>) {
+        self.data = data;
+    }
 }
 
 impl From<Payload> for WrappedPayload {
@@ -145,7 +190,9 @@ impl From<&WrappedPayload> for Payload {
 #[pyclass(name = "WorkflowExecution")]
 #[derive(Clone)]
 pub struct WrappedWorkflowExecution {
+    #[pyo3(get, set)]
     pub workflow_id: String,
+    #[pyo3(get, set)]
     pub run_id: String,
 }
 
@@ -183,10 +230,15 @@ impl From<WrappedWorkflowExecution> for WorkflowExecution {
 #[pyclass(name = "RetryPolicy")]
 #[derive(Clone)]
 pub struct WrappedRetryPolicy {
+    #[pyo3(get, set)]
     pub initial_interval: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub backoff_coefficient: f64,
+    #[pyo3(get, set)]
     pub maximum_interval: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub maximum_attempts: i32,
+    #[pyo3(get, set)]
     pub non_retryable_error_types: Vec<String>,
 }
 
@@ -1,6 +1,7 @@
 // FIXME move to coresdk
 
 use pyo3::prelude::*;
+use pyo3::types::PyBytes;
 
 use temporal_sdk_core::protos::coresdk::{
     ActivityTaskCompletion,
@@ -27,7 +28,9 @@ pub(crate) mod workflow_completion;
 #[derive(Clone)]
 pub struct WrappedActivityHeartbeat {
     pub task_token: Vec<u8>,
+    #[pyo3(get, set)]
     pub task_queue: String,
+    #[pyo3(get, set)]
     pub details: Vec<WrappedPayload>,
 }
 
@@ -43,6 +46,16 @@ impl WrappedActivityHeartbeat {
             details,
         }
     }
+
+    #[getter]
+    fn get_task_token<'p>(&self, py: Python<'p>) -> &'p PyBytes {
+        PyBytes::new(py, &self.task_token)
+    }
+
+    #[setter]
+    fn set_task_token(&mut self, task_token: Vec<u8>) {
+        self.task_token = task_token;
+    }
 }
 
 impl From<ActivityHeartbeat> for WrappedActivityHeartbeat {
@@ -70,7 +83,9 @@ impl From<WrappedActivityHeartbeat> for ActivityHeartbeat {
 #[derive(Clone)]
 pub struct WrappedActivityTaskCompletion {
     pub task_token: Vec<u8>,
+    #[pyo3(get, set)]
     pub task_queue: String,
+    #[pyo3(get, set)]
     pub result: Option<WrappedActivityResult>,
 }
 
@@ -86,6 +101,16 @@ impl WrappedActivityTaskCompletion {
             result,
         }
     }
+
+    #[getter]
+    fn get_task_token<'p>(&self, py: Python<'p>) -> &'p PyBytes {
+        PyBytes::new(py, &self.task_token)
+    }
+
+    #[setter]
+    fn set_task_token(&mut self, task_token: Vec<u8>) {
+        self.task_token = task_token;
+    }
 }
 
 impl From<ActivityTaskCompletion> for WrappedActivityTaskCompletion {
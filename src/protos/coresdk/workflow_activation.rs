@@ -1,6 +1,9 @@
 use std::collections::HashMap;
+use std::convert::TryFrom;
 
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyTuple;
 use temporal_sdk_core::protos::coresdk::{
     workflow_activation::{
         StartWorkflow,
@@ -22,21 +25,32 @@ use crate::protos::coresdk::{
     activity_result::WrappedActivityResult,
 };
 use crate::utils::{
-    prost_types_timestamp_to_u128,
-    u128_to_prost_types_timestamp,
+    UtcDateTime,
+    prost_types_timestamp_to_utc_datetime,
+    utc_datetime_to_prost_types_timestamp,
     vec_of_payloads_to_vec_of_wrapped_payloads,
     vec_of_wrapped_payloads_to_vec_of_payloads,
     hashmap_of_string_payloads_to_hashmap_of_string_wrapped_payloads,
     hashmap_of_string_wrapped_payloads_to_hashmap_of_string_payloads,
 };
 
-#[pyclass(name = "StartWorkflow")]
+#[pyclass(name = "Variant", subclass)]
+#[derive(Clone)]
+pub struct WrappedVariantBase {}
+
+
+#[pyclass(name = "StartWorkflow", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedStartWorkflow {
+    #[pyo3(get, set)]
     pub workflow_type: String,
+    #[pyo3(get, set)]
     pub workflow_id: String,
+    #[pyo3(get, set)]
     pub arguments: Vec<WrappedPayload>,
+    #[pyo3(get, set)]
     pub randomness_seed: u64,
+    #[pyo3(get, set)]
     pub headers: HashMap<String, WrappedPayload>,
 }
 
@@ -47,14 +61,30 @@ impl WrappedStartWorkflow {
            workflow_id: String,
            arguments: Vec<WrappedPayload>,
            randomness_seed: u64,
-           headers: HashMap<String, WrappedPayload>) -> Self {
-        WrappedStartWorkflow {
+           headers: HashMap<String, WrappedPayload>) -> (Self, WrappedVariantBase) {
+        (WrappedStartWorkflow {
             workflow_type,
             workflow_id,
             arguments,
             randomness_seed,
             headers,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "start_workflow"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["workflow_type", "workflow_id", "arguments", "randomness_seed", "headers"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedStartWorkflow {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -85,19 +115,36 @@ impl From<WrappedStartWorkflow> for StartWorkflow {
 }
 
 
-#[pyclass(name = "FireTimer")]
+#[pyclass(name = "FireTimer", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedFireTimer {
+    #[pyo3(get, set)]
     pub timer_id: String,
 }
 
 #[pymethods]
 impl WrappedFireTimer {
     #[new]
-    fn new(timer_id: String) -> Self {
-        WrappedFireTimer {
+    fn new(timer_id: String) -> (Self, WrappedVariantBase) {
+        (WrappedFireTimer {
             timer_id,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "fire_timer"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["timer_id"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedFireTimer {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -119,19 +166,36 @@ impl From<WrappedFireTimer> for FireTimer {
 }
 
 
-#[pyclass(name = "UpdateRandomSeed")]
+#[pyclass(name = "UpdateRandomSeed", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedUpdateRandomSeed {
+    #[pyo3(get, set)]
     pub randomness_seed: u64,
 }
 
 #[pymethods]
 impl WrappedUpdateRandomSeed {
     #[new]
-    fn new(randomness_seed: u64) -> Self {
-        WrappedUpdateRandomSeed {
+    fn new(randomness_seed: u64) -> (Self, WrappedVariantBase) {
+        (WrappedUpdateRandomSeed {
             randomness_seed,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "update_random_seed"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["randomness_seed"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedUpdateRandomSeed {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -153,11 +217,14 @@ impl From<WrappedUpdateRandomSeed> for UpdateRandomSeed {
 }
 
 
-#[pyclass(name = "QueryWorkflow")]
+#[pyclass(name = "QueryWorkflow", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedQueryWorkflow {
+    #[pyo3(get, set)]
     pub query_id: String,
+    #[pyo3(get, set)]
     pub query_type: String,
+    #[pyo3(get, set)]
     pub arguments: Vec<WrappedPayload>,
 }
 
@@ -166,12 +233,28 @@ impl WrappedQueryWorkflow {
     #[new]
     fn new(query_id: String,
            query_type: String,
-           arguments: Vec<WrappedPayload>) -> Self {
-        WrappedQueryWorkflow {
+           arguments: Vec<WrappedPayload>) -> (Self, WrappedVariantBase) {
+        (WrappedQueryWorkflow {
             query_id,
             query_type,
             arguments,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "query_workflow"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["query_id", "query_type", "arguments"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedQueryWorkflow {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -196,19 +279,36 @@ impl From<WrappedQueryWorkflow> for QueryWorkflow {
 }
 
 
-#[pyclass(name = "CancelWorkflow")]
+#[pyclass(name = "CancelWorkflow", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedCancelWorkflow {
+    #[pyo3(get, set)]
     pub details: Vec<WrappedPayload>,
 }
 
 #[pymethods]
 impl WrappedCancelWorkflow {
     #[new]
-    fn new(details: Vec<WrappedPayload>) -> Self {
-        WrappedCancelWorkflow {
+    fn new(details: Vec<WrappedPayload>) -> (Self, WrappedVariantBase) {
+        (WrappedCancelWorkflow {
             details
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "cancel_workflow"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["details"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedCancelWorkflow {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -231,11 +331,14 @@ impl From<WrappedCancelWorkflow> for CancelWorkflow {
 }
 
 
-#[pyclass(name = "SignalWorkflow")]
+#[pyclass(name = "SignalWorkflow", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedSignalWorkflow {
+    #[pyo3(get, set)]
     pub signal_name: String,
+    #[pyo3(get, set)]
     pub input: Vec<WrappedPayload>,
+    #[pyo3(get, set)]
     pub identity: String,
 }
 
@@ -244,12 +347,28 @@ impl WrappedSignalWorkflow {
     #[new]
     fn new(signal_name: String,
            input: Vec<WrappedPayload>,
-           identity: String) -> Self {
-        WrappedSignalWorkflow {
+           identity: String) -> (Self, WrappedVariantBase) {
+        (WrappedSignalWorkflow {
             signal_name,
             input,
             identity,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "signal_workflow"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["signal_name", "input", "identity"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedSignalWorkflow {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -274,10 +393,12 @@ impl From<WrappedSignalWorkflow> for SignalWorkflow {
 }
 
 
-#[pyclass(name = "ResolveActivity")]
+#[pyclass(name = "ResolveActivity", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedResolveActivity {
+    #[pyo3(get, set)]
     pub activity_id: String,
+    #[pyo3(get, set)]
     pub result: Option<WrappedActivityResult>,
 }
 
@@ -285,11 +406,27 @@ pub struct WrappedResolveActivity {
 impl WrappedResolveActivity {
     #[new]
     fn new(activity_id: String,
-           result: Option<WrappedActivityResult>) -> Self {
-        WrappedResolveActivity {
+           result: Option<WrappedActivityResult>) -> (Self, WrappedVariantBase) {
+        (WrappedResolveActivity {
             activity_id,
             result,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "resolve_activity"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["activity_id", "result"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedResolveActivity {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -319,39 +456,91 @@ impl From<WrappedResolveActivity> for ResolveActivity {
 }
 
 
-#[pyclass(name = "Variant")]
+#[pyclass(name = "RemoveFromCache", extends = WrappedVariantBase)]
 #[derive(Clone)]
-pub struct WrappedVariant {
-    pub start_workflow: Option<WrappedStartWorkflow>,
-    pub fire_timer: Option<WrappedFireTimer>,
-    pub update_random_seed: Option<WrappedUpdateRandomSeed>,
-    pub query_workflow: Option<WrappedQueryWorkflow>,
-    pub cancel_workflow: Option<WrappedCancelWorkflow>,
-    pub signal_workflow: Option<WrappedSignalWorkflow>,
-    pub resolve_activity: Option<WrappedResolveActivity>,
-    pub remove_from_cache: Option<bool>,
+pub struct WrappedRemoveFromCache {
+    #[pyo3(get, set)]
+    pub remove_from_cache: bool,
 }
 
 #[pymethods]
-impl WrappedVariant {
+impl WrappedRemoveFromCache {
     #[new]
-    fn new(start_workflow: Option<WrappedStartWorkflow>,
-           fire_timer: Option<WrappedFireTimer>,
-           update_random_seed: Option<WrappedUpdateRandomSeed>,
-           query_workflow: Option<WrappedQueryWorkflow>,
-           cancel_workflow: Option<WrappedCancelWorkflow>,
-           signal_workflow: Option<WrappedSignalWorkflow>,
-           resolve_activity: Option<WrappedResolveActivity>,
-           remove_from_cache: Option<bool>) -> Self {
-        WrappedVariant {
-            start_workflow,
-            fire_timer,
-            update_random_seed,
-            query_workflow,
-            cancel_workflow,
-            signal_workflow,
-            resolve_activity,
+    fn new(remove_from_cache: bool) -> (Self, WrappedVariantBase) {
+        (WrappedRemoveFromCache {
             remove_from_cache,
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "remove_from_cache"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["remove_from_cache"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedRemoveFromCache {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
+    }
+}
+
+
+#[derive(Clone)]
+pub enum WrappedVariant {
+    StartWorkflow(WrappedStartWorkflow),
+    FireTimer(WrappedFireTimer),
+    UpdateRandomSeed(WrappedUpdateRandomSeed),
+    QueryWorkflow(WrappedQueryWorkflow),
+    CancelWorkflow(WrappedCancelWorkflow),
+    SignalWorkflow(WrappedSignalWorkflow),
+    ResolveActivity(WrappedResolveActivity),
+    RemoveFromCache(WrappedRemoveFromCache),
+}
+
+impl<'source> FromPyObject<'source> for WrappedVariant {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(start_workflow_job) = ob.extract::<WrappedStartWorkflow>() {
+            Ok(WrappedVariant::StartWorkflow(start_workflow_job))
+        } else if let Ok(fire_timer_job) = ob.extract::<WrappedFireTimer>() {
+            Ok(WrappedVariant::FireTimer(fire_timer_job))
+        } else if let Ok(update_random_seed_job) = ob.extract::<WrappedUpdateRandomSeed>() {
+            Ok(WrappedVariant::UpdateRandomSeed(update_random_seed_job))
+        } else if let Ok(query_workflow_job) = ob.extract::<WrappedQueryWorkflow>() {
+            Ok(WrappedVariant::QueryWorkflow(query_workflow_job))
+        } else if let Ok(cancel_workflow_job) = ob.extract::<WrappedCancelWorkflow>() {
+            Ok(WrappedVariant::CancelWorkflow(cancel_workflow_job))
+        } else if let Ok(signal_workflow_job) = ob.extract::<WrappedSignalWorkflow>() {
+            Ok(WrappedVariant::SignalWorkflow(signal_workflow_job))
+        } else if let Ok(resolve_activity_job) = ob.extract::<WrappedResolveActivity>() {
+            Ok(WrappedVariant::ResolveActivity(resolve_activity_job))
+        } else if let Ok(remove_from_cache_job) = ob.extract::<WrappedRemoveFromCache>() {
+            Ok(WrappedVariant::RemoveFromCache(remove_from_cache_job))
+        } else {
+            Err(PyValueError::new_err(format!(
+                "WfActivationJob.variant must be one of StartWorkflow, FireTimer, UpdateRandomSeed, QueryWorkflow, \
+                CancelWorkflow, SignalWorkflow, ResolveActivity or RemoveFromCache, got {}",
+                ob.get_type().name()?
+            )))
+        }
+    }
+}
+
+impl IntoPy<PyObject> for WrappedVariant {
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            WrappedVariant::StartWorkflow(start_workflow_job) => start_workflow_job.into_py(py),
+            WrappedVariant::FireTimer(fire_timer_job) => fire_timer_job.into_py(py),
+            WrappedVariant::UpdateRandomSeed(update_random_seed_job) => update_random_seed_job.into_py(py),
+            WrappedVariant::QueryWorkflow(query_workflow_job) => query_workflow_job.into_py(py),
+            WrappedVariant::CancelWorkflow(cancel_workflow_job) => cancel_workflow_job.into_py(py),
+            WrappedVariant::SignalWorkflow(signal_workflow_job) => signal_workflow_job.into_py(py),
+            WrappedVariant::ResolveActivity(resolve_activity_job) => resolve_activity_job.into_py(py),
+            WrappedVariant::RemoveFromCache(remove_from_cache_job) => remove_from_cache_job.into_py(py),
         }
     }
 }
@@ -359,110 +548,61 @@ impl WrappedVariant {
 impl From<wf_activation_job::Variant> for WrappedVariant {
     fn from(i: wf_activation_job::Variant) -> Self {
         match i {
-            wf_activation_job::Variant::StartWorkflow(start_workflow_job) => WrappedVariant {
-                start_workflow: Some(WrappedStartWorkflow::from(start_workflow_job)),
-                fire_timer: None,
-                update_random_seed: None,
-                query_workflow: None,
-                cancel_workflow: None,
-                signal_workflow: None,
-                resolve_activity: None,
-                remove_from_cache: None,
-            },
-            wf_activation_job::Variant::FireTimer(fire_timer_job) => WrappedVariant {
-                start_workflow: None,
-                fire_timer: Some(WrappedFireTimer::from(fire_timer_job)),
-                update_random_seed: None,
-                query_workflow: None,
-                cancel_workflow: None,
-                signal_workflow: None,
-                resolve_activity: None,
-                remove_from_cache: None,
-            },
-            wf_activation_job::Variant::UpdateRandomSeed(update_random_seed_job) => WrappedVariant {
-                start_workflow: None,
-                fire_timer: None,
-                update_random_seed: Some(WrappedUpdateRandomSeed::from(update_random_seed_job)),
-                query_workflow: None,
-                cancel_workflow: None,
-                signal_workflow: None,
-                resolve_activity: None,
-                remove_from_cache: None,
-            },
-            wf_activation_job::Variant::QueryWorkflow(query_workflow_job) => WrappedVariant {
-                start_workflow: None,
-                fire_timer: None,
-                update_random_seed: None,
-                query_workflow: Some(WrappedQueryWorkflow::from(query_workflow_job)),
-                cancel_workflow: None,
-                signal_workflow: None,
-                resolve_activity: None,
-                remove_from_cache: None,
-            },
-            wf_activation_job::Variant::CancelWorkflow(cancel_workflow_job) => WrappedVariant {
-                start_workflow: None,
-                fire_timer: None,
-                update_random_seed: None,
-                query_workflow: None,
-                cancel_workflow: Some(WrappedCancelWorkflow::from(cancel_workflow_job)),
-                signal_workflow: None,
-                resolve_activity: None,
-                remove_from_cache: None,
-            },
-            wf_activation_job::Variant::SignalWorkflow(signal_workflow_job) => WrappedVariant {
-                start_workflow: None,
-                fire_timer: None,
-                update_random_seed: None,
-                query_workflow: None,
-                cancel_workflow: None,
-                signal_workflow: Some(WrappedSignalWorkflow::from(signal_workflow_job)),
-                resolve_activity: None,
-                remove_from_cache: None,
-            },
-            wf_activation_job::Variant::ResolveActivity(resolve_activity_job) => WrappedVariant {
-                start_workflow: None,
-                fire_timer: None,
-                update_random_seed: None,
-                query_workflow: None,
-                cancel_workflow: None,
-                signal_workflow: None,
-                resolve_activity: Some(WrappedResolveActivity::from(resolve_activity_job)),
-                remove_from_cache: None,
-            },
-            wf_activation_job::Variant::RemoveFromCache(remove_from_cache) => WrappedVariant {
-                start_workflow: None,
-                fire_timer: None,
-                update_random_seed: None,
-                query_workflow: None,
-                cancel_workflow: None,
-                signal_workflow: None,
-                resolve_activity: None,
-                remove_from_cache: Some(remove_from_cache),
-            },
+            wf_activation_job::Variant::StartWorkflow(start_workflow_job) => {
+                WrappedVariant::StartWorkflow(WrappedStartWorkflow::from(start_workflow_job))
+            }
+            wf_activation_job::Variant::FireTimer(fire_timer_job) => {
+                WrappedVariant::FireTimer(WrappedFireTimer::from(fire_timer_job))
+            }
+            wf_activation_job::Variant::UpdateRandomSeed(update_random_seed_job) => {
+                WrappedVariant::UpdateRandomSeed(WrappedUpdateRandomSeed::from(update_random_seed_job))
+            }
+            wf_activation_job::Variant::QueryWorkflow(query_workflow_job) => {
+                WrappedVariant::QueryWorkflow(WrappedQueryWorkflow::from(query_workflow_job))
+            }
+            wf_activation_job::Variant::CancelWorkflow(cancel_workflow_job) => {
+                WrappedVariant::CancelWorkflow(WrappedCancelWorkflow::from(cancel_workflow_job))
+            }
+            wf_activation_job::Variant::SignalWorkflow(signal_workflow_job) => {
+                WrappedVariant::SignalWorkflow(WrappedSignalWorkflow::from(signal_workflow_job))
+            }
+            wf_activation_job::Variant::ResolveActivity(resolve_activity_job) => {
+                WrappedVariant::ResolveActivity(WrappedResolveActivity::from(resolve_activity_job))
+            }
+            wf_activation_job::Variant::RemoveFromCache(remove_from_cache) => {
+                WrappedVariant::RemoveFromCache(WrappedRemoveFromCache { remove_from_cache })
+            }
         }
     }
 }
 
 impl From<WrappedVariant> for wf_activation_job::Variant {
     fn from(i: WrappedVariant) -> Self {
-        if let Some(start_workflow_job) = i.start_workflow {
-            return wf_activation_job::Variant::StartWorkflow(StartWorkflow::from(start_workflow_job));
-        } else if let Some(fire_timer_job) = i.fire_timer {
-            return wf_activation_job::Variant::FireTimer(FireTimer::from(fire_timer_job));
-        } else if let Some(update_random_seed_job) = i.update_random_seed {
-            return wf_activation_job::Variant::UpdateRandomSeed(UpdateRandomSeed::from(update_random_seed_job));
-        } else if let Some(query_workflow_job) = i.query_workflow {
-            return wf_activation_job::Variant::QueryWorkflow(QueryWorkflow::from(query_workflow_job));
-        } else if let Some(cancel_workflow_job) = i.cancel_workflow {
-            return wf_activation_job::Variant::CancelWorkflow(CancelWorkflow::from(cancel_workflow_job));
-        } else if let Some(signal_workflow_job) = i.signal_workflow {
-            return wf_activation_job::Variant::SignalWorkflow(SignalWorkflow::from(signal_workflow_job));
-        } else if let Some(resolve_activity_job) = i.resolve_activity {
-            return wf_activation_job::Variant::ResolveActivity(ResolveActivity::from(resolve_activity_job));
-        } else if let Some(remove_from_cache) = i.remove_from_cache {
-            return wf_activation_job::Variant::RemoveFromCache(remove_from_cache);
-        } else {
-            panic!("Only one of job types must be set");
+        match i {
+            WrappedVariant::StartWorkflow(start_workflow_job) => {
+                wf_activation_job::Variant::StartWorkflow(StartWorkflow::from(start_workflow_job))
+            }
+            WrappedVariant::FireTimer(fire_timer_job) => {
+                wf_activation_job::Variant::FireTimer(FireTimer::from(fire_timer_job))
+            }
+            WrappedVariant::UpdateRandomSeed(update_random_seed_job) => {
+                wf_activation_job::Variant::UpdateRandomSeed(UpdateRandomSeed::from(update_random_seed_job))
+            }
+            WrappedVariant::QueryWorkflow(query_workflow_job) => {
+                wf_activation_job::Variant::QueryWorkflow(QueryWorkflow::from(query_workflow_job))
+            }
+            WrappedVariant::CancelWorkflow(cancel_workflow_job) => {
+                wf_activation_job::Variant::CancelWorkflow(CancelWorkflow::from(cancel_workflow_job))
+            }
+            WrappedVariant::SignalWorkflow(signal_workflow_job) => {
+                wf_activation_job::Variant::SignalWorkflow(SignalWorkflow::from(signal_workflow_job))
+            }
+            WrappedVariant::ResolveActivity(resolve_activity_job) => {
+                wf_activation_job::Variant::ResolveActivity(ResolveActivity::from(resolve_activity_job))
+            }
+            WrappedVariant::RemoveFromCache(remove_from_cache_job) => {
+                wf_activation_job::Variant::RemoveFromCache(remove_from_cache_job.remove_from_cache)
+            }
         }
     }
 }
@@ -470,6 +610,7 @@ impl From<WrappedVariant> for wf_activation_job::Variant {
 #[pyclass(name = "WfActivationJob")]
 #[derive(Clone)]
 pub struct WrappedWfActivationJob {
+    #[pyo3(get, set)]
     pub variant: Option<WrappedVariant>,
 }
 
@@ -533,9 +674,13 @@ impl From<&WrappedWfActivationJob> for WfActivationJob {
 #[pyclass(name = "WfActivation")]
 #[derive(Clone)]
 pub struct WrappedWfActivation {
+    #[pyo3(get, set)]
     pub run_id: String,
-    pub timestamp: Option<u128>,
+    #[pyo3(get, set)]
+    pub timestamp: Option<UtcDateTime>,
+    #[pyo3(get, set)]
     pub is_replaying: bool,
+    #[pyo3(get, set)]
     pub jobs: Vec<WrappedWfActivationJob>,
 }
 
@@ -543,7 +688,7 @@ pub struct WrappedWfActivation {
 impl WrappedWfActivation {
     #[new]
     fn new(run_id: String,
-           timestamp: Option<u128>,
+           timestamp: Option<UtcDateTime>,
            is_replaying: bool,
            jobs: Vec<WrappedWfActivationJob>) -> Self {
         WrappedWfActivation {
@@ -555,16 +700,18 @@ impl WrappedWfActivation {
     }
 }
 
-impl From<WfActivation> for WrappedWfActivation {
-    fn from(i: WfActivation) -> Self {
+impl TryFrom<WfActivation> for WrappedWfActivation {
+    type Error = PyErr;
+
+    fn try_from(i: WfActivation) -> Result<Self, Self::Error> {
         let wrapped_jobs = i.jobs.iter().map(|x| WrappedWfActivationJob::from(x)).collect::<Vec<_>>();
 
-        WrappedWfActivation {
+        Ok(WrappedWfActivation {
             run_id: i.run_id,
-            timestamp: prost_types_timestamp_to_u128(i.timestamp),
+            timestamp: prost_types_timestamp_to_utc_datetime(i.timestamp)?,
             is_replaying: i.is_replaying,
             jobs: wrapped_jobs,
-        }
+        })
     }
 }
 
@@ -574,7 +721,7 @@ impl From<WrappedWfActivation> for WfActivation {
 
         WfActivation {
             run_id: i.run_id,
-            timestamp: u128_to_prost_types_timestamp(i.timestamp),
+            timestamp: utc_datetime_to_prost_types_timestamp(i.timestamp),
             is_replaying: i.is_replaying,
             jobs: unwrapped_jobs,
         }
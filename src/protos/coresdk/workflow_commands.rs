@@ -2,6 +2,8 @@ use std::convert::TryFrom;
 use std::collections::HashMap;
 
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyTuple;
 
 use temporal_sdk_core::protos::coresdk::{
     common::{
@@ -43,21 +45,49 @@ use crate::utils::{
 };
 
 
-#[pyclass(name = "StartTimer")]
+#[pyclass(name = "Variant", subclass)]
+#[derive(Clone)]
+pub struct WrappedVariantBase {}
+
+
+#[pyclass(name = "QueryResultVariant", subclass)]
+#[derive(Clone)]
+pub struct WrappedQueryResultVariantBase {}
+
+
+#[pyclass(name = "StartTimer", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedStartTimer {
+    #[pyo3(get, set)]
     pub timer_id: String,
+    #[pyo3(get, set)]
     pub start_to_fire_timeout: Option<pyo3_chrono::Duration>,
 }
 
 #[pymethods]
 impl WrappedStartTimer {
     #[new]
-    fn new(timer_id: String, start_to_fire_timeout: Option<pyo3_chrono::Duration>) -> Self {
-        WrappedStartTimer {
+    fn new(timer_id: String, start_to_fire_timeout: Option<pyo3_chrono::Duration>) -> (Self, WrappedVariantBase) {
+        (WrappedStartTimer {
             timer_id,
             start_to_fire_timeout,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "start_timer"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["timer_id", "start_to_fire_timeout"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedStartTimer {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -84,19 +114,36 @@ impl TryFrom<WrappedStartTimer> for StartTimer {
 }
 
 
-#[pyclass(name = "CancelTimer")]
+#[pyclass(name = "CancelTimer", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedCancelTimer {
+    #[pyo3(get, set)]
     pub timer_id: String,
 }
 
 #[pymethods]
 impl WrappedCancelTimer {
     #[new]
-    fn new(timer_id: String) -> Self {
-        WrappedCancelTimer {
+    fn new(timer_id: String) -> (Self, WrappedVariantBase) {
+        (WrappedCancelTimer {
             timer_id,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "cancel_timer"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["timer_id"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedCancelTimer {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -117,21 +164,33 @@ impl From<WrappedCancelTimer> for CancelTimer {
 }
 
 
-#[pyclass(name = "ScheduleActivity")]
+#[pyclass(name = "ScheduleActivity", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedScheduleActivity {
+    #[pyo3(get, set)]
     pub activity_id: String,
+    #[pyo3(get, set)]
     pub activity_type: String,
+    #[pyo3(get, set)]
     pub namespace: String,
+    #[pyo3(get, set)]
     pub task_queue: String,
+    #[pyo3(get, set)]
     pub header_fields: HashMap<String, WrappedPayload>,
+    #[pyo3(get, set)]
     pub arguments: Vec<WrappedPayload>,
+    #[pyo3(get, set)]
     pub schedule_to_close_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub schedule_to_start_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub start_to_close_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub heartbeat_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub retry_policy: Option<WrappedRetryPolicy>,
     // FIXME enum
+    #[pyo3(get, set)]
     pub cancellation_type: i32,
 }
 
@@ -150,8 +209,8 @@ impl WrappedScheduleActivity {
            heartbeat_timeout: Option<pyo3_chrono::Duration>,
            retry_policy: Option<WrappedRetryPolicy>,
            // FIXME enum
-           cancellation_type: i32) -> Self {
-        WrappedScheduleActivity {
+           cancellation_type: i32) -> (Self, WrappedVariantBase) {
+        (WrappedScheduleActivity {
             activity_id,
             activity_type,
             namespace,
@@ -164,7 +223,23 @@ impl WrappedScheduleActivity {
             heartbeat_timeout,
             retry_policy,
             cancellation_type,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "schedule_activity"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["activity_id", "activity_type", "namespace", "task_queue", "header_fields", "arguments", "schedule_to_close_timeout", "schedule_to_start_timeout", "start_to_close_timeout", "heartbeat_timeout", "retry_policy", "cancellation_type"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedScheduleActivity {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -217,19 +292,36 @@ impl TryFrom<WrappedScheduleActivity> for ScheduleActivity {
 }
 
 
-#[pyclass(name = "RequestCancelActivity")]
+#[pyclass(name = "RequestCancelActivity", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedRequestCancelActivity {
+    #[pyo3(get, set)]
     pub activity_id: String,
 }
 
 #[pymethods]
 impl WrappedRequestCancelActivity {
     #[new]
-    fn new(activity_id: String) -> Self {
-        WrappedRequestCancelActivity {
+    fn new(activity_id: String) -> (Self, WrappedVariantBase) {
+        (WrappedRequestCancelActivity {
             activity_id,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "request_cancel_activity"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["activity_id"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedRequestCancelActivity {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -250,19 +342,36 @@ impl From<WrappedRequestCancelActivity> for RequestCancelActivity {
 }
 
 
-#[pyclass(name = "QuerySuccess")]
+#[pyclass(name = "QuerySuccess", extends = WrappedQueryResultVariantBase)]
 #[derive(Clone)]
 pub struct WrappedQuerySuccess {
+    #[pyo3(get, set)]
     pub response: Option<WrappedPayload>,
 }
 
 #[pymethods]
 impl WrappedQuerySuccess {
     #[new]
-    fn new(response: Option<WrappedPayload>) -> Self {
-        WrappedQuerySuccess {
+    fn new(response: Option<WrappedPayload>) -> (Self, WrappedQueryResultVariantBase) {
+        (WrappedQuerySuccess {
             response,
-        }
+        }, WrappedQueryResultVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "succeeded"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["response"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedQuerySuccess {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedQueryResultVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -289,22 +398,66 @@ impl From<WrappedQuerySuccess> for QuerySuccess {
 }
 
 
-// FIXME called just "Variant" in the original protobuf
-#[pyclass(name = "QueryResultVariant")]
+#[pyclass(name = "QueryFailed", extends = WrappedQueryResultVariantBase)]
 #[derive(Clone)]
-pub struct WrappedQueryResultVariant {
-    pub succeeded: Option<WrappedQuerySuccess>,
-    pub failed: Option<WrappedUserCodeFailure>,
+pub struct WrappedQueryFailed {
+    #[pyo3(get, set)]
+    pub failed: WrappedUserCodeFailure,
 }
 
 #[pymethods]
-impl WrappedQueryResultVariant {
+impl WrappedQueryFailed {
     #[new]
-    fn new(succeeded: Option<WrappedQuerySuccess>,
-           failed: Option<WrappedUserCodeFailure>) -> Self {
-        WrappedQueryResultVariant {
-            succeeded,
+    fn new(failed: WrappedUserCodeFailure) -> (Self, WrappedQueryResultVariantBase) {
+        (WrappedQueryFailed {
             failed,
+        }, WrappedQueryResultVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "failed"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["failed"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedQueryFailed {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedQueryResultVariantBase {})).unwrap().into_py(py)
+    }
+}
+
+
+#[derive(Clone)]
+pub enum WrappedQueryResultVariant {
+    Succeeded(WrappedQuerySuccess),
+    Failed(WrappedQueryFailed),
+}
+
+impl<'source> FromPyObject<'source> for WrappedQueryResultVariant {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(success) = ob.extract::<WrappedQuerySuccess>() {
+            Ok(WrappedQueryResultVariant::Succeeded(success))
+        } else if let Ok(failure) = ob.extract::<WrappedQueryFailed>() {
+            Ok(WrappedQueryResultVariant::Failed(failure))
+        } else {
+            Err(PyValueError::new_err(format!(
+                "QueryResult.variant must be one of QuerySuccess or QueryFailed, got {}",
+                ob.get_type().name()?
+            )))
+        }
+    }
+}
+
+impl IntoPy<PyObject> for WrappedQueryResultVariant {
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            WrappedQueryResultVariant::Succeeded(success) => success.into_py(py),
+            WrappedQueryResultVariant::Failed(failure) => failure.into_py(py),
         }
     }
 }
@@ -312,13 +465,11 @@ impl WrappedQueryResultVariant {
 impl From<query_result::Variant> for WrappedQueryResultVariant {
     fn from(i: query_result::Variant) -> Self {
         match i {
-            query_result::Variant::Succeeded(success) => WrappedQueryResultVariant {
-                succeeded: Some(WrappedQuerySuccess::from(success)),
-                failed: None,
-            },
-            query_result::Variant::Failed(failure) => WrappedQueryResultVariant {
-                succeeded: None,
-                failed: Some(WrappedUserCodeFailure::from(failure)),
+            query_result::Variant::Succeeded(success) => {
+                WrappedQueryResultVariant::Succeeded(WrappedQuerySuccess::from(success))
+            }
+            query_result::Variant::Failed(failure) => {
+                WrappedQueryResultVariant::Failed(WrappedQueryFailed { failed: WrappedUserCodeFailure::from(failure) })
             }
         }
     }
@@ -326,21 +477,24 @@ impl From<query_result::Variant> for WrappedQueryResultVariant {
 
 impl From<WrappedQueryResultVariant> for query_result::Variant {
     fn from(i: WrappedQueryResultVariant) -> Self {
-        if let Some(success) = i.succeeded {
-            query_result::Variant::Succeeded(QuerySuccess::from(success))
-        } else if let Some(failure) = i.failed {
-            query_result::Variant::Failed(UserCodeFailure::from(failure))
-        } else {
-            panic!("Only one of query result variants must be set");
+        match i {
+            WrappedQueryResultVariant::Succeeded(success) => {
+                query_result::Variant::Succeeded(QuerySuccess::from(success))
+            }
+            WrappedQueryResultVariant::Failed(failure) => {
+                query_result::Variant::Failed(UserCodeFailure::from(failure.failed))
+            }
         }
     }
 }
 
 
-#[pyclass(name = "QueryResult")]
+#[pyclass(name = "QueryResult", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedQueryResult {
+    #[pyo3(get, set)]
     pub query_id: String,
+    #[pyo3(get, set)]
     pub variant: Option<WrappedQueryResultVariant>,
 }
 
@@ -348,11 +502,27 @@ pub struct WrappedQueryResult {
 impl WrappedQueryResult {
     #[new]
     fn new(query_id: String,
-           variant: Option<WrappedQueryResultVariant>) -> Self {
-        WrappedQueryResult {
+           variant: Option<WrappedQueryResultVariant>) -> (Self, WrappedVariantBase) {
+        (WrappedQueryResult {
             query_id,
             variant,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "respond_to_query"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["query_id", "variant"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedQueryResult {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -381,19 +551,36 @@ impl From<WrappedQueryResult> for QueryResult {
 }
 
 
-#[pyclass(name = "CompleteWorkflowExecution")]
+#[pyclass(name = "CompleteWorkflowExecution", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedCompleteWorkflowExecution {
+    #[pyo3(get, set)]
     pub result: Option<WrappedPayload>,
 }
 
 #[pymethods]
 impl WrappedCompleteWorkflowExecution {
     #[new]
-    fn new(result: Option<WrappedPayload>) -> Self {
-        WrappedCompleteWorkflowExecution {
+    fn new(result: Option<WrappedPayload>) -> (Self, WrappedVariantBase) {
+        (WrappedCompleteWorkflowExecution {
             result,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "complete_workflow_execution"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["result"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedCompleteWorkflowExecution {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -420,19 +607,36 @@ impl From<WrappedCompleteWorkflowExecution> for CompleteWorkflowExecution {
 }
 
 
-#[pyclass(name = "FailWorkflowExecution")]
+#[pyclass(name = "FailWorkflowExecution", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedFailWorkflowExecution {
+    #[pyo3(get, set)]
     pub failure: Option<WrappedUserCodeFailure>,
 }
 
 #[pymethods]
 impl WrappedFailWorkflowExecution {
     #[new]
-    fn new(failure: Option<WrappedUserCodeFailure>) -> Self {
-        WrappedFailWorkflowExecution {
+    fn new(failure: Option<WrappedUserCodeFailure>) -> (Self, WrappedVariantBase) {
+        (WrappedFailWorkflowExecution {
             failure,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "fail_workflow_execution"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["failure"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedFailWorkflowExecution {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -459,16 +663,24 @@ impl From<WrappedFailWorkflowExecution> for FailWorkflowExecution {
 }
 
 
-#[pyclass(name = "ContinueAsNewWorkflowExecution")]
+#[pyclass(name = "ContinueAsNewWorkflowExecution", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedContinueAsNewWorkflowExecution {
+    #[pyo3(get, set)]
     pub workflow_type: String,
+    #[pyo3(get, set)]
     pub task_queue: String,
+    #[pyo3(get, set)]
     pub arguments: Vec<WrappedPayload>,
+    #[pyo3(get, set)]
     pub workflow_run_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub workflow_task_timeout: Option<pyo3_chrono::Duration>,
+    #[pyo3(get, set)]
     pub memo: HashMap<String, WrappedPayload>,
+    #[pyo3(get, set)]
     pub header: HashMap<String, WrappedPayload>,
+    #[pyo3(get, set)]
     pub search_attributes: HashMap<String, WrappedPayload>,
 }
 
@@ -482,8 +694,8 @@ impl WrappedContinueAsNewWorkflowExecution {
            workflow_task_timeout: Option<pyo3_chrono::Duration>,
            memo: HashMap<String, WrappedPayload>,
            header: HashMap<String, WrappedPayload>,
-           search_attributes: HashMap<String, WrappedPayload>) -> Self {
-        WrappedContinueAsNewWorkflowExecution {
+           search_attributes: HashMap<String, WrappedPayload>) -> (Self, WrappedVariantBase) {
+        (WrappedContinueAsNewWorkflowExecution {
             workflow_type,
             task_queue,
             arguments,
@@ -492,7 +704,23 @@ impl WrappedContinueAsNewWorkflowExecution {
             memo,
             header,
             search_attributes,
-        }
+        }, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "continue_as_new_workflow_execution"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["workflow_type", "task_queue", "arguments", "workflow_run_timeout", "workflow_task_timeout", "memo", "header", "search_attributes"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedContinueAsNewWorkflowExecution {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -532,15 +760,31 @@ impl TryFrom<WrappedContinueAsNewWorkflowExecution> for ContinueAsNewWorkflowExe
 }
 
 
-#[pyclass(name = "CancelWorkflowExecution")]
+#[pyclass(name = "CancelWorkflowExecution", extends = WrappedVariantBase)]
 #[derive(Clone)]
 pub struct WrappedCancelWorkflowExecution {}
 
 #[pymethods]
 impl WrappedCancelWorkflowExecution {
     #[new]
-    fn new() -> Self {
-        WrappedCancelWorkflowExecution {}
+    fn new() -> (Self, WrappedVariantBase) {
+        (WrappedCancelWorkflowExecution {}, WrappedVariantBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "cancel_workflow_execution"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::empty(py).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedCancelWorkflowExecution {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedVariantBase {})).unwrap().into_py(py)
     }
 }
 
@@ -557,42 +801,62 @@ impl From<WrappedCancelWorkflowExecution> for CancelWorkflowExecution {
 }
 
 
-#[pyclass(name = "Variant")]
 #[derive(Clone)]
-pub struct WrappedVariant {
-    pub start_timer: Option<WrappedStartTimer>,
-    pub schedule_activity: Option<WrappedScheduleActivity>,
-    pub respond_to_query: Option<WrappedQueryResult>,
-    pub request_cancel_activity: Option<WrappedRequestCancelActivity>,
-    pub cancel_timer: Option<WrappedCancelTimer>,
-    pub complete_workflow_execution: Option<WrappedCompleteWorkflowExecution>,
-    pub fail_workflow_execution: Option<WrappedFailWorkflowExecution>,
-    pub continue_as_new_workflow_execution: Option<WrappedContinueAsNewWorkflowExecution>,
-    pub cancel_workflow_execution: Option<WrappedCancelWorkflowExecution>,
+pub enum WrappedVariant {
+    StartTimer(WrappedStartTimer),
+    ScheduleActivity(WrappedScheduleActivity),
+    RespondToQuery(WrappedQueryResult),
+    RequestCancelActivity(WrappedRequestCancelActivity),
+    CancelTimer(WrappedCancelTimer),
+    CompleteWorkflowExecution(WrappedCompleteWorkflowExecution),
+    FailWorkflowExecution(WrappedFailWorkflowExecution),
+    ContinueAsNewWorkflowExecution(WrappedContinueAsNewWorkflowExecution),
+    CancelWorkflowExecution(WrappedCancelWorkflowExecution),
+}
+
+impl<'source> FromPyObject<'source> for WrappedVariant {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(start_timer) = ob.extract::<WrappedStartTimer>() {
+            Ok(WrappedVariant::StartTimer(start_timer))
+        } else if let Ok(schedule_activity) = ob.extract::<WrappedScheduleActivity>() {
+            Ok(WrappedVariant::ScheduleActivity(schedule_activity))
+        } else if let Ok(respond_to_query) = ob.extract::<WrappedQueryResult>() {
+            Ok(WrappedVariant::RespondToQuery(respond_to_query))
+        } else if let Ok(request_cancel_activity) = ob.extract::<WrappedRequestCancelActivity>() {
+            Ok(WrappedVariant::RequestCancelActivity(request_cancel_activity))
+        } else if let Ok(cancel_timer) = ob.extract::<WrappedCancelTimer>() {
+            Ok(WrappedVariant::CancelTimer(cancel_timer))
+        } else if let Ok(complete_workflow_execution) = ob.extract::<WrappedCompleteWorkflowExecution>() {
+            Ok(WrappedVariant::CompleteWorkflowExecution(complete_workflow_execution))
+        } else if let Ok(fail_workflow_execution) = ob.extract::<WrappedFailWorkflowExecution>() {
+            Ok(WrappedVariant::FailWorkflowExecution(fail_workflow_execution))
+        } else if let Ok(continue_as_new_workflow_execution) = ob.extract::<WrappedContinueAsNewWorkflowExecution>() {
+            Ok(WrappedVariant::ContinueAsNewWorkflowExecution(continue_as_new_workflow_execution))
+        } else if let Ok(cancel_workflow_execution) = ob.extract::<WrappedCancelWorkflowExecution>() {
+            Ok(WrappedVariant::CancelWorkflowExecution(cancel_workflow_execution))
+        } else {
+            Err(PyValueError::new_err(format!(
+                "WorkflowCommand.variant must be one of StartTimer, ScheduleActivity, QueryResult, RequestCancelActivity, \
+                CancelTimer, CompleteWorkflowExecution, FailWorkflowExecution, ContinueAsNewWorkflowExecution or \
+                CancelWorkflowExecution, got {}",
+                ob.get_type().name()?
+            )))
+        }
+    }
 }
 
-#[pymethods]
-impl WrappedVariant {
-    #[new]
-    fn new(start_timer: Option<WrappedStartTimer>,
-           schedule_activity: Option<WrappedScheduleActivity>,
-           respond_to_query: Option<WrappedQueryResult>,
-           request_cancel_activity: Option<WrappedRequestCancelActivity>,
-           cancel_timer: Option<WrappedCancelTimer>,
-           complete_workflow_execution: Option<WrappedCompleteWorkflowExecution>,
-           fail_workflow_execution: Option<WrappedFailWorkflowExecution>,
-           continue_as_new_workflow_execution: Option<WrappedContinueAsNewWorkflowExecution>,
-           cancel_workflow_execution: Option<WrappedCancelWorkflowExecution>) -> Self {
-        WrappedVariant {
-            start_timer,
-            schedule_activity,
-            respond_to_query,
-            request_cancel_activity,
-            cancel_timer,
-            complete_workflow_execution,
-            fail_workflow_execution,
-            continue_as_new_workflow_execution,
-            cancel_workflow_execution,
+impl IntoPy<PyObject> for WrappedVariant {
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            WrappedVariant::StartTimer(start_timer) => start_timer.into_py(py),
+            WrappedVariant::ScheduleActivity(schedule_activity) => schedule_activity.into_py(py),
+            WrappedVariant::RespondToQuery(respond_to_query) => respond_to_query.into_py(py),
+            WrappedVariant::RequestCancelActivity(request_cancel_activity) => request_cancel_activity.into_py(py),
+            WrappedVariant::CancelTimer(cancel_timer) => cancel_timer.into_py(py),
+            WrappedVariant::CompleteWorkflowExecution(complete_workflow_execution) => complete_workflow_execution.into_py(py),
+            WrappedVariant::FailWorkflowExecution(fail_workflow_execution) => fail_workflow_execution.into_py(py),
+            WrappedVariant::ContinueAsNewWorkflowExecution(continue_as_new_workflow_execution) => continue_as_new_workflow_execution.into_py(py),
+            WrappedVariant::CancelWorkflowExecution(cancel_workflow_execution) => cancel_workflow_execution.into_py(py),
         }
     }
 }
@@ -602,137 +866,70 @@ impl TryFrom<workflow_command::Variant> for WrappedVariant {
 
     fn try_from(i: workflow_command::Variant) -> Result<Self, Self::Error> {
         Ok(match i {
-            workflow_command::Variant::StartTimer(start_timer) => WrappedVariant {
-                start_timer: Some(WrappedStartTimer::try_from(start_timer)?),
-                schedule_activity: None,
-                respond_to_query: None,
-                request_cancel_activity: None,
-                cancel_timer: None,
-                complete_workflow_execution: None,
-                fail_workflow_execution: None,
-                continue_as_new_workflow_execution: None,
-                cancel_workflow_execution: None,
-            },
-            workflow_command::Variant::ScheduleActivity(schedule_activity) => WrappedVariant {
-                start_timer: None,
-                schedule_activity: Some(WrappedScheduleActivity::try_from(schedule_activity)?),
-                respond_to_query: None,
-                request_cancel_activity: None,
-                cancel_timer: None,
-                complete_workflow_execution: None,
-                fail_workflow_execution: None,
-                continue_as_new_workflow_execution: None,
-                cancel_workflow_execution: None,
-            },
-            workflow_command::Variant::RespondToQuery(respond_to_query) => WrappedVariant {
-                start_timer: None,
-                schedule_activity: None,
-                respond_to_query: Some(WrappedQueryResult::try_from(respond_to_query)?),
-                request_cancel_activity: None,
-                cancel_timer: None,
-                complete_workflow_execution: None,
-                fail_workflow_execution: None,
-                continue_as_new_workflow_execution: None,
-                cancel_workflow_execution: None,
-            },
-            workflow_command::Variant::RequestCancelActivity(request_cancel_activity) => WrappedVariant {
-                start_timer: None,
-                schedule_activity: None,
-                respond_to_query: None,
-                request_cancel_activity: Some(WrappedRequestCancelActivity::try_from(request_cancel_activity)?),
-                cancel_timer: None,
-                complete_workflow_execution: None,
-                fail_workflow_execution: None,
-                continue_as_new_workflow_execution: None,
-                cancel_workflow_execution: None,
-            },
-            workflow_command::Variant::CancelTimer(cancel_timer) => WrappedVariant {
-                start_timer: None,
-                schedule_activity: None,
-                respond_to_query: None,
-                request_cancel_activity: None,
-                cancel_timer: Some(WrappedCancelTimer::try_from(cancel_timer)?),
-                complete_workflow_execution: None,
-                fail_workflow_execution: None,
-                continue_as_new_workflow_execution: None,
-                cancel_workflow_execution: None,
-            },
-            workflow_command::Variant::CompleteWorkflowExecution(complete_workflow_execution) => WrappedVariant {
-                start_timer: None,
-                schedule_activity: None,
-                respond_to_query: None,
-                request_cancel_activity: None,
-                cancel_timer: None,
-                complete_workflow_execution: Some(WrappedCompleteWorkflowExecution::try_from(complete_workflow_execution)?),
-                fail_workflow_execution: None,
-                continue_as_new_workflow_execution: None,
-                cancel_workflow_execution: None,
-            },
-            workflow_command::Variant::FailWorkflowExecution(fail_workflow_execution) => WrappedVariant {
-                start_timer: None,
-                schedule_activity: None,
-                respond_to_query: None,
-                request_cancel_activity: None,
-                cancel_timer: None,
-                complete_workflow_execution: None,
-                fail_workflow_execution: Some(WrappedFailWorkflowExecution::try_from(fail_workflow_execution)?),
-                continue_as_new_workflow_execution: None,
-                cancel_workflow_execution: None,
-            },
-            workflow_command::Variant::ContinueAsNewWorkflowExecution(continue_as_new_workflow_execution) => WrappedVariant {
-                start_timer: None,
-                schedule_activity: None,
-                respond_to_query: None,
-                request_cancel_activity: None,
-                cancel_timer: None,
-                complete_workflow_execution: None,
-                fail_workflow_execution: None,
-                continue_as_new_workflow_execution: Some(WrappedContinueAsNewWorkflowExecution::try_from(continue_as_new_workflow_execution)?),
-                cancel_workflow_execution: None,
-            },
-            workflow_command::Variant::CancelWorkflowExecution(cancel_workflow_execution) => WrappedVariant {
-                start_timer: None,
-                schedule_activity: None,
-                respond_to_query: None,
-                request_cancel_activity: None,
-                cancel_timer: None,
-                complete_workflow_execution: None,
-                fail_workflow_execution: None,
-                continue_as_new_workflow_execution: None,
-                cancel_workflow_execution: Some(WrappedCancelWorkflowExecution::try_from(cancel_workflow_execution)?),
-            },
+            workflow_command::Variant::StartTimer(start_timer) => {
+                WrappedVariant::StartTimer(WrappedStartTimer::try_from(start_timer)?)
+            }
+            workflow_command::Variant::ScheduleActivity(schedule_activity) => {
+                WrappedVariant::ScheduleActivity(WrappedScheduleActivity::try_from(schedule_activity)?)
+            }
+            workflow_command::Variant::RespondToQuery(respond_to_query) => {
+                WrappedVariant::RespondToQuery(WrappedQueryResult::from(respond_to_query))
+            }
+            workflow_command::Variant::RequestCancelActivity(request_cancel_activity) => {
+                WrappedVariant::RequestCancelActivity(WrappedRequestCancelActivity::from(request_cancel_activity))
+            }
+            workflow_command::Variant::CancelTimer(cancel_timer) => {
+                WrappedVariant::CancelTimer(WrappedCancelTimer::from(cancel_timer))
+            }
+            workflow_command::Variant::CompleteWorkflowExecution(complete_workflow_execution) => {
+                WrappedVariant::CompleteWorkflowExecution(WrappedCompleteWorkflowExecution::from(complete_workflow_execution))
+            }
+            workflow_command::Variant::FailWorkflowExecution(fail_workflow_execution) => {
+                WrappedVariant::FailWorkflowExecution(WrappedFailWorkflowExecution::from(fail_workflow_execution))
+            }
+            workflow_command::Variant::ContinueAsNewWorkflowExecution(continue_as_new_workflow_execution) => {
+                WrappedVariant::ContinueAsNewWorkflowExecution(WrappedContinueAsNewWorkflowExecution::try_from(continue_as_new_workflow_execution)?)
+            }
+            workflow_command::Variant::CancelWorkflowExecution(cancel_workflow_execution) => {
+                WrappedVariant::CancelWorkflowExecution(WrappedCancelWorkflowExecution::from(cancel_workflow_execution))
+            }
         })
     }
 }
 
-
 impl TryFrom<WrappedVariant> for workflow_command::Variant {
     type Error = PyErr;
 
     fn try_from(i: WrappedVariant) -> Result<Self, Self::Error> {
-        Ok(
-            if let Some(start_timer) = i.start_timer {
+        Ok(match i {
+            WrappedVariant::StartTimer(start_timer) => {
                 workflow_command::Variant::StartTimer(StartTimer::try_from(start_timer)?)
-            } else if let Some(schedule_activity) = i.schedule_activity {
+            }
+            WrappedVariant::ScheduleActivity(schedule_activity) => {
                 workflow_command::Variant::ScheduleActivity(ScheduleActivity::try_from(schedule_activity)?)
-            } else if let Some(respond_to_query) = i.respond_to_query {
+            }
+            WrappedVariant::RespondToQuery(respond_to_query) => {
                 workflow_command::Variant::RespondToQuery(QueryResult::from(respond_to_query))
-            } else if let Some(request_cancel_activity) = i.request_cancel_activity {
+            }
+            WrappedVariant::RequestCancelActivity(request_cancel_activity) => {
                 workflow_command::Variant::RequestCancelActivity(RequestCancelActivity::from(request_cancel_activity))
-            } else if let Some(cancel_timer) = i.cancel_timer {
+            }
+            WrappedVariant::CancelTimer(cancel_timer) => {
                 workflow_command::Variant::CancelTimer(CancelTimer::from(cancel_timer))
-            } else if let Some(complete_workflow_execution) = i.complete_workflow_execution {
+            }
+            WrappedVariant::CompleteWorkflowExecution(complete_workflow_execution) => {
                 workflow_command::Variant::CompleteWorkflowExecution(CompleteWorkflowExecution::from(complete_workflow_execution))
-            } else if let Some(fail_workflow_execution) = i.fail_workflow_execution {
+            }
+            WrappedVariant::FailWorkflowExecution(fail_workflow_execution) => {
                 workflow_command::Variant::FailWorkflowExecution(FailWorkflowExecution::from(fail_workflow_execution))
-            } else if let Some(continue_as_new_workflow_execution) = i.continue_as_new_workflow_execution {
+            }
+            WrappedVariant::ContinueAsNewWorkflowExecution(continue_as_new_workflow_execution) => {
                 workflow_command::Variant::ContinueAsNewWorkflowExecution(ContinueAsNewWorkflowExecution::try_from(continue_as_new_workflow_execution)?)
-            } else if let Some(cancel_workflow_execution) = i.cancel_workflow_execution {
+            }
+            WrappedVariant::CancelWorkflowExecution(cancel_workflow_execution) => {
                 workflow_command::Variant::CancelWorkflowExecution(CancelWorkflowExecution::from(cancel_workflow_execution))
-            } else {
-                panic!("Only one of workflow command variants must be set");
             }
-        )
+        })
     }
 }
 
@@ -740,6 +937,7 @@ impl TryFrom<WrappedVariant> for workflow_command::Variant {
 #[pyclass(name = "WorkflowCommand")]
 #[derive(Clone)]
 pub struct WrappedWorkflowCommand {
+    #[pyo3(get, set)]
     pub variant: Option<WrappedVariant>,
 }
 
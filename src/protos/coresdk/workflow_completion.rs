@@ -1,6 +1,8 @@
 use std::convert::TryFrom;
 
 use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyTuple;
 
 use temporal_sdk_core::protos::coresdk::{
     common::UserCodeFailure,
@@ -21,19 +23,41 @@ use crate::protos::coresdk::{
 };
 
 
-#[pyclass(name = "Success")]
+#[pyclass(name = "Status", subclass)]
+#[derive(Clone)]
+pub struct WrappedStatusBase {}
+
+
+#[pyclass(name = "Success", extends = WrappedStatusBase)]
 #[derive(Clone)]
 pub struct WrappedSuccess {
+    #[pyo3(get, set)]
     pub commands: Vec<WrappedWorkflowCommand>,
 }
 
 #[pymethods]
 impl WrappedSuccess {
     #[new]
-    fn new(commands: Vec<WrappedWorkflowCommand>) -> Self {
-        WrappedSuccess {
+    fn new(commands: Vec<WrappedWorkflowCommand>) -> (Self, WrappedStatusBase) {
+        (WrappedSuccess {
             commands,
-        }
+        }, WrappedStatusBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "successful"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["commands"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedSuccess {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedStatusBase {})).unwrap().into_py(py)
     }
 }
 
@@ -69,19 +93,36 @@ impl TryFrom<WrappedSuccess> for Success {
 }
 
 
-#[pyclass(name = "Failure")]
+#[pyclass(name = "Failure", extends = WrappedStatusBase)]
 #[derive(Clone)]
 pub struct WrappedFailure {
+    #[pyo3(get, set)]
     pub failure: Option<WrappedUserCodeFailure>,
 }
 
 #[pymethods]
 impl WrappedFailure {
     #[new]
-    fn new(failure: Option<WrappedUserCodeFailure>) -> Self {
-        WrappedFailure {
+    fn new(failure: Option<WrappedUserCodeFailure>) -> (Self, WrappedStatusBase) {
+        (WrappedFailure {
             failure,
-        }
+        }, WrappedStatusBase {})
+    }
+
+    #[classattr]
+    fn kind() -> &'static str {
+        "failed"
+    }
+
+    #[classattr]
+    fn __match_args__() -> PyObject {
+        Python::with_gil(|py| PyTuple::new(py, &["failure"]).into())
+    }
+}
+
+impl IntoPy<PyObject> for WrappedFailure {
+    fn into_py(self, py: Python) -> PyObject {
+        Py::new(py, (self, WrappedStatusBase {})).unwrap().into_py(py)
     }
 }
 
@@ -109,21 +150,32 @@ impl From<WrappedFailure> for Failure {
 }
 
 
-#[pyclass(name = "Status")]
 #[derive(Clone)]
-pub struct WrappedStatus {
-    pub successful: Option<WrappedSuccess>,
-    pub failed: Option<WrappedFailure>,
+pub enum WrappedStatus {
+    Successful(WrappedSuccess),
+    Failed(WrappedFailure),
 }
 
+impl<'source> FromPyObject<'source> for WrappedStatus {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        if let Ok(success) = ob.extract::<WrappedSuccess>() {
+            Ok(WrappedStatus::Successful(success))
+        } else if let Ok(failure) = ob.extract::<WrappedFailure>() {
+            Ok(WrappedStatus::Failed(failure))
+        } else {
+            Err(PyValueError::new_err(format!(
+                "WfActivationCompletion.status must be one of Success or Failure, got {}",
+                ob.get_type().name()?
+            )))
+        }
+    }
+}
 
-#[pymethods]
-impl WrappedStatus {
-    #[new]
-    fn new(successful: Option<WrappedSuccess>, failed: Option<WrappedFailure>) -> Self {
-        WrappedStatus {
-            successful,
-            failed,
+impl IntoPy<PyObject> for WrappedStatus {
+    fn into_py(self, py: Python) -> PyObject {
+        match self {
+            WrappedStatus::Successful(success) => success.into_py(py),
+            WrappedStatus::Failed(failure) => failure.into_py(py),
         }
     }
 }
@@ -133,14 +185,12 @@ impl TryFrom<wf_activation_completion::Status> for WrappedStatus {
 
     fn try_from(i: wf_activation_completion::Status) -> Result<Self, Self::Error> {
         Ok(match i {
-            wf_activation_completion::Status::Successful(success) => WrappedStatus {
-                successful: Some(WrappedSuccess::try_from(success)?),
-                failed: None,
-            },
-            wf_activation_completion::Status::Failed(failure) => WrappedStatus {
-                successful: None,
-                failed: Some(WrappedFailure::from(failure)),
-            },
+            wf_activation_completion::Status::Successful(success) => {
+                WrappedStatus::Successful(WrappedSuccess::try_from(success)?)
+            }
+            wf_activation_completion::Status::Failed(failure) => {
+                WrappedStatus::Failed(WrappedFailure::from(failure))
+            }
         })
     }
 }
@@ -150,15 +200,14 @@ impl TryFrom<WrappedStatus> for wf_activation_completion::Status {
     type Error = PyErr;
 
     fn try_from(i: WrappedStatus) -> Result<Self, Self::Error> {
-        Ok(
-            if let Some(success) = i.successful {
+        Ok(match i {
+            WrappedStatus::Successful(success) => {
                 wf_activation_completion::Status::Successful(Success::try_from(success)?)
-            } else if let Some(failure) = i.failed {
-                wf_activation_completion::Status::Failed(Failure::try_from(failure)?)
-            } else {
-                panic!("Only one of workflow completion statuses must be set");
             }
-        )
+            WrappedStatus::Failed(failure) => {
+                wf_activation_completion::Status::Failed(Failure::from(failure))
+            }
+        })
     }
 }
 
@@ -166,7 +215,9 @@ impl TryFrom<WrappedStatus> for wf_activation_completion::Status {
 #[pyclass(name = "WfActivationCompletion")]
 #[derive(Clone)]
 pub struct WrappedWfActivationCompletion {
+    #[pyo3(get, set)]
     pub run_id: String,
+    #[pyo3(get, set)]
     pub status: Option<WrappedStatus>,
 }
 
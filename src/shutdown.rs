@@ -0,0 +1,72 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use tokio::sync::Notify;
+
+
+// Keeps track of what's being shut down so that pending pollers can be woken up and so that the shutdown can wait
+// for the completions that are still being sent to the server
+#[derive(Default)]
+pub(crate) struct ShutdownTracker {
+    core_shut_down: Mutex<bool>,
+    shut_down_task_queues: Mutex<HashSet<String>>,
+    shutdown_requested: Notify,
+    in_flight_completions: AtomicUsize,
+    completions_drained: Notify,
+}
+
+impl ShutdownTracker {
+    pub(crate) fn is_shut_down(&self, task_queue: &str) -> bool {
+        *self.core_shut_down.lock().unwrap() || self.shut_down_task_queues.lock().unwrap().contains(task_queue)
+    }
+
+    pub(crate) fn shut_down_core(&self) {
+        *self.core_shut_down.lock().unwrap() = true;
+        self.shutdown_requested.notify_waiters();
+    }
+
+    pub(crate) fn shut_down_task_queue(&self, task_queue: &str) {
+        self.shut_down_task_queues.lock().unwrap().insert(String::from(task_queue));
+        self.shutdown_requested.notify_waiters();
+    }
+
+    // Resolves once either the whole Core or the given task queue gets shut down
+    pub(crate) async fn wait_for_shutdown(&self, task_queue: &str) {
+        loop {
+            let notified = self.shutdown_requested.notified();
+            if self.is_shut_down(task_queue) {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    pub(crate) fn start_completion(&self) -> InFlightCompletion<'_> {
+        self.in_flight_completions.fetch_add(1, Ordering::SeqCst);
+        InFlightCompletion { tracker: self }
+    }
+
+    pub(crate) async fn wait_for_completions(&self) {
+        loop {
+            let notified = self.completions_drained.notified();
+            if self.in_flight_completions.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+
+pub(crate) struct InFlightCompletion<'a> {
+    tracker: &'a ShutdownTracker,
+}
+
+impl Drop for InFlightCompletion<'_> {
+    fn drop(&mut self) {
+        if self.tracker.in_flight_completions.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.tracker.completions_drained.notify_waiters();
+        }
+    }
+}
@@ -0,0 +1,742 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use temporal_sdk_core::{
+    Core,
+    ServerGatewayApis,
+    WorkerConfig,
+    errors::{
+        CompleteActivityError,
+        CompleteWfError,
+        PollActivityError,
+        PollWfError,
+        WorkerRegistrationError,
+    },
+    protos::coresdk::{
+        ActivityHeartbeat,
+        ActivityTaskCompletion,
+        activity_task::{
+            ActivityCancelReason,
+            ActivityTask,
+        },
+        workflow_activation::WfActivation,
+        workflow_completion::WfActivationCompletion,
+    },
+};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::WrappedCore;
+use crate::converter::data_converter::WrappedDataConverter;
+use crate::converter::failure::{
+    py_err_to_user_code_failure,
+    user_code_failure_to_exception,
+};
+use crate::errors::{
+    WorkflowCancelledError,
+    WorkflowFailureError,
+    new_err_with_attributes,
+};
+use crate::protos::coresdk::{
+    WrappedActivityTaskCompletion,
+    activity_result::{
+        WrappedActivityResult,
+        WrappedCancelation,
+        WrappedStatus as WrappedActivityStatus,
+    },
+    activity_task::{
+        WrappedActivityTask,
+        WrappedCancel,
+        WrappedStart,
+        WrappedVariant as WrappedActivityTaskVariant,
+    },
+    common::{
+        WrappedPayload,
+        WrappedUserCodeFailure,
+        WrappedWorkflowExecution,
+    },
+    workflow_activation::{
+        WrappedCancelWorkflow,
+        WrappedFireTimer,
+        WrappedResolveActivity,
+        WrappedSignalWorkflow,
+        WrappedStartWorkflow,
+        WrappedVariant as WrappedJobVariant,
+        WrappedWfActivation,
+        WrappedWfActivationJob,
+    },
+    workflow_commands::WrappedVariant as WrappedCommandVariant,
+    workflow_completion::{
+        WrappedStatus as WrappedCompletionStatus,
+        WrappedWfActivationCompletion,
+    },
+};
+use crate::testing::TEST_NAMESPACE;
+use crate::testing::gateway::NoServerGateway;
+use crate::utils::UtcDateTime;
+
+
+const SIGNAL_IDENTITY: &str = "WorkflowEnvironment";
+
+
+fn job(variant: WrappedJobVariant) -> WrappedWfActivationJob {
+    WrappedWfActivationJob {
+        variant: Some(variant),
+    }
+}
+
+
+// Server side of a workflow run that hasn't finished yet
+struct TestRun {
+    run_id: String,
+    workflow_type: String,
+    task_queue: String,
+    pending_jobs: Vec<WrappedWfActivationJob>,
+    // Activation that has been polled and hasn't been completed yet
+    activation_outstanding: bool,
+    timers: HashMap<String, DateTime<Utc>>,
+}
+
+impl TestRun {
+    fn new(workflow_id: &str,
+           workflow_type: String,
+           task_queue: String,
+           arguments: Vec<WrappedPayload>,
+           headers: HashMap<String, WrappedPayload>) -> Self {
+        let start_workflow = WrappedStartWorkflow {
+            workflow_type: workflow_type.clone(),
+            workflow_id: String::from(workflow_id),
+            arguments,
+            randomness_seed: rand::random(),
+            headers,
+        };
+        TestRun {
+            run_id: Uuid::new_v4().to_string(),
+            workflow_type,
+            task_queue,
+            pending_jobs: vec![job(WrappedJobVariant::StartWorkflow(start_workflow))],
+            activation_outstanding: false,
+            timers: HashMap::new(),
+        }
+    }
+}
+
+
+#[derive(Clone)]
+enum TestRunResult {
+    Completed(Option<WrappedPayload>),
+    Failed(Option<WrappedUserCodeFailure>),
+    Cancelled,
+}
+
+
+// Activity that has been scheduled and hasn't been resolved yet
+struct TestActivity {
+    workflow_id: String,
+    run_id: String,
+    activity_id: String,
+    task_queue: String,
+}
+
+
+// In-memory model of the server: runs, their timers and the activities they've scheduled, all on a clock that only
+// moves when time gets skipped
+struct TestServerRecord {
+    now: DateTime<Utc>,
+    auto_time_skipping: bool,
+    registered_task_queues: HashSet<String>,
+    shut_down_task_queues: HashSet<String>,
+    shut_down: bool,
+    // Keyed by workflow id, like the results of the runs that have finished
+    runs: HashMap<String, TestRun>,
+    results: HashMap<String, TestRunResult>,
+    activity_tasks: HashMap<String, VecDeque<ActivityTask>>,
+    // Keyed by task token
+    activities: HashMap<Vec<u8>, TestActivity>,
+    next_task_token: u64,
+}
+
+impl TestServerRecord {
+    fn is_shut_down(&self, task_queue: &str) -> bool {
+        self.shut_down || self.shut_down_task_queues.contains(task_queue)
+    }
+
+    // Nothing is going to happen without time passing: no run has an activation to go through and no activity is
+    // running, so every workflow coroutine is blocked
+    fn is_idle(&self) -> bool {
+        self.activities.is_empty() && self.runs.values().all(|run| {
+            run.pending_jobs.is_empty() && !run.activation_outstanding
+        })
+    }
+
+    fn fire_due_timers(&mut self) {
+        let now = self.now;
+        for run in self.runs.values_mut() {
+            let mut due_timers: Vec<(String, DateTime<Utc>)> = run.timers.iter()
+                .filter(|(_, fire_at)| **fire_at <= now)
+                .map(|(timer_id, fire_at)| (timer_id.clone(), *fire_at))
+                .collect();
+            due_timers.sort_by_key(|(_, fire_at)| *fire_at);
+            for (timer_id, _) in due_timers {
+                run.timers.remove(&timer_id);
+                run.pending_jobs.push(job(WrappedJobVariant::FireTimer(WrappedFireTimer {
+                    timer_id,
+                })));
+            }
+        }
+    }
+
+    // Returns whether there was a timer to skip to
+    fn skip_to_next_timer(&mut self) -> bool {
+        let next_fire_at = self.runs.values().flat_map(|run| run.timers.values()).min().cloned();
+        match next_fire_at {
+            None => false,
+            Some(fire_at) => {
+                if fire_at > self.now {
+                    self.now = fire_at;
+                }
+                self.fire_due_timers();
+                true
+            }
+        }
+    }
+
+    fn next_activation(&mut self, task_queue: &str) -> Option<WfActivation> {
+        let now = self.now;
+        let run = self.runs.values_mut().find(|run| {
+            run.task_queue == task_queue && !run.activation_outstanding && !run.pending_jobs.is_empty()
+        })?;
+        run.activation_outstanding = true;
+        Some(WfActivation::from(WrappedWfActivation {
+            run_id: run.run_id.clone(),
+            timestamp: Some(UtcDateTime(now)),
+            is_replaying: false,
+            jobs: run.pending_jobs.drain(..).collect(),
+        }))
+    }
+
+    fn workflow_id_of_run(&self, run_id: &str) -> Option<String> {
+        self.runs.iter().find(|(_, run)| run.run_id == run_id).map(|(workflow_id, _)| workflow_id.clone())
+    }
+
+    // Activities of a run that is over don't get resolved anymore, the ones that haven't been polled yet don't even
+    // get run
+    fn drop_activities(&mut self, workflow_id: &str) {
+        let task_tokens: Vec<Vec<u8>> = self.activities.iter()
+            .filter(|(_, activity)| activity.workflow_id == workflow_id)
+            .map(|(task_token, _)| task_token.clone())
+            .collect();
+        for task_token in task_tokens {
+            if let Some(activity) = self.activities.remove(&task_token) {
+                if let Some(queue) = self.activity_tasks.get_mut(&activity.task_queue) {
+                    queue.retain(|activity_task| activity_task.task_token != task_token);
+                }
+            }
+        }
+    }
+
+    fn finish(&mut self, workflow_id: &str, result: TestRunResult) {
+        self.drop_activities(workflow_id);
+        self.runs.remove(workflow_id);
+        self.results.insert(String::from(workflow_id), result);
+    }
+
+    // Unlike the server, which would retry the workflow task, the environment fails the run on workflow task failures
+    // so that they surface in the test that caused them
+    fn complete_activation(&mut self, completion: WrappedWfActivationCompletion) -> PyResult<()> {
+        let workflow_id = match self.workflow_id_of_run(&completion.run_id) {
+            None => return Ok(()),
+            Some(workflow_id) => workflow_id,
+        };
+        if let Some(run) = self.runs.get_mut(&workflow_id) {
+            run.activation_outstanding = false;
+        }
+
+        match completion.status {
+            None => {}
+            Some(WrappedCompletionStatus::Failed(failure)) => {
+                self.finish(&workflow_id, TestRunResult::Failed(failure.failure));
+            }
+            Some(WrappedCompletionStatus::Successful(success)) => {
+                for command in success.commands {
+                    if let Some(variant) = command.variant {
+                        // Commands after the one that has ended the run don't have a run to apply to anymore
+                        if self.apply_command(&workflow_id, variant)? {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Returns whether the command has ended the run
+    fn apply_command(&mut self, workflow_id: &str, command: WrappedCommandVariant) -> PyResult<bool> {
+        let now = self.now;
+        let run = match self.runs.get_mut(workflow_id) {
+            None => return Ok(true),
+            Some(run) => run,
+        };
+
+        match command {
+            WrappedCommandVariant::StartTimer(start_timer) => {
+                let start_to_fire_timeout = match start_timer.start_to_fire_timeout {
+                    None => chrono::Duration::zero(),
+                    Some(start_to_fire_timeout) => start_to_fire_timeout.0,
+                };
+                // Timers too long to fire before the end of time never do
+                let fire_at = now.checked_add_signed(start_to_fire_timeout).unwrap_or(chrono::MAX_DATETIME);
+                run.timers.insert(start_timer.timer_id, fire_at);
+            }
+            WrappedCommandVariant::CancelTimer(cancel_timer) => {
+                run.timers.remove(&cancel_timer.timer_id);
+            }
+            // Timeouts and retry policies aren't enforced, activities run once for as long as they take
+            WrappedCommandVariant::ScheduleActivity(schedule_activity) => {
+                let task_token = self.next_task_token.to_be_bytes().to_vec();
+                self.next_task_token += 1;
+                let task_queue = if schedule_activity.task_queue.is_empty() {
+                    run.task_queue.clone()
+                } else {
+                    schedule_activity.task_queue.clone()
+                };
+                let activity_task = ActivityTask::try_from(WrappedActivityTask {
+                    task_token: task_token.clone(),
+                    activity_id: schedule_activity.activity_id.clone(),
+                    variant: Some(WrappedActivityTaskVariant::Start(WrappedStart {
+                        workflow_namespace: schedule_activity.namespace,
+                        workflow_type: run.workflow_type.clone(),
+                        workflow_execution: Some(WrappedWorkflowExecution {
+                            workflow_id: String::from(workflow_id),
+                            run_id: run.run_id.clone(),
+                        }),
+                        activity_type: schedule_activity.activity_type,
+                        header_fields: schedule_activity.header_fields,
+                        input: schedule_activity.arguments,
+                        heartbeat_details: Vec::new(),
+                        scheduled_time: Some(UtcDateTime(now)),
+                        current_attempt_scheduled_time: Some(UtcDateTime(now)),
+                        started_time: Some(UtcDateTime(now)),
+                        attempt: 1,
+                        schedule_to_close_timeout: schedule_activity.schedule_to_close_timeout,
+                        start_to_close_timeout: schedule_activity.start_to_close_timeout,
+                        heartbeat_timeout: schedule_activity.heartbeat_timeout,
+                        retry_policy: schedule_activity.retry_policy,
+                    })),
+                })?;
+                self.activities.insert(task_token, TestActivity {
+                    workflow_id: String::from(workflow_id),
+                    run_id: run.run_id.clone(),
+                    activity_id: schedule_activity.activity_id,
+                    task_queue: task_queue.clone(),
+                });
+                self.activity_tasks.entry(task_queue).or_insert_with(VecDeque::new).push_back(activity_task);
+            }
+            // Activities that haven't been polled yet get resolved as cancelled right away, the running ones get a
+            // cancellation and resolve once they've honored it
+            WrappedCommandVariant::RequestCancelActivity(request_cancel_activity) => {
+                let run_id = run.run_id.clone();
+                let activity = self.activities.iter().find(|(_, activity)| {
+                    activity.workflow_id == workflow_id
+                        && activity.run_id == run_id
+                        && activity.activity_id == request_cancel_activity.activity_id
+                }).map(|(task_token, activity)| (task_token.clone(), activity.task_queue.clone()));
+                if let Some((task_token, task_queue)) = activity {
+                    let queue = self.activity_tasks.entry(task_queue).or_insert_with(VecDeque::new);
+                    match queue.iter().position(|activity_task| activity_task.task_token == task_token) {
+                        Some(position) => {
+                            queue.remove(position);
+                            self.activities.remove(&task_token);
+                            run.pending_jobs.push(job(WrappedJobVariant::ResolveActivity(WrappedResolveActivity {
+                                activity_id: request_cancel_activity.activity_id,
+                                result: Some(WrappedActivityResult {
+                                    status: Some(WrappedActivityStatus::Canceled(WrappedCancelation {
+                                        details: None,
+                                    })),
+                                }),
+                            })));
+                        }
+                        None => queue.push_front(ActivityTask::try_from(WrappedActivityTask {
+                            task_token,
+                            activity_id: request_cancel_activity.activity_id,
+                            variant: Some(WrappedActivityTaskVariant::Cancel(WrappedCancel {
+                                reason: ActivityCancelReason::Cancelled as i32,
+                            })),
+                        })?),
+                    }
+                }
+            }
+            // Queries can't be sent to the environment's runs
+            WrappedCommandVariant::RespondToQuery(_) => {}
+            WrappedCommandVariant::CompleteWorkflowExecution(complete_workflow_execution) => {
+                self.finish(workflow_id, TestRunResult::Completed(complete_workflow_execution.result));
+                return Ok(true);
+            }
+            WrappedCommandVariant::FailWorkflowExecution(fail_workflow_execution) => {
+                self.finish(workflow_id, TestRunResult::Failed(fail_workflow_execution.failure));
+                return Ok(true);
+            }
+            WrappedCommandVariant::CancelWorkflowExecution(_) => {
+                self.finish(workflow_id, TestRunResult::Cancelled);
+                return Ok(true);
+            }
+            // New run keeps the workflow id, so waiting for the workflow's result waits for the last run's
+            WrappedCommandVariant::ContinueAsNewWorkflowExecution(continue_as_new) => {
+                let workflow_type = if continue_as_new.workflow_type.is_empty() {
+                    run.workflow_type.clone()
+                } else {
+                    continue_as_new.workflow_type
+                };
+                let task_queue = if continue_as_new.task_queue.is_empty() {
+                    run.task_queue.clone()
+                } else {
+                    continue_as_new.task_queue
+                };
+                self.drop_activities(workflow_id);
+                self.runs.insert(String::from(workflow_id), TestRun::new(
+                    workflow_id,
+                    workflow_type,
+                    task_queue,
+                    continue_as_new.arguments,
+                    continue_as_new.header,
+                ));
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn complete_activity(&mut self, completion: WrappedActivityTaskCompletion) {
+        let activity = match self.activities.remove(&completion.task_token) {
+            None => return,
+            Some(activity) => activity,
+        };
+        if let Some(run) = self.runs.get_mut(&activity.workflow_id) {
+            if run.run_id == activity.run_id {
+                run.pending_jobs.push(job(WrappedJobVariant::ResolveActivity(WrappedResolveActivity {
+                    activity_id: activity.activity_id,
+                    result: completion.result,
+                })));
+            }
+        }
+    }
+}
+
+
+struct TestServerState {
+    record: Mutex<TestServerRecord>,
+    // Wakes up pending polls and result waiters whenever anything changes
+    changed: Notify,
+}
+
+
+// Core that runs workflows against the in-memory server rather than a real one; Python never runs while the record is
+// locked, so the GIL must not be acquired with the record locked either
+struct TestServerCoreInternal {
+    state: Arc<TestServerState>,
+}
+
+#[async_trait]
+impl Core for TestServerCoreInternal {
+    async fn register_worker(&self, config: WorkerConfig) -> Result<(), WorkerRegistrationError> {
+        let mut record = self.state.record.lock().unwrap();
+        if !record.registered_task_queues.insert(config.task_queue.clone()) {
+            return Err(WorkerRegistrationError::WorkerAlreadyRegisteredForQueue(config.task_queue));
+        }
+        Ok(())
+    }
+
+    async fn poll_workflow_task(&self, task_queue: &str) -> Result<WfActivation, PollWfError> {
+        loop {
+            let changed = self.state.changed.notified();
+            {
+                let mut record = self.state.record.lock().unwrap();
+                if !record.registered_task_queues.contains(task_queue) {
+                    return Err(PollWfError::NoWorkerForQueue(String::from(task_queue)));
+                }
+                if let Some(wf_activation) = record.next_activation(task_queue) {
+                    return Ok(wf_activation);
+                }
+                if record.is_shut_down(task_queue) {
+                    return Err(PollWfError::ShutDown);
+                }
+                // Every workflow coroutine is blocked, so the clock skips ahead to the earliest timer
+                if record.auto_time_skipping && record.is_idle() && record.skip_to_next_timer() {
+                    self.state.changed.notify_waiters();
+                    continue;
+                }
+            }
+            changed.await;
+        }
+    }
+
+    async fn poll_activity_task(&self, task_queue: &str) -> Result<ActivityTask, PollActivityError> {
+        loop {
+            let changed = self.state.changed.notified();
+            {
+                let mut record = self.state.record.lock().unwrap();
+                if !record.registered_task_queues.contains(task_queue) {
+                    return Err(PollActivityError::NoWorkerForQueue(String::from(task_queue)));
+                }
+                if let Some(activity_task) = record.activity_tasks.get_mut(task_queue).and_then(|queue| queue.pop_front()) {
+                    return Ok(activity_task);
+                }
+                if record.is_shut_down(task_queue) {
+                    return Err(PollActivityError::ShutDown);
+                }
+            }
+            changed.await;
+        }
+    }
+
+    async fn complete_workflow_task(&self, completion: WfActivationCompletion) -> Result<(), CompleteWfError> {
+        let run_id = completion.run_id.clone();
+        let result = WrappedWfActivationCompletion::try_from(completion).and_then(|completion| {
+            self.state.record.lock().unwrap().complete_activation(completion)
+        });
+        if let Err(err) = result {
+            let failure = WrappedUserCodeFailure::from(py_err_to_user_code_failure(&err));
+            let mut record = self.state.record.lock().unwrap();
+            if let Some(workflow_id) = record.workflow_id_of_run(&run_id) {
+                record.finish(&workflow_id, TestRunResult::Failed(Some(failure)));
+            }
+        }
+        self.state.changed.notify_waiters();
+        Ok(())
+    }
+
+    async fn complete_activity_task(&self, completion: ActivityTaskCompletion) -> Result<(), CompleteActivityError> {
+        let completion = WrappedActivityTaskCompletion::from(completion);
+        self.state.record.lock().unwrap().complete_activity(completion);
+        self.state.changed.notify_waiters();
+        Ok(())
+    }
+
+    fn record_activity_heartbeat(&self, _details: ActivityHeartbeat) {}
+
+    fn request_workflow_eviction(&self, _run_id: &str) {}
+
+    fn server_gateway(&self) -> Arc<dyn ServerGatewayApis> {
+        Arc::new(NoServerGateway::new("WorkflowEnvironment"))
+    }
+
+    async fn shutdown(&self) {
+        self.state.record.lock().unwrap().shut_down = true;
+        self.state.changed.notify_waiters();
+    }
+
+    async fn shutdown_worker(&self, task_queue: &str) {
+        self.state.record.lock().unwrap().shut_down_task_queues.insert(String::from(task_queue));
+        self.state.changed.notify_waiters();
+    }
+}
+
+
+// Core to run workers against in tests, with workflows started, signalled and awaited through the environment: with
+// auto time skipping, the clock jumps to the next timer as soon as every workflow is blocked and no activity is
+// running, so workflows that sleep for days finish right away. The environment's data converter has to match the
+// workers' one
+#[pyclass(name = "WorkflowEnvironment", extends = WrappedCore)]
+pub(crate) struct WrappedWorkflowEnvironment {
+    state: Arc<TestServerState>,
+    #[pyo3(get)]
+    data_converter: WrappedDataConverter,
+}
+
+impl WrappedWorkflowEnvironment {
+    fn result_to_py(&self, py: Python, workflow_id: &str, result: TestRunResult) -> PyResult<PyObject> {
+        match result {
+            TestRunResult::Completed(None) => Ok(py.None()),
+            TestRunResult::Completed(Some(payload)) => self.data_converter.decode(py, payload),
+            TestRunResult::Failed(failure) => {
+                let err = new_err_with_attributes::<WorkflowFailureError>(py, format!(
+                    "Workflow {} failed",
+                    workflow_id
+                ), vec![
+                    ("workflow_id", workflow_id.into_py(py)),
+                ]);
+                if let Some(failure) = failure {
+                    err.instance(py).setattr("__cause__", user_code_failure_to_exception(py, failure)?)?;
+                }
+                Err(err)
+            }
+            TestRunResult::Cancelled => Err(new_err_with_attributes::<WorkflowCancelledError>(py, format!(
+                "Workflow {} has been cancelled",
+                workflow_id
+            ), vec![
+                ("workflow_id", workflow_id.into_py(py)),
+            ])),
+        }
+    }
+
+    fn with_run<F: FnOnce(&mut TestRun)>(&self, workflow_id: &str, f: F) -> PyResult<()> {
+        match self.state.record.lock().unwrap().runs.get_mut(workflow_id) {
+            None => return Err(PyValueError::new_err(format!(
+                "Workflow {} isn't running",
+                workflow_id
+            ))),
+            Some(run) => f(run),
+        }
+        self.state.changed.notify_waiters();
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl WrappedWorkflowEnvironment {
+    #[new]
+    #[args(start_time = "None", "*", auto_time_skipping = "true", data_converter = "None")]
+    fn new(py: Python,
+           start_time: Option<UtcDateTime>,
+           auto_time_skipping: bool,
+           data_converter: Option<WrappedDataConverter>) -> PyResult<(Self, WrappedCore)> {
+        let data_converter = match data_converter {
+            None => WrappedDataConverter::new_default(py)?,
+            Some(data_converter) => data_converter,
+        };
+        let state = Arc::new(TestServerState {
+            record: Mutex::new(TestServerRecord {
+                now: match start_time {
+                    None => Utc::now(),
+                    Some(start_time) => start_time.0,
+                },
+                auto_time_skipping,
+                registered_task_queues: HashSet::new(),
+                shut_down_task_queues: HashSet::new(),
+                shut_down: false,
+                runs: HashMap::new(),
+                results: HashMap::new(),
+                activity_tasks: HashMap::new(),
+                activities: HashMap::new(),
+                next_task_token: 0,
+            }),
+            changed: Notify::new(),
+        });
+        let internal: Arc<dyn Core> = Arc::new(TestServerCoreInternal {
+            state: state.clone(),
+        });
+        let core = WrappedCore::new(internal, String::from(TEST_NAMESPACE));
+        Ok((WrappedWorkflowEnvironment { state, data_converter }, core))
+    }
+
+    #[getter]
+    fn get_now(&self) -> UtcDateTime {
+        UtcDateTime(self.state.record.lock().unwrap().now)
+    }
+
+    #[getter]
+    fn get_auto_time_skipping(&self) -> bool {
+        self.state.record.lock().unwrap().auto_time_skipping
+    }
+
+    #[setter]
+    fn set_auto_time_skipping(&self, auto_time_skipping: bool) {
+        self.state.record.lock().unwrap().auto_time_skipping = auto_time_skipping;
+        self.state.changed.notify_waiters();
+    }
+
+    // Moves the clock forward, firing every timer that is due by then, whether or not the workflows are blocked
+    fn skip_time(&self, duration: pyo3_chrono::Duration) -> PyResult<()> {
+        if duration.0 < chrono::Duration::zero() {
+            return Err(PyValueError::new_err("Time can't be skipped backwards"));
+        }
+        {
+            let mut record = self.state.record.lock().unwrap();
+            let now = record.now;
+            record.now = now.checked_add_signed(duration.0).ok_or_else(|| {
+                PyValueError::new_err(format!("Can't skip {} past {}", duration.0, now))
+            })?;
+            record.fire_due_timers();
+        }
+        self.state.changed.notify_waiters();
+        Ok(())
+    }
+
+    #[args(arguments = "Vec::new()")]
+    fn start_workflow(&self,
+                      py: Python,
+                      workflow_type: String,
+                      workflow_id: String,
+                      task_queue: String,
+                      arguments: Vec<PyObject>) -> PyResult<WrappedWorkflowExecution> {
+        let arguments = self.data_converter.encode_all(py, arguments)?;
+        let run_id = {
+            let mut record = self.state.record.lock().unwrap();
+            if record.runs.contains_key(&workflow_id) {
+                return Err(PyValueError::new_err(format!(
+                    "Workflow {} is already running",
+                    workflow_id
+                )));
+            }
+            let run = TestRun::new(&workflow_id, workflow_type, task_queue, arguments, HashMap::new());
+            let run_id = run.run_id.clone();
+            record.results.remove(&workflow_id);
+            record.runs.insert(workflow_id.clone(), run);
+            run_id
+        };
+        self.state.changed.notify_waiters();
+        Ok(WrappedWorkflowExecution {
+            workflow_id,
+            run_id,
+        })
+    }
+
+    #[args(arguments = "Vec::new()")]
+    fn signal_workflow(&self,
+                       py: Python,
+                       workflow_id: String,
+                       signal_name: String,
+                       arguments: Vec<PyObject>) -> PyResult<()> {
+        let input = self.data_converter.encode_all(py, arguments)?;
+        self.with_run(&workflow_id, |run| {
+            run.pending_jobs.push(job(WrappedJobVariant::SignalWorkflow(WrappedSignalWorkflow {
+                signal_name,
+                input,
+                identity: String::from(SIGNAL_IDENTITY),
+            })));
+        })
+    }
+
+    fn cancel_workflow(&self, workflow_id: String) -> PyResult<()> {
+        self.with_run(&workflow_id, |run| {
+            run.pending_jobs.push(job(WrappedJobVariant::CancelWorkflow(WrappedCancelWorkflow {
+                details: Vec::new(),
+            })));
+        })
+    }
+
+    // Waits for the workflow's last run to finish and returns its result, or raises WorkflowFailureError with the
+    // workflow's failure as its cause
+    fn workflow_result<'p>(slf: PyRef<Self>, py: Python<'p>, workflow_id: String) -> PyResult<&'p PyAny> {
+        {
+            let record = slf.state.record.lock().unwrap();
+            if !record.runs.contains_key(&workflow_id) && !record.results.contains_key(&workflow_id) {
+                return Err(PyValueError::new_err(format!(
+                    "Workflow {} has never been started",
+                    workflow_id
+                )));
+            }
+        }
+
+        let environment: Py<Self> = slf.into();
+        let state = environment.borrow(py).state.clone();
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            let result = loop {
+                let changed = state.changed.notified();
+                let result = state.record.lock().unwrap().results.get(&workflow_id).cloned();
+                if let Some(result) = result {
+                    break result;
+                }
+                changed.await;
+            };
+            Python::with_gil(|py| environment.borrow(py).result_to_py(py, &workflow_id, result))
+        })
+    }
+}
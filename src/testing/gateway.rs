@@ -0,0 +1,163 @@
+use std::time::Duration as StdDuration;
+
+use async_trait::async_trait;
+use temporal_sdk_core::{
+    ServerGatewayApis,
+    TaskToken,
+    WorkflowTaskCompletion,
+    protos::temporal::api::{
+        common::v1::{
+            Payload,
+            Payloads,
+        },
+        enums::v1::WorkflowTaskFailedCause,
+        failure::v1::Failure,
+        query::v1::{
+            WorkflowQuery,
+            WorkflowQueryResult,
+        },
+        workflowservice::v1::{
+            DescribeWorkflowExecutionResponse,
+            GetWorkflowExecutionHistoryResponse,
+            PollActivityTaskQueueResponse,
+            PollWorkflowTaskQueueResponse,
+            QueryWorkflowResponse,
+            RecordActivityTaskHeartbeatResponse,
+            ResetStickyTaskQueueResponse,
+            RespondActivityTaskCanceledResponse,
+            RespondActivityTaskCompletedResponse,
+            RespondActivityTaskFailedResponse,
+            RespondQueryTaskCompletedResponse,
+            RespondWorkflowTaskCompletedResponse,
+            RespondWorkflowTaskFailedResponse,
+            SignalWorkflowExecutionResponse,
+            StartWorkflowExecutionResponse,
+        },
+    },
+};
+use tonic::Status;
+
+
+// Gateway of the Cores that tests run workers against, which have no server to talk to, so every call fails rather
+// than anything that asks a Core for its gateway bringing the worker down
+pub(crate) struct NoServerGateway {
+    core_name: &'static str,
+}
+
+impl NoServerGateway {
+    pub(crate) fn new(core_name: &'static str) -> Self {
+        NoServerGateway {
+            core_name,
+        }
+    }
+
+    fn unavailable<T>(&self) -> Result<T, Status> {
+        Err(Status::failed_precondition(format!("{} doesn't have a server gateway", self.core_name)))
+    }
+}
+
+#[async_trait]
+impl ServerGatewayApis for NoServerGateway {
+    async fn start_workflow(&self,
+                            _input: Vec<Payload>,
+                            _task_queue: String,
+                            _workflow_id: String,
+                            _workflow_type: String,
+                            _task_timeout: Option<StdDuration>) -> Result<StartWorkflowExecutionResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn poll_workflow_task(&self,
+                                _task_queue: String,
+                                _is_sticky: bool) -> Result<PollWorkflowTaskQueueResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn poll_activity_task(&self, _task_queue: String) -> Result<PollActivityTaskQueueResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn reset_sticky_task_queue(&self,
+                                     _workflow_id: String,
+                                     _run_id: String) -> Result<ResetStickyTaskQueueResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn complete_workflow_task(&self,
+                                    _request: WorkflowTaskCompletion,
+    ) -> Result<RespondWorkflowTaskCompletedResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn complete_activity_task(&self,
+                                    _task_token: TaskToken,
+                                    _result: Option<Payloads>,
+    ) -> Result<RespondActivityTaskCompletedResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn record_activity_heartbeat(&self,
+                                       _task_token: TaskToken,
+                                       _details: Option<Payloads>,
+    ) -> Result<RecordActivityTaskHeartbeatResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn cancel_activity_task(&self,
+                                  _task_token: TaskToken,
+                                  _details: Option<Payloads>,
+    ) -> Result<RespondActivityTaskCanceledResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn fail_activity_task(&self,
+                                _task_token: TaskToken,
+                                _failure: Option<Failure>) -> Result<RespondActivityTaskFailedResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn fail_workflow_task(&self,
+                                _task_token: TaskToken,
+                                _cause: WorkflowTaskFailedCause,
+                                _failure: Option<Failure>) -> Result<RespondWorkflowTaskFailedResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn signal_workflow_execution(&self,
+                                       _workflow_id: String,
+                                       _run_id: String,
+                                       _signal_name: String,
+                                       _payloads: Option<Payloads>,
+    ) -> Result<SignalWorkflowExecutionResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn query_workflow_execution(&self,
+                                      _workflow_id: String,
+                                      _run_id: String,
+                                      _query: WorkflowQuery) -> Result<QueryWorkflowResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn describe_workflow_execution(&self,
+                                         _workflow_id: String,
+                                         _run_id: Option<String>,
+    ) -> Result<DescribeWorkflowExecutionResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn get_workflow_execution_history(&self,
+                                            _workflow_id: String,
+                                            _run_id: Option<String>,
+                                            _page_token: Vec<u8>,
+    ) -> Result<GetWorkflowExecutionHistoryResponse, Status> {
+        self.unavailable()
+    }
+
+    async fn respond_legacy_query(&self,
+                                  _task_token: TaskToken,
+                                  _query_result: WorkflowQueryResult,
+    ) -> Result<RespondQueryTaskCompletedResponse, Status> {
+        self.unavailable()
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use pyo3::prelude::*;
+use temporal_sdk_core::{
+    Core,
+    ServerGatewayApis,
+    WorkerConfig,
+    errors::{
+        CompleteActivityError,
+        CompleteWfError,
+        PollActivityError,
+        PollWfError,
+        WorkerRegistrationError,
+    },
+    protos::coresdk::{
+        ActivityHeartbeat,
+        ActivityTaskCompletion,
+        activity_task::ActivityTask,
+        workflow_activation::WfActivation,
+        workflow_completion::WfActivationCompletion,
+    },
+};
+use tokio::sync::Notify;
+
+use crate::WrappedCore;
+use crate::protos::coresdk::{
+    WrappedActivityHeartbeat,
+    WrappedActivityTaskCompletion,
+    activity_task::WrappedActivityTask,
+    workflow_activation::WrappedWfActivation,
+    workflow_completion::WrappedWfActivationCompletion,
+};
+use crate::testing::TEST_NAMESPACE;
+use crate::testing::gateway::NoServerGateway;
+
+
+// Scripted tasks waiting to be polled and everything that the worker has sent back
+#[derive(Default)]
+struct MockCoreRecord {
+    registered_task_queues: HashSet<String>,
+    shut_down_task_queues: HashSet<String>,
+    shut_down: bool,
+    wf_activations: HashMap<String, VecDeque<WfActivation>>,
+    activity_tasks: HashMap<String, VecDeque<ActivityTask>>,
+    wf_completions: Vec<WfActivationCompletion>,
+    activity_completions: Vec<ActivityTaskCompletion>,
+    activity_heartbeats: Vec<ActivityHeartbeat>,
+    evicted_run_ids: Vec<String>,
+}
+
+impl MockCoreRecord {
+    fn is_shut_down(&self, task_queue: &str) -> bool {
+        self.shut_down || self.shut_down_task_queues.contains(task_queue)
+    }
+}
+
+
+#[derive(Default)]
+struct MockCoreState {
+    record: Mutex<MockCoreRecord>,
+    // Wakes up pending polls whenever a task gets scripted or something gets shut down
+    changed: Notify,
+}
+
+
+// Core that never talks to a server: polls get served from the scripted tasks, waiting for more to get scripted when
+// there are none left, and completions and heartbeats get recorded
+struct MockCoreInternal {
+    state: Arc<MockCoreState>,
+}
+
+#[async_trait]
+impl Core for MockCoreInternal {
+    async fn register_worker(&self, config: WorkerConfig) -> Result<(), WorkerRegistrationError> {
+        let mut record = self.state.record.lock().unwrap();
+        if !record.registered_task_queues.insert(config.task_queue.clone()) {
+            return Err(WorkerRegistrationError::WorkerAlreadyRegisteredForQueue(config.task_queue));
+        }
+        Ok(())
+    }
+
+    async fn poll_workflow_task(&self, task_queue: &str) -> Result<WfActivation, PollWfError> {
+        loop {
+            let changed = self.state.changed.notified();
+            {
+                let mut record = self.state.record.lock().unwrap();
+                if !record.registered_task_queues.contains(task_queue) {
+                    return Err(PollWfError::NoWorkerForQueue(String::from(task_queue)));
+                }
+                if let Some(wf_activation) = record.wf_activations.get_mut(task_queue).and_then(|queue| queue.pop_front()) {
+                    return Ok(wf_activation);
+                }
+                if record.is_shut_down(task_queue) {
+                    return Err(PollWfError::ShutDown);
+                }
+            }
+            changed.await;
+        }
+    }
+
+    async fn poll_activity_task(&self, task_queue: &str) -> Result<ActivityTask, PollActivityError> {
+        loop {
+            let changed = self.state.changed.notified();
+            {
+                let mut record = self.state.record.lock().unwrap();
+                if !record.registered_task_queues.contains(task_queue) {
+                    return Err(PollActivityError::NoWorkerForQueue(String::from(task_queue)));
+                }
+                if let Some(activity_task) = record.activity_tasks.get_mut(task_queue).and_then(|queue| queue.pop_front()) {
+                    return Ok(activity_task);
+                }
+                if record.is_shut_down(task_queue) {
+                    return Err(PollActivityError::ShutDown);
+                }
+            }
+            changed.await;
+        }
+    }
+
+    async fn complete_workflow_task(&self, completion: WfActivationCompletion) -> Result<(), CompleteWfError> {
+        self.state.record.lock().unwrap().wf_completions.push(completion);
+        Ok(())
+    }
+
+    async fn complete_activity_task(&self, completion: ActivityTaskCompletion) -> Result<(), CompleteActivityError> {
+        self.state.record.lock().unwrap().activity_completions.push(completion);
+        Ok(())
+    }
+
+    fn record_activity_heartbeat(&self, details: ActivityHeartbeat) {
+        self.state.record.lock().unwrap().activity_heartbeats.push(details);
+    }
+
+    fn request_workflow_eviction(&self, run_id: &str) {
+        self.state.record.lock().unwrap().evicted_run_ids.push(String::from(run_id));
+    }
+
+    fn server_gateway(&self) -> Arc<dyn ServerGatewayApis> {
+        Arc::new(NoServerGateway::new("MockCore"))
+    }
+
+    async fn shutdown(&self) {
+        self.state.record.lock().unwrap().shut_down = true;
+        self.state.changed.notify_waiters();
+    }
+
+    async fn shutdown_worker(&self, task_queue: &str) {
+        self.state.record.lock().unwrap().shut_down_task_queues.insert(String::from(task_queue));
+        self.state.changed.notify_waiters();
+    }
+}
+
+
+// Drop-in replacement for Core in tests: it can be passed to Worker or polled and completed by hand, with the tasks
+// to poll scripted upfront or while the worker is running
+#[pyclass(name = "MockCore", extends = WrappedCore)]
+pub(crate) struct WrappedMockCore {
+    state: Arc<MockCoreState>,
+}
+
+#[pymethods]
+impl WrappedMockCore {
+    #[new]
+    fn new() -> (Self, WrappedCore) {
+        let state = Arc::new(MockCoreState::default());
+        let internal: Arc<dyn Core> = Arc::new(MockCoreInternal {
+            state: state.clone(),
+        });
+        (WrappedMockCore { state }, WrappedCore::new(internal, String::from(TEST_NAMESPACE)))
+    }
+
+    fn add_workflow_activation(&self, task_queue: String, wf_activation: WrappedWfActivation) {
+        self.state.record.lock().unwrap().wf_activations
+            .entry(task_queue)
+            .or_insert_with(VecDeque::new)
+            .push_back(WfActivation::from(wf_activation));
+        self.state.changed.notify_waiters();
+    }
+
+    fn add_activity_task(&self, task_queue: String, activity_task: WrappedActivityTask) -> PyResult<()> {
+        let activity_task = ActivityTask::try_from(activity_task)?;
+        self.state.record.lock().unwrap().activity_tasks
+            .entry(task_queue)
+            .or_insert_with(VecDeque::new)
+            .push_back(activity_task);
+        self.state.changed.notify_waiters();
+        Ok(())
+    }
+
+    #[getter]
+    fn get_registered_task_queues(&self) -> HashSet<String> {
+        self.state.record.lock().unwrap().registered_task_queues.clone()
+    }
+
+    #[getter]
+    fn get_workflow_completions(&self) -> PyResult<Vec<WrappedWfActivationCompletion>> {
+        let completions = self.state.record.lock().unwrap().wf_completions.clone();
+        completions.into_iter().map(WrappedWfActivationCompletion::try_from).collect()
+    }
+
+    #[getter]
+    fn get_activity_completions(&self) -> Vec<WrappedActivityTaskCompletion> {
+        let completions = self.state.record.lock().unwrap().activity_completions.clone();
+        completions.into_iter().map(WrappedActivityTaskCompletion::from).collect()
+    }
+
+    #[getter]
+    fn get_activity_heartbeats(&self) -> Vec<WrappedActivityHeartbeat> {
+        let heartbeats = self.state.record.lock().unwrap().activity_heartbeats.clone();
+        heartbeats.into_iter().map(WrappedActivityHeartbeat::from).collect()
+    }
+
+    #[getter]
+    fn get_evicted_run_ids(&self) -> Vec<String> {
+        self.state.record.lock().unwrap().evicted_run_ids.clone()
+    }
+}
@@ -0,0 +1,7 @@
+pub(crate) mod environment;
+pub(crate) mod gateway;
+pub(crate) mod mock_core;
+
+
+// Cores in tests don't talk to a server, so their metrics get labelled with the namespace that servers start with
+pub(crate) const TEST_NAMESPACE: &str = "default";
@@ -0,0 +1,328 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration as StdDuration, Instant};
+
+use async_trait::async_trait;
+use pyo3_chrono;
+use temporal_sdk_core::{
+    Core,
+    CoreInitOptions,
+    ServerGatewayApis,
+    WorkerConfig,
+    errors::{
+        CompleteActivityError,
+        CompleteWfError,
+        PollActivityError,
+        PollWfError,
+        WorkerRegistrationError,
+    },
+    init,
+    protos::coresdk::{
+        ActivityHeartbeat,
+        ActivityTaskCompletion,
+        activity_task::ActivityTask,
+        workflow_activation::WfActivation,
+        workflow_completion::WfActivationCompletion,
+    },
+};
+
+use crate::pollers::tls::TlsFiles;
+use crate::utils::std_duration_to_pyo3_chrono_duration;
+
+
+const DEFAULT_TLS_RELOAD_INTERVAL: StdDuration = StdDuration::from_secs(30);
+
+// How often the replaced Cores get checked for whether they still have tasks outstanding
+const RETIRED_CORE_CHECK_INTERVAL: StdDuration = StdDuration::from_secs(1);
+
+// Replaced Cores get shut down regardless after this long, for the tasks that never get completed not to keep their
+// connection open for good; long running activities that are still going by then get completed on the current Core
+const RETIRED_CORE_MAX_DRAIN_TIME: StdDuration = StdDuration::from_secs(10 * 60);
+
+
+pub(crate) fn default_tls_reload_interval() -> Option<pyo3_chrono::Duration> {
+    std_duration_to_pyo3_chrono_duration(DEFAULT_TLS_RELOAD_INTERVAL).ok()
+}
+
+
+// Core that an outstanding task came from, for it to get completed on the same one even if the current one has been
+// replaced since
+struct OutstandingTask {
+    task_queue: String,
+    core: Arc<dyn Core>,
+}
+
+
+struct ReloadingCoreState {
+    current: Arc<dyn Core>,
+    worker_configs: Vec<WorkerConfig>,
+    activation_cores: HashMap<String, OutstandingTask>,
+    activity_cores: HashMap<Vec<u8>, OutstandingTask>,
+    // Replaced Cores that haven't been shut down yet
+    retired: Vec<Arc<dyn Core>>,
+    shut_down: bool,
+}
+
+impl ReloadingCoreState {
+    fn has_outstanding_tasks(&self, core: &Arc<dyn Core>) -> bool {
+        self.activation_cores.values().chain(self.activity_cores.values()).any(|task| Arc::ptr_eq(&task.core, core))
+    }
+
+    fn forget_outstanding_tasks<F>(&mut self, forget: F)
+        where F: Fn(&OutstandingTask) -> bool {
+        self.activation_cores.retain(|_, task| !forget(task));
+        self.activity_cores.retain(|_, task| !forget(task));
+    }
+}
+
+
+// Core whose gateway gets reconnected with the new certificates whenever the TLS files change. sdk-core can't swap
+// the gateway of a Core that's running, so a new Core gets initialized with the new TLS config and has the same
+// workers registered with it, new polls go to it and the tasks that the old Core has handed out get completed on the
+// old one, which gets shut down once they're all done
+pub(crate) struct ReloadingCore {
+    opts: CoreInitOptions,
+    state: Mutex<ReloadingCoreState>,
+}
+
+impl ReloadingCore {
+    pub(crate) fn new(opts: CoreInitOptions, core: Arc<dyn Core>) -> Self {
+        ReloadingCore {
+            opts,
+            state: Mutex::new(ReloadingCoreState {
+                current: core,
+                worker_configs: Vec::new(),
+                activation_cores: HashMap::new(),
+                activity_cores: HashMap::new(),
+                retired: Vec::new(),
+                shut_down: false,
+            }),
+        }
+    }
+
+    fn current(&self) -> Arc<dyn Core> {
+        self.state.lock().unwrap().current.clone()
+    }
+
+    // Polls that the replaced Core stopped with ShutDown get retried on the current one
+    fn should_retry_poll(&self, polled: &Arc<dyn Core>) -> bool {
+        let state = self.state.lock().unwrap();
+        !state.shut_down && !Arc::ptr_eq(polled, &state.current)
+    }
+
+    fn all_cores(&self) -> Vec<Arc<dyn Core>> {
+        let state = self.state.lock().unwrap();
+        let mut cores = vec![state.current.clone()];
+        cores.extend(state.retired.iter().cloned());
+        cores
+    }
+
+    async fn reconnect(self: &Arc<Self>, files: &TlsFiles) -> Result<(), String> {
+        let tls_config = files.read().map_err(|err| err.to_string())?;
+        let mut opts = self.opts.clone();
+        opts.gateway_opts.tls_cfg = Some(tls_config);
+        let core: Arc<dyn Core> = Arc::new(init(opts).await.map_err(|err| err.to_string())?);
+
+        // Workers that get registered while the new Core is getting them registered would be missing from it, so it
+        // only gets swapped in under the same lock that finds none of them left to register
+        let mut registered_task_queues = HashSet::new();
+        let retired = loop {
+            let pending_configs = {
+                let mut state = self.state.lock().unwrap();
+                let pending_configs: Vec<WorkerConfig> = state.worker_configs.iter()
+                    .filter(|config| !registered_task_queues.contains(&config.task_queue))
+                    .cloned()
+                    .collect();
+                if pending_configs.is_empty() {
+                    if state.shut_down {
+                        break None;
+                    }
+                    let retired = std::mem::replace(&mut state.current, core.clone());
+                    state.retired.push(retired.clone());
+                    break Some(retired);
+                }
+                pending_configs
+            };
+            for config in pending_configs {
+                registered_task_queues.insert(config.task_queue.clone());
+                if let Err(err) = core.register_worker(config).await {
+                    core.shutdown().await;
+                    return Err(err.to_string());
+                }
+            }
+        };
+        match retired {
+            // Core got shut down while the new one was getting initialized
+            None => core.shutdown().await,
+            Some(retired) => {
+                tokio::spawn(shut_down_when_drained(Arc::downgrade(self), retired));
+            }
+        }
+        Ok(())
+    }
+}
+
+
+async fn shut_down_when_drained(reloading_core: Weak<ReloadingCore>, retired: Arc<dyn Core>) {
+    let retired_at = Instant::now();
+    loop {
+        let reloading_core = match reloading_core.upgrade() {
+            None => return,
+            Some(reloading_core) => reloading_core,
+        };
+        if !reloading_core.state.lock().unwrap().has_outstanding_tasks(&retired) {
+            break;
+        }
+        if retired_at.elapsed() >= RETIRED_CORE_MAX_DRAIN_TIME {
+            tracing::warn!(
+                target_url = reloading_core.opts.gateway_opts.target_url.as_str(),
+                "Shutting down the Core that TLS reloading has replaced {:?} ago with tasks still outstanding",
+                RETIRED_CORE_MAX_DRAIN_TIME
+            );
+            reloading_core.state.lock().unwrap().forget_outstanding_tasks(|task| Arc::ptr_eq(&task.core, &retired));
+            break;
+        }
+        drop(reloading_core);
+        tokio::time::sleep(RETIRED_CORE_CHECK_INTERVAL).await;
+    }
+    retired.shutdown().await;
+    if let Some(reloading_core) = reloading_core.upgrade() {
+        reloading_core.state.lock().unwrap().retired.retain(|core| !Arc::ptr_eq(core, &retired));
+    }
+}
+
+
+// Checks the files every interval and reconnects once their contents change; a change that doesn't make for a valid
+// config, e.g. the certificate having been replaced but not yet the key, gets retried on the next change
+pub(crate) async fn watch_tls_files(reloading_core: Weak<ReloadingCore>, files: TlsFiles, interval: StdDuration) {
+    let mut last_contents = files.contents();
+    loop {
+        tokio::time::sleep(interval).await;
+        let reloading_core = match reloading_core.upgrade() {
+            None => return,
+            Some(reloading_core) => reloading_core,
+        };
+        if reloading_core.state.lock().unwrap().shut_down {
+            return;
+        }
+
+        let contents = files.contents();
+        if contents == last_contents {
+            continue;
+        }
+        last_contents = contents;
+
+        let target_url = reloading_core.opts.gateway_opts.target_url.as_str();
+        match reloading_core.reconnect(&files).await {
+            Ok(()) => tracing::info!(
+                files = %files.paths().join(", "),
+                target_url,
+                "Reconnected with rotated TLS certificates"
+            ),
+            Err(err) => tracing::warn!(
+                files = %files.paths().join(", "),
+                target_url,
+                "Not reconnecting with changed TLS files, keeping the current connection: {}",
+                err
+            ),
+        }
+    }
+}
+
+
+#[async_trait]
+impl Core for ReloadingCore {
+    // The config gets recorded under the same lock that picks the current Core, so a reconnect either swaps in a Core
+    // that it has registered the worker with already or has swapped it in before the worker gets registered with it
+    async fn register_worker(&self, config: WorkerConfig) -> Result<(), WorkerRegistrationError> {
+        let core = {
+            let mut state = self.state.lock().unwrap();
+            state.worker_configs.push(config.clone());
+            state.current.clone()
+        };
+        let task_queue = config.task_queue.clone();
+        let result = core.register_worker(config).await;
+        if result.is_err() {
+            let mut state = self.state.lock().unwrap();
+            if let Some(index) = state.worker_configs.iter().rposition(|config| config.task_queue == task_queue) {
+                state.worker_configs.remove(index);
+            }
+        }
+        result
+    }
+
+    async fn poll_workflow_task(&self, task_queue: &str) -> Result<WfActivation, PollWfError> {
+        loop {
+            let core = self.current();
+            match core.poll_workflow_task(task_queue).await {
+                Err(PollWfError::ShutDown) if self.should_retry_poll(&core) => continue,
+                Err(err) => return Err(err),
+                Ok(wf_activation) => {
+                    self.state.lock().unwrap().activation_cores.insert(wf_activation.run_id.clone(), OutstandingTask {
+                        task_queue: String::from(task_queue),
+                        core,
+                    });
+                    return Ok(wf_activation);
+                }
+            }
+        }
+    }
+
+    async fn poll_activity_task(&self, task_queue: &str) -> Result<ActivityTask, PollActivityError> {
+        loop {
+            let core = self.current();
+            match core.poll_activity_task(task_queue).await {
+                Err(PollActivityError::ShutDown) if self.should_retry_poll(&core) => continue,
+                Err(err) => return Err(err),
+                Ok(activity_task) => {
+                    self.state.lock().unwrap().activity_cores.insert(activity_task.task_token.clone(), OutstandingTask {
+                        task_queue: String::from(task_queue),
+                        core,
+                    });
+                    return Ok(activity_task);
+                }
+            }
+        }
+    }
+
+    async fn complete_workflow_task(&self, completion: WfActivationCompletion) -> Result<(), CompleteWfError> {
+        let task = self.state.lock().unwrap().activation_cores.remove(&completion.run_id);
+        task.map_or_else(|| self.current(), |task| task.core).complete_workflow_task(completion).await
+    }
+
+    async fn complete_activity_task(&self, completion: ActivityTaskCompletion) -> Result<(), CompleteActivityError> {
+        let task = self.state.lock().unwrap().activity_cores.remove(&completion.task_token);
+        task.map_or_else(|| self.current(), |task| task.core).complete_activity_task(completion).await
+    }
+
+    fn record_activity_heartbeat(&self, details: ActivityHeartbeat) {
+        let core = self.state.lock().unwrap().activity_cores.get(&details.task_token).map(|task| task.core.clone());
+        core.unwrap_or_else(|| self.current()).record_activity_heartbeat(details)
+    }
+
+    fn request_workflow_eviction(&self, run_id: &str) {
+        let core = self.state.lock().unwrap().activation_cores.get(run_id).map(|task| task.core.clone());
+        core.unwrap_or_else(|| self.current()).request_workflow_eviction(run_id)
+    }
+
+    fn server_gateway(&self) -> Arc<dyn ServerGatewayApis> {
+        self.current().server_gateway()
+    }
+
+    // Tasks that are still outstanding once the workers are shut down never get completed
+    async fn shutdown(&self) {
+        self.state.lock().unwrap().shut_down = true;
+        for core in self.all_cores() {
+            core.shutdown().await;
+        }
+        self.state.lock().unwrap().forget_outstanding_tasks(|_| true);
+    }
+
+    async fn shutdown_worker(&self, task_queue: &str) {
+        self.state.lock().unwrap().worker_configs.retain(|config| config.task_queue != task_queue);
+        for core in self.all_cores() {
+            core.shutdown_worker(task_queue).await;
+        }
+        self.state.lock().unwrap().forget_outstanding_tasks(|task| task.task_queue == task_queue);
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use pyo3::types::{
+    IntoPyDict,
+    PyDict,
+};
+
+use crate::converter::data_converter::WrappedDataConverter;
+use crate::protos::coresdk::common::WrappedPayload;
+
+
+// Header that Temporal's other SDKs carry the propagated trace context in, as a JSON object of the propagator's fields
+const TRACER_HEADER: &str = "_tracer-data";
+
+const TRACER_NAME: &str = "pytemporalio";
+
+
+// OpenTelemetry's API is an optional dependency, without it nothing gets traced or propagated
+fn opentelemetry<'p>(py: Python<'p>, module: &str) -> Option<&'p PyModule> {
+    py.import(format!("opentelemetry.{}", module).as_str()).ok()
+}
+
+
+// Tracing is best effort: whatever goes wrong with it gets printed rather than failing the workflow or activity
+fn best_effort<T>(py: Python, result: PyResult<Option<T>>) -> Option<T> {
+    match result {
+        Ok(value) => value,
+        Err(err) => {
+            err.print(py);
+            None
+        }
+    }
+}
+
+
+// OpenTelemetry context of the headers' trace, or a context without a trace if they don't carry one
+pub(crate) fn extract_context(py: Python,
+                              data_converter: &WrappedDataConverter,
+                              headers: &HashMap<String, WrappedPayload>) -> Option<PyObject> {
+    best_effort(py, (|| -> PyResult<_> {
+        let propagate = match opentelemetry(py, "propagate") {
+            None => return Ok(None),
+            Some(propagate) => propagate,
+        };
+        let carrier = match headers.get(TRACER_HEADER) {
+            None => PyDict::new(py).into(),
+            Some(payload) => data_converter.decode(py, payload.clone())?,
+        };
+        Ok(Some(propagate.call_method1("extract", (carrier,))?.into()))
+    })())
+}
+
+
+pub(crate) fn inject_context(py: Python,
+                             data_converter: &WrappedDataConverter,
+                             context: &PyObject,
+                             headers: &mut HashMap<String, WrappedPayload>) {
+    best_effort(py, (|| -> PyResult<_> {
+        let propagate = match opentelemetry(py, "propagate") {
+            None => return Ok(None),
+            Some(propagate) => propagate,
+        };
+        let carrier = PyDict::new(py);
+        propagate.call_method("inject", (carrier,), Some([("context", context)].into_py_dict(py)))?;
+        if !carrier.is_empty() {
+            headers.insert(String::from(TRACER_HEADER), data_converter.encode(py, carrier)?);
+        }
+        Ok(Some(()))
+    })());
+}
+
+
+pub(crate) struct TraceSpan {
+    span: PyObject,
+    // Parent's context with the span set as the current one
+    context: PyObject,
+}
+
+impl TraceSpan {
+    pub(crate) fn start(py: Python,
+                        name: &str,
+                        parent: Option<&PyObject>,
+                        attributes: Vec<(&str, String)>) -> Option<Self> {
+        best_effort(py, (|| -> PyResult<_> {
+            let trace = match opentelemetry(py, "trace") {
+                None => return Ok(None),
+                Some(trace) => trace,
+            };
+            let tracer = trace.call_method1("get_tracer", (TRACER_NAME,))?;
+
+            let span_kwargs = PyDict::new(py);
+            span_kwargs.set_item("attributes", attributes.into_py_dict(py))?;
+            let context_kwargs = PyDict::new(py);
+            if let Some(parent) = parent {
+                span_kwargs.set_item("context", parent)?;
+                context_kwargs.set_item("context", parent)?;
+            }
+            let span = tracer.call_method("start_span", (name,), Some(span_kwargs))?;
+            let context = trace.call_method("set_span_in_context", (span,), Some(context_kwargs))?;
+            Ok(Some(TraceSpan {
+                span: span.into(),
+                context: context.into(),
+            }))
+        })())
+    }
+
+    pub(crate) fn context(&self, py: Python) -> PyObject {
+        self.context.clone_ref(py)
+    }
+
+    // Makes the span the current one for whatever runs in the contextvars.Context from now on
+    pub(crate) fn attach(&self, py: Python, contextvars_context: &PyObject) {
+        best_effort(py, (|| -> PyResult<_> {
+            let attach = py.import("opentelemetry.context")?.getattr("attach")?;
+            contextvars_context.call_method1(py, "run", (attach, self.context.clone_ref(py)))?;
+            Ok(Some(()))
+        })());
+    }
+
+    // Error, if any, gets recorded on the span and marks it as failed
+    pub(crate) fn end(self, py: Python, error: Option<&PyErr>) {
+        best_effort(py, (|| -> PyResult<_> {
+            if let Some(error) = error {
+                let trace = py.import("opentelemetry.trace")?;
+                let status = trace.getattr("Status")?.call1((
+                    trace.getattr("StatusCode")?.getattr("ERROR")?,
+                    error.instance(py).str()?,
+                ))?;
+                self.span.call_method1(py, "record_exception", (error.instance(py),))?;
+                self.span.call_method1(py, "set_status", (status,))?;
+            }
+            self.span.call_method0(py, "end")?;
+            Ok(Some(()))
+        })());
+    }
+}
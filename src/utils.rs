@@ -1,13 +1,22 @@
-use std::convert::TryInto;
 use std::collections::HashMap;
 use std::time::Duration as StdDuration;
 
-use chrono;
+use chrono::{
+    self,
+    Datelike,
+    Timelike,
+};
 use prost_types::{
     Duration as ProstDuration,
     Timestamp as ProstTimestamp,
 };
+use pyo3::prelude::*;
 use pyo3::exceptions::PyValueError;
+use pyo3::types::{
+    PyDateAccess,
+    PyDateTime,
+    PyTimeAccess,
+};
 use pyo3_chrono;
 use temporal_sdk_core::protos::coresdk::common::Payload;
 
@@ -77,31 +86,100 @@ pub(crate) fn pyo3_chrono_duration_to_prost_duration(duration: Option<pyo3_chron
 }
 
 
-// FIXME make sure duration since epoch works fine
-pub(crate) fn prost_types_timestamp_to_u128(timestamp: Option<ProstTimestamp>) -> Option<u128> {
-    match timestamp {
-        None => None,
-        Some(ts) => Some(ts.seconds as u128 * 1000 + ts.nanos as u128),
+// Python's datetime can't go past these years
+const MIN_DATETIME_YEAR: i32 = 1;
+const MAX_DATETIME_YEAR: i32 = 9999;
+
+
+fn python_utc_timezone(py: Python) -> PyResult<PyObject> {
+    Ok(py.import("datetime")?.getattr("timezone")?.getattr("utc")?.into())
+}
+
+
+// UTC timestamp that gets exposed to Python as a timezone-aware datetime; nanoseconds are kept around so that the
+// round trip to prost's Timestamp is lossless, but the datetime only gets microseconds as that's all it can hold
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct UtcDateTime(pub chrono::DateTime<chrono::Utc>);
+
+impl IntoPy<PyObject> for UtcDateTime {
+    fn into_py(self, py: Python) -> PyObject {
+        // Range gets checked when converting from prost's Timestamp or Python's datetime so this can't fail
+        PyDateTime::new(
+            py,
+            self.0.year(),
+            self.0.month() as u8,
+            self.0.day() as u8,
+            self.0.hour() as u8,
+            self.0.minute() as u8,
+            self.0.second() as u8,
+            self.0.nanosecond() / 1_000,
+            Some(&python_utc_timezone(py).unwrap()),
+        ).unwrap().into_py(py)
+    }
+}
+
+impl<'source> FromPyObject<'source> for UtcDateTime {
+    fn extract(value: &'source PyAny) -> PyResult<Self> {
+        let py = value.py();
+        let datetime = value.downcast::<PyDateTime>()?;
+        if datetime.getattr("tzinfo")?.is_none() {
+            return Err(PyValueError::new_err(format!(
+                "Datetime {} is not timezone-aware",
+                datetime.str()?
+            )));
+        }
+        let utc_datetime = datetime.call_method1("astimezone", (python_utc_timezone(py)?,))?.downcast::<PyDateTime>()?;
+        let naive_datetime = chrono::NaiveDate::from_ymd_opt(
+            utc_datetime.get_year(),
+            utc_datetime.get_month() as u32,
+            utc_datetime.get_day() as u32,
+        ).and_then(|date| date.and_hms_micro_opt(
+            utc_datetime.get_hour() as u32,
+            utc_datetime.get_minute() as u32,
+            utc_datetime.get_second() as u32,
+            utc_datetime.get_microsecond(),
+        ));
+        match naive_datetime {
+            None => Err(PyValueError::new_err(format!(
+                "Datetime {} is out of range",
+                datetime.str()?
+            ))),
+            Some(naive_datetime) => Ok(UtcDateTime(chrono::DateTime::from_utc(naive_datetime, chrono::Utc))),
+        }
     }
 }
 
 
-// FIXME untested at all
-pub(crate) fn u128_to_prost_types_timestamp(timestamp: Option<u128>) -> Option<ProstTimestamp> {
-    match timestamp {
-        None => None,
-        Some(ts) => {
-            let seconds = ts / 1000;
-            let nanos = ts - (seconds * 1000);
-            Some(ProstTimestamp {
-                seconds: seconds.try_into().unwrap(),
-                nanos: nanos.try_into().unwrap(),
-            })
+pub(crate) fn prost_types_timestamp_to_utc_datetime(timestamp: Option<ProstTimestamp>) -> Result<Option<UtcDateTime>, crate::PyErr> {
+    let ts = match timestamp {
+        None => return Ok(None),
+        Some(ts) => ts,
+    };
+    let naive_datetime = match ts.nanos {
+        nanos if nanos < 0 || nanos >= 1_000_000_000 => None,
+        nanos => chrono::NaiveDateTime::from_timestamp_opt(ts.seconds, nanos as u32),
+    };
+    match naive_datetime {
+        Some(naive_datetime) if naive_datetime.year() >= MIN_DATETIME_YEAR && naive_datetime.year() <= MAX_DATETIME_YEAR => {
+            Ok(Some(UtcDateTime(chrono::DateTime::from_utc(naive_datetime, chrono::Utc))))
         }
+        _ => Err(PyValueError::new_err(format!(
+            "Timestamp with {} seconds and {} nanos is out of range",
+            ts.seconds,
+            ts.nanos
+        ))),
     }
 }
 
 
+pub(crate) fn utc_datetime_to_prost_types_timestamp(datetime: Option<UtcDateTime>) -> Option<ProstTimestamp> {
+    datetime.map(|datetime| ProstTimestamp {
+        seconds: datetime.0.timestamp(),
+        nanos: datetime.0.timestamp_subsec_nanos() as i32,
+    })
+}
+
+
 // FIXME rename to a shorter name
 pub(crate) fn vec_of_payloads_to_vec_of_wrapped_payloads(payloads: Vec<Payload>) -> Vec<WrappedPayload> {
     payloads.iter().map(|x| WrappedPayload::from(x)).collect::<Vec<_>>()
@@ -1,9 +1,21 @@
 use std::convert::TryFrom;
 
+use chrono;
 use pyo3::prelude::*;
+use pyo3::types::{
+    IntoPyDict,
+    PyDict,
+};
 use pyo3_chrono;
-use temporal_sdk_core::WorkerConfig;
+use temporal_sdk_core::{
+    WorkerConfig,
+    WorkerConfigBuilder,
+};
 
+use crate::errors::{
+    ConfigError,
+    new_err_with_attributes,
+};
 use crate::utils::{
     std_duration_to_pyo3_chrono_duration,
     pyo3_chrono_duration_to_std_duration,
@@ -12,39 +24,140 @@ use crate::utils::{
 #[pyclass(name = "WorkerConfig")]
 #[derive(Clone)]
 pub struct WrappedWorkerConfig {
+    #[pyo3(get)]
     pub task_queue: String,
+    #[pyo3(get)]
     pub max_outstanding_workflow_tasks: usize,
+    #[pyo3(get)]
     pub max_outstanding_activities: usize,
+    #[pyo3(get)]
     pub max_concurrent_wft_polls: usize,
+    #[pyo3(get)]
     pub nonsticky_to_sticky_poll_ratio: f32,
+    #[pyo3(get)]
     pub max_concurrent_at_polls: usize,
+    #[pyo3(get)]
     pub no_remote_activities: bool,
+    #[pyo3(get)]
     pub sticky_queue_schedule_to_start_timeout: pyo3_chrono::Duration,
 }
 
 
+impl WrappedWorkerConfig {
+    // Whatever sdk-core's own builder defaults to, so that the two can't drift apart
+    fn with_defaults(py: Python, task_queue: String) -> PyResult<Self> {
+        let defaults = WorkerConfigBuilder::default()
+            .task_queue(task_queue)
+            .build()
+            .map_err(|err| new_err_with_attributes::<ConfigError>(py, err.to_string(), vec![]))?;
+        WrappedWorkerConfig::try_from(defaults)
+    }
+
+    fn validate(&self, py: Python) -> PyResult<()> {
+        let mut invalid_fields: Vec<(&str, String)> = Vec::new();
+        if self.task_queue.trim().is_empty() {
+            invalid_fields.push(("task_queue", String::from("must not be empty")));
+        }
+        for (field, value) in [
+            ("max_outstanding_workflow_tasks", self.max_outstanding_workflow_tasks),
+            ("max_outstanding_activities", self.max_outstanding_activities),
+            ("max_concurrent_wft_polls", self.max_concurrent_wft_polls),
+            ("max_concurrent_at_polls", self.max_concurrent_at_polls),
+        ] {
+            if value == 0 {
+                invalid_fields.push((field, String::from("must be greater than 0")));
+            }
+        }
+        if !(0.0..=1.0).contains(&self.nonsticky_to_sticky_poll_ratio) {
+            invalid_fields.push(("nonsticky_to_sticky_poll_ratio", format!(
+                "must be between 0 and 1, got {}",
+                self.nonsticky_to_sticky_poll_ratio
+            )));
+        }
+        if self.sticky_queue_schedule_to_start_timeout.0 < chrono::Duration::zero() {
+            invalid_fields.push(("sticky_queue_schedule_to_start_timeout", String::from("must not be negative")));
+        }
+
+        if invalid_fields.is_empty() {
+            return Ok(());
+        }
+        let message = invalid_fields.iter()
+            .map(|(field, problem)| format!("{} {}", field, problem))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Err(new_err_with_attributes::<ConfigError>(py, format!("Invalid worker config: {}", message), vec![
+            ("invalid_fields", invalid_fields.into_py_dict(py).into()),
+        ]))
+    }
+
+    fn to_kwargs<'p>(&self, py: Python<'p>) -> &'p PyDict {
+        let kwargs = PyDict::new(py);
+        // Setting items of a fresh dict with string keys doesn't fail
+        let _ = kwargs.set_item("task_queue", &self.task_queue);
+        let _ = kwargs.set_item("max_outstanding_workflow_tasks", self.max_outstanding_workflow_tasks);
+        let _ = kwargs.set_item("max_outstanding_activities", self.max_outstanding_activities);
+        let _ = kwargs.set_item("max_concurrent_wft_polls", self.max_concurrent_wft_polls);
+        let _ = kwargs.set_item("nonsticky_to_sticky_poll_ratio", self.nonsticky_to_sticky_poll_ratio);
+        let _ = kwargs.set_item("max_concurrent_at_polls", self.max_concurrent_at_polls);
+        let _ = kwargs.set_item("no_remote_activities", self.no_remote_activities);
+        let _ = kwargs.set_item("sticky_queue_schedule_to_start_timeout", self.sticky_queue_schedule_to_start_timeout);
+        kwargs
+    }
+}
+
+
 #[pymethods]
 impl WrappedWorkerConfig {
-    // FIXME defaults
+    // Every field but task_queue is optional and raises ConfigError, listing all of the invalid ones, if it's out of
+    // range
     #[new]
-    fn new(task_queue: String,
-           max_outstanding_workflow_tasks: usize,
-           max_outstanding_activities: usize,
-           max_concurrent_wft_polls: usize,
-           nonsticky_to_sticky_poll_ratio: f32,
-           max_concurrent_at_polls: usize,
-           no_remote_activities: bool,
-           sticky_queue_schedule_to_start_timeout: pyo3_chrono::Duration) -> Self {
-        WrappedWorkerConfig {
-            task_queue,
-            max_outstanding_workflow_tasks,
-            max_outstanding_activities,
-            max_concurrent_wft_polls,
-            nonsticky_to_sticky_poll_ratio,
-            max_concurrent_at_polls,
-            no_remote_activities,
-            sticky_queue_schedule_to_start_timeout,
+    #[args(
+        "*",
+        max_outstanding_workflow_tasks = "None",
+        max_outstanding_activities = "None",
+        max_concurrent_wft_polls = "None",
+        nonsticky_to_sticky_poll_ratio = "None",
+        max_concurrent_at_polls = "None",
+        no_remote_activities = "None",
+        sticky_queue_schedule_to_start_timeout = "None",
+    )]
+    pub(crate) fn new(py: Python,
+                      task_queue: String,
+                      max_outstanding_workflow_tasks: Option<usize>,
+                      max_outstanding_activities: Option<usize>,
+                      max_concurrent_wft_polls: Option<usize>,
+                      nonsticky_to_sticky_poll_ratio: Option<f32>,
+                      max_concurrent_at_polls: Option<usize>,
+                      no_remote_activities: Option<bool>,
+                      sticky_queue_schedule_to_start_timeout: Option<pyo3_chrono::Duration>) -> PyResult<Self> {
+        let defaults = WrappedWorkerConfig::with_defaults(py, task_queue)?;
+        let config = WrappedWorkerConfig {
+            task_queue: defaults.task_queue,
+            max_outstanding_workflow_tasks: max_outstanding_workflow_tasks
+                .unwrap_or(defaults.max_outstanding_workflow_tasks),
+            max_outstanding_activities: max_outstanding_activities.unwrap_or(defaults.max_outstanding_activities),
+            max_concurrent_wft_polls: max_concurrent_wft_polls.unwrap_or(defaults.max_concurrent_wft_polls),
+            nonsticky_to_sticky_poll_ratio: nonsticky_to_sticky_poll_ratio
+                .unwrap_or(defaults.nonsticky_to_sticky_poll_ratio),
+            max_concurrent_at_polls: max_concurrent_at_polls.unwrap_or(defaults.max_concurrent_at_polls),
+            no_remote_activities: no_remote_activities.unwrap_or(defaults.no_remote_activities),
+            sticky_queue_schedule_to_start_timeout: sticky_queue_schedule_to_start_timeout
+                .unwrap_or(defaults.sticky_queue_schedule_to_start_timeout),
+        };
+        config.validate(py)?;
+        Ok(config)
+    }
+
+    // Copy of the config with the given fields changed, e.g. config.replace(task_queue="other-queue")
+    #[args(changes = "**")]
+    fn replace(&self, py: Python, changes: Option<&PyDict>) -> PyResult<Self> {
+        let kwargs = self.to_kwargs(py);
+        if let Some(changes) = changes {
+            for (field, value) in changes {
+                kwargs.set_item(field, value)?;
+            }
         }
+        py.get_type::<WrappedWorkerConfig>().call((), Some(kwargs))?.extract()
     }
 }
 
@@ -0,0 +1,526 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::types::{PyDict, PyTuple};
+use pyo3_asyncio;
+use tokio::sync::{
+    oneshot,
+    Semaphore,
+};
+use temporal_sdk_core::{
+    WorkerConfig,
+    protos::coresdk::{
+        ActivityTaskCompletion,
+        activity_result::{
+            ActivityResult,
+            activity_result,
+            Success as ActivitySuccess,
+            Failure as ActivityFailure,
+            Cancelation as ActivityCancelation,
+        },
+        activity_task::{
+            ActivityTask,
+            Start,
+            activity_task,
+        },
+        common::Payload,
+        workflow_activation::{
+            WfActivation,
+            wf_activation_job,
+        },
+        workflow_commands::WorkflowCommand,
+        workflow_completion::{
+            WfActivationCompletion,
+            wf_activation_completion,
+            Success as WfSuccess,
+            Failure as WfFailure,
+        },
+    },
+};
+
+use crate::WrappedCore;
+use crate::activity::{
+    cancellation::ActivityCancellation,
+    info::{
+        WrappedActivityInfo,
+        activity_context,
+    },
+    registry::registered_activities,
+};
+use crate::converter::{
+    data_converter::WrappedDataConverter,
+    failure::py_err_to_user_code_failure,
+};
+use crate::errors::ShutdownError;
+use crate::protos::coresdk::{
+    activity_task::WrappedStart,
+    workflow_activation::WrappedWfActivation,
+};
+use crate::trace_context::{
+    TraceSpan,
+    extract_context,
+};
+use crate::worker::config::WrappedWorkerConfig;
+use crate::workflow::event_loop::WrappedWorkflowEventLoop;
+
+
+// asyncio.CancelledError isn't an Exception subclass on every Python version, so it gets looked up at runtime
+fn is_cancelled_error(py: Python, err: &PyErr) -> bool {
+    match py.import("asyncio").and_then(|asyncio| asyncio.getattr("CancelledError")) {
+        Ok(cancelled_error) => err.matches(py, cancelled_error),
+        Err(_) => false,
+    }
+}
+
+
+#[pyclass]
+struct HandlerDoneCallback {
+    sender: Option<oneshot::Sender<PyResult<PyObject>>>,
+}
+
+#[pymethods]
+impl HandlerDoneCallback {
+    #[call]
+    fn __call__(&mut self, future: &PyAny) {
+        let result = future.call_method0("result").map(|result| result.into());
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(result);
+        }
+    }
+}
+
+
+#[pyclass]
+struct StartActivityTask {
+    coroutine: Option<PyObject>,
+    done_callback: Option<HandlerDoneCallback>,
+    cancellation: Arc<ActivityCancellation>,
+}
+
+#[pymethods]
+impl StartActivityTask {
+    // Gets called on the worker's event loop, in the activity's context which the task inherits
+    #[call]
+    fn __call__(&mut self, py: Python) -> PyResult<()> {
+        let (coroutine, done_callback) = match (self.coroutine.take(), self.done_callback.take()) {
+            (Some(coroutine), Some(done_callback)) => (coroutine, done_callback),
+            _ => return Ok(()),
+        };
+        let task = py.import("asyncio")?.call_method1("ensure_future", (coroutine,))?;
+        task.call_method1("add_done_callback", (done_callback,))?;
+        self.cancellation.set_task(py, task.into())
+    }
+}
+
+
+// Coroutine functions get run on the worker's event loop, plain functions get run on a blocking thread so that they
+// don't stall the poll loops; either way, they get run in the given contextvars.Context
+async fn call_handler(event_loop: PyObject,
+                      context: PyObject,
+                      cancellation: Arc<ActivityCancellation>,
+                      handler: PyObject,
+                      arguments: Vec<PyObject>) -> PyResult<PyObject> {
+    let is_coroutine_function = Python::with_gil(|py| -> PyResult<bool> {
+        py.import("inspect")?.call_method1("iscoroutinefunction", (handler.as_ref(py),))?.is_true()
+    })?;
+
+    let mut run_arguments = vec![handler];
+    run_arguments.extend(arguments);
+
+    if !is_coroutine_function {
+        return match tokio::task::spawn_blocking(move || {
+            Python::with_gil(|py| context.call_method1(py, "run", PyTuple::new(py, run_arguments)))
+        }).await {
+            Ok(result) => result,
+            Err(err) => Err(PyRuntimeError::new_err(format!(
+                "{}",
+                err.to_string()
+            ))),
+        };
+    }
+
+    let receiver = Python::with_gil(|py| -> PyResult<_> {
+        let (sender, receiver) = oneshot::channel();
+        let coroutine = context.call_method1(py, "run", PyTuple::new(py, run_arguments))?;
+        let start_activity_task = StartActivityTask {
+            coroutine: Some(coroutine),
+            done_callback: Some(HandlerDoneCallback { sender: Some(sender) }),
+            cancellation,
+        };
+        let call_soon_threadsafe = event_loop.getattr(py, "call_soon_threadsafe")?;
+        context.call_method1(py, "run", (call_soon_threadsafe, start_activity_task))?;
+        Ok(receiver)
+    })?;
+
+    match receiver.await {
+        Ok(result) => result,
+        Err(_) => Err(PyRuntimeError::new_err("Handler got dropped without returning a result")),
+    }
+}
+
+
+#[derive(Clone)]
+struct WorkerRunner {
+    core: WrappedCore,
+    event_loop: PyObject,
+    task_queue: String,
+    workflows: Arc<HashMap<String, PyObject>>,
+    activities: Arc<HashMap<String, PyObject>>,
+    data_converter: WrappedDataConverter,
+    // Event loops of the runs that are in Core's cache, created on their StartWorkflow jobs
+    run_event_loops: Arc<Mutex<HashMap<String, Py<WrappedWorkflowEventLoop>>>>,
+    // Activities that are being run, keyed by task token
+    running_activities: Arc<Mutex<HashMap<Vec<u8>, Arc<ActivityCancellation>>>>,
+}
+
+impl WorkerRunner {
+    async fn run(self, config: WrappedWorkerConfig) -> PyResult<()> {
+        let max_outstanding_workflow_tasks = config.max_outstanding_workflow_tasks.max(1);
+        let max_outstanding_activities = config.max_outstanding_activities.max(1);
+        let workflow_slots = Arc::new(Semaphore::new(max_outstanding_workflow_tasks));
+        let activity_slots = Arc::new(Semaphore::new(max_outstanding_activities));
+
+        let max_concurrent_wft_polls = config.max_concurrent_wft_polls;
+        let max_concurrent_at_polls = config.max_concurrent_at_polls;
+        let no_remote_activities = config.no_remote_activities;
+        self.core.register(WorkerConfig::try_from(config)?).await?;
+
+        let mut pollers = Vec::new();
+        for _ in 0..max_concurrent_wft_polls {
+            pollers.push(tokio::spawn(self.clone().poll_wf_activations(workflow_slots.clone())));
+        }
+        if !no_remote_activities {
+            for _ in 0..max_concurrent_at_polls {
+                pollers.push(tokio::spawn(self.clone().poll_activity_tasks(activity_slots.clone())));
+            }
+        }
+
+        let mut result = Ok(());
+        for poller in pollers {
+            let poller_result = match poller.await {
+                Ok(poller_result) => poller_result,
+                Err(err) => Err(PyRuntimeError::new_err(format!(
+                    "{}",
+                    err.to_string()
+                ))),
+            };
+            if let Err(err) = poller_result {
+                if result.is_ok() {
+                    result = Err(err);
+                }
+            }
+        }
+
+        // Wait for the activations and activities that are still being processed
+        let _ = workflow_slots.acquire_many(max_outstanding_workflow_tasks as u32).await;
+        let _ = activity_slots.acquire_many(max_outstanding_activities as u32).await;
+
+        result
+    }
+
+    // Shutdown of Core or of this task queue is how the pollers are supposed to stop; any other error stops the rest
+    // of the pollers too
+    fn stop_polling(&self, err: PyErr) -> PyResult<()> {
+        if Python::with_gil(|py| err.is_instance::<ShutdownError>(py)) {
+            return Ok(());
+        }
+        self.core.shutdown.shut_down_task_queue(self.task_queue.as_str());
+        Err(err)
+    }
+
+    fn report_error(&self, message: &str, err: PyErr) {
+        Python::with_gil(|py| {
+            let context = PyDict::new(py);
+            let reported = context.set_item("message", message)
+                .and_then(|_| context.set_item("exception", err.pvalue(py)))
+                .and_then(|_| self.event_loop.call_method1(py, "call_exception_handler", (context,)));
+            if let Err(reporting_err) = reported {
+                reporting_err.print(py);
+            }
+        })
+    }
+
+    async fn poll_wf_activations(self, slots: Arc<Semaphore>) -> PyResult<()> {
+        loop {
+            let permit = slots.clone().acquire_owned().await.expect("Worker's semaphores never get closed");
+            let wf_activation = match self.core.poll_wf_activation(self.task_queue.as_str()).await {
+                Ok(wf_activation) => wf_activation,
+                Err(err) => return self.stop_polling(err),
+            };
+            let runner = self.clone();
+            tokio::spawn(async move {
+                let completion = runner.process_wf_activation(wf_activation).await;
+                if let Err(err) = runner.core.complete_wf_activation(completion).await {
+                    runner.report_error("Unable to complete workflow activation", err);
+                }
+                drop(permit);
+            });
+        }
+    }
+
+    async fn process_wf_activation(&self, wf_activation: WfActivation) -> WfActivationCompletion {
+        let run_id = wf_activation.run_id.clone();
+        let evicted = wf_activation.jobs.iter().any(|job| {
+            matches!(job.variant, Some(wf_activation_job::Variant::RemoveFromCache(true)))
+        });
+
+        let status = match self.activate_workflow(wf_activation).await {
+            Ok(commands) => wf_activation_completion::Status::Successful(WfSuccess { commands }),
+            Err(err) => wf_activation_completion::Status::Failed(WfFailure {
+                failure: Some(py_err_to_user_code_failure(&err)),
+            }),
+        };
+
+        if evicted {
+            self.run_event_loops.lock().unwrap().remove(&run_id);
+        }
+
+        WfActivationCompletion {
+            run_id,
+            status: Some(status),
+        }
+    }
+
+    // Along with the event loop that got replaced, for when Core starts over with a run that is still cached here, e.g.
+    // after TLS reloading has moved the run to a new Core which replays it from the start
+    fn workflow_event_loop(&self,
+                           wf_activation: &WfActivation,
+    ) -> PyResult<(Py<WrappedWorkflowEventLoop>, Option<Py<WrappedWorkflowEventLoop>>)> {
+        for job in &wf_activation.jobs {
+            if let Some(wf_activation_job::Variant::StartWorkflow(start_workflow)) = &job.variant {
+                let workflow = match self.workflows.get(&start_workflow.workflow_type) {
+                    None => return Err(PyRuntimeError::new_err(format!(
+                        "Workflow type {} is not registered with this worker",
+                        start_workflow.workflow_type
+                    ))),
+                    Some(workflow) => workflow.clone(),
+                };
+                let event_loop = Python::with_gil(|py| {
+                    let event_loop = WrappedWorkflowEventLoop::new(
+                        py,
+                        workflow,
+                        self.task_queue.clone(),
+                        Some(self.data_converter.clone()),
+                    )?;
+                    Py::new(py, event_loop)
+                })?;
+                let replaced = self.run_event_loops.lock().unwrap().insert(wf_activation.run_id.clone(), event_loop.clone());
+                return Ok((event_loop, replaced));
+            }
+        }
+
+        match self.run_event_loops.lock().unwrap().get(&wf_activation.run_id) {
+            None => Err(PyRuntimeError::new_err(format!(
+                "Run {} is not cached by this worker",
+                wf_activation.run_id
+            ))),
+            Some(event_loop) => Ok((event_loop.clone(), None)),
+        }
+    }
+
+    async fn activate_workflow(&self, wf_activation: WfActivation) -> PyResult<Vec<WorkflowCommand>> {
+        let (event_loop, replaced) = self.workflow_event_loop(&wf_activation)?;
+
+        // Workflow code never blocks on anything but the event loop, so it's only the CPU time that has to be kept away
+        // from the poll loops
+        let runner = self.clone();
+        let wrapped_commands = match tokio::task::spawn_blocking(move || {
+            Python::with_gil(|py| {
+                // Old loop's tasks and timers would otherwise stay around for good
+                if let Some(replaced) = replaced {
+                    if let Err(err) = WrappedWorkflowEventLoop::close(replaced.as_ref(py), py) {
+                        runner.report_error("Unable to close the replaced workflow event loop", err);
+                    }
+                }
+                WrappedWorkflowEventLoop::activate(event_loop.as_ref(py), py, WrappedWfActivation::try_from(wf_activation)?)
+            })
+        }).await {
+            Ok(wrapped_commands) => wrapped_commands?,
+            Err(err) => return Err(PyRuntimeError::new_err(format!(
+                "{}",
+                err.to_string()
+            ))),
+        };
+
+        let mut commands = Vec::new();
+        for wrapped_command in wrapped_commands {
+            commands.push(WorkflowCommand::try_from(wrapped_command)?);
+        }
+        Ok(commands)
+    }
+
+    // Cancellations come from the same polls as the activities to run, so polling doesn't wait for a slot: with every
+    // slot taken by an activity that's waiting to get cancelled, the cancellations would otherwise never arrive
+    async fn poll_activity_tasks(self, slots: Arc<Semaphore>) -> PyResult<()> {
+        loop {
+            let activity_task = match self.core.poll_activity(self.task_queue.as_str()).await {
+                Ok(activity_task) => activity_task,
+                Err(err) => return self.stop_polling(err),
+            };
+            if !matches!(activity_task.variant, Some(activity_task::Variant::Start(_))) {
+                self.process_activity_task(activity_task).await;
+                continue;
+            }
+            let permit = slots.clone().acquire_owned().await.expect("Worker's semaphores never get closed");
+            let runner = self.clone();
+            tokio::spawn(async move {
+                if let Some(completion) = runner.process_activity_task(activity_task).await {
+                    if let Err(err) = runner.core.complete_activity(completion).await {
+                        runner.report_error("Unable to complete activity task", err);
+                    }
+                }
+                drop(permit);
+            });
+        }
+    }
+
+    fn cancel_activity(&self, task_token: &[u8], reason: i32) -> PyResult<()> {
+        // Activities that have finished in the meantime have nothing left to cancel
+        let cancellation = self.running_activities.lock().unwrap().get(task_token).cloned();
+        match cancellation {
+            None => Ok(()),
+            Some(cancellation) => Python::with_gil(|py| cancellation.cancel(py, reason)),
+        }
+    }
+
+    async fn process_activity_task(&self, activity_task: ActivityTask) -> Option<ActivityTaskCompletion> {
+        let task_token = activity_task.task_token;
+        let start = match activity_task.variant {
+            Some(activity_task::Variant::Start(start)) => start,
+            Some(activity_task::Variant::Cancel(cancel)) => {
+                if let Err(err) = self.cancel_activity(&task_token, cancel.reason) {
+                    self.report_error("Unable to cancel activity", err);
+                }
+                return None;
+            }
+            None => return None,
+        };
+
+        let cancellation = Arc::new(ActivityCancellation::default());
+        self.running_activities.lock().unwrap().insert(task_token.clone(), cancellation.clone());
+        let result = self.run_activity(task_token.clone(), activity_task.activity_id, start, cancellation.clone()).await;
+        self.running_activities.lock().unwrap().remove(&task_token);
+
+        let status = match result {
+            Ok(result) => activity_result::Status::Completed(ActivitySuccess { result }),
+            // Activity honored the cancellation by letting asyncio.CancelledError through
+            Err(err) if cancellation.is_cancelled() && Python::with_gil(|py| is_cancelled_error(py, &err)) => {
+                activity_result::Status::Canceled(ActivityCancelation {
+                    details: None,
+                })
+            }
+            Err(err) => activity_result::Status::Failed(ActivityFailure {
+                failure: Some(py_err_to_user_code_failure(&err)),
+            }),
+        };
+
+        Some(ActivityTaskCompletion {
+            task_token,
+            task_queue: self.task_queue.clone(),
+            result: Some(ActivityResult {
+                status: Some(status),
+            }),
+        })
+    }
+
+    async fn run_activity(&self,
+                          task_token: Vec<u8>,
+                          activity_id: String,
+                          start: Start,
+                          cancellation: Arc<ActivityCancellation>) -> PyResult<Option<Payload>> {
+        let handler = match self.activities.get(&start.activity_type) {
+            None => return Err(PyRuntimeError::new_err(format!(
+                "Activity type {} is not registered with this worker",
+                start.activity_type
+            ))),
+            Some(handler) => handler.clone(),
+        };
+
+        let (context, arguments, span) = Python::with_gil(|py| -> PyResult<_> {
+            let start = WrappedStart::try_from(start)?;
+            let arguments = self.data_converter.decode_all(py, start.input.clone())?;
+            let trace_parent = extract_context(py, &self.data_converter, &start.header_fields);
+            let span = TraceSpan::start(py, &format!("RunActivity:{}", start.activity_type), trace_parent.as_ref(), vec![
+                ("temporal.activity_type", start.activity_type.clone()),
+                ("temporal.activity_id", activity_id.clone()),
+                ("temporal.task_queue", self.task_queue.clone()),
+            ]);
+            let info = WrappedActivityInfo::new(task_token, self.task_queue.clone(), activity_id, start, cancellation.clone());
+            let context = activity_context(py, info)?;
+            // Handler inherits the context, so whatever it traces ends up in the activity's span
+            if let Some(span) = &span {
+                span.attach(py, &context);
+            }
+            Ok((context, arguments, span))
+        })?;
+        let result = call_handler(self.event_loop.clone(), context, cancellation, handler, arguments).await;
+        if let Some(span) = span {
+            Python::with_gil(|py| span.end(py, result.as_ref().err()));
+        }
+        let result = result?;
+
+        Python::with_gil(|py| Ok(Some(Payload::from(self.data_converter.encode(py, result.as_ref(py))?))))
+    }
+}
+
+
+#[pyclass(name = "Worker")]
+pub struct WrappedWorker {
+    core: WrappedCore,
+    config: WrappedWorkerConfig,
+    workflows: Arc<HashMap<String, PyObject>>,
+    activities: Arc<HashMap<String, PyObject>>,
+    #[pyo3(get)]
+    data_converter: WrappedDataConverter,
+}
+
+#[pymethods]
+impl WrappedWorker {
+    // Activities registered with @activity.defn get used unless they're passed explicitly
+    #[new]
+    #[args(workflows = "HashMap::new()", activities = "None", "*", data_converter = "None")]
+    fn new(py: Python,
+           core: WrappedCore,
+           config: WrappedWorkerConfig,
+           workflows: HashMap<String, PyObject>,
+           activities: Option<HashMap<String, PyObject>>,
+           data_converter: Option<WrappedDataConverter>) -> PyResult<Self> {
+        let activities = match activities {
+            Some(activities) => activities,
+            None => registered_activities(py)?,
+        };
+        let data_converter = match data_converter {
+            Some(data_converter) => data_converter,
+            None => WrappedDataConverter::new_default(py)?,
+        };
+        Ok(WrappedWorker {
+            core,
+            config,
+            workflows: Arc::new(workflows),
+            activities: Arc::new(activities),
+            data_converter,
+        })
+    }
+
+    fn run<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let current_loop = pyo3_asyncio::get_running_loop(py)?;
+        let runner = WorkerRunner {
+            core: self.core.clone(),
+            event_loop: current_loop.into(),
+            task_queue: self.config.task_queue.clone(),
+            workflows: self.workflows.clone(),
+            activities: self.activities.clone(),
+            data_converter: self.data_converter.clone(),
+            run_event_loops: Arc::new(Mutex::new(HashMap::new())),
+            running_activities: Arc::new(Mutex::new(HashMap::new())),
+        };
+        let config = self.config.clone();
+        pyo3_asyncio::tokio::future_into_py_with_loop(current_loop, async move {
+            runner.run(config).await?;
+            Python::with_gil(|py| Ok(py.None()))
+        })
+    }
+}
@@ -0,0 +1,810 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration as StdDuration;
+
+use pyo3::prelude::*;
+use pyo3::exceptions::{
+    PyKeyError,
+    PyRuntimeError,
+    PyValueError,
+};
+use pyo3::types::{PyDict, PyTuple};
+
+use crate::converter::{
+    data_converter::WrappedDataConverter,
+    failure::{
+        py_err_to_user_code_failure,
+        user_code_failure_to_exception,
+    },
+};
+use crate::errors::{
+    ActivityError,
+    new_err_with_attributes,
+};
+use crate::protos::coresdk::{
+    activity_result::WrappedStatus as WrappedActivityStatus,
+    common::{
+        WrappedPayload,
+        WrappedRetryPolicy,
+        WrappedUserCodeFailure,
+    },
+    workflow_activation::{
+        WrappedResolveActivity,
+        WrappedQueryWorkflow,
+        WrappedSignalWorkflow,
+        WrappedStartWorkflow,
+        WrappedVariant as WrappedJobVariant,
+        WrappedWfActivation,
+    },
+    workflow_commands::{
+        WrappedCancelTimer,
+        WrappedCancelWorkflowExecution,
+        WrappedCompleteWorkflowExecution,
+        WrappedContinueAsNewWorkflowExecution,
+        WrappedFailWorkflowExecution,
+        WrappedQueryFailed,
+        WrappedQueryResult,
+        WrappedQueryResultVariant,
+        WrappedQuerySuccess,
+        WrappedRequestCancelActivity,
+        WrappedScheduleActivity,
+        WrappedStartTimer,
+        WrappedVariant as WrappedCommandVariant,
+        WrappedWorkflowCommand,
+    },
+};
+use crate::trace_context::{
+    TraceSpan,
+    extract_context,
+    inject_context,
+};
+use crate::utils::std_duration_to_pyo3_chrono_duration;
+
+
+// Everything that callbacks scheduled on the loop might touch while the loop is running, hence the mutex instead of
+// PyCell's borrow tracking; the lock never gets held while Python code is running as that code might call back into
+// the loop
+#[derive(Default)]
+struct EventLoopState {
+    time: f64,
+    running: bool,
+    closed: bool,
+    ready: VecDeque<Py<WrappedHandle>>,
+    // Handles scheduled with call_later() / call_at() that are waiting for their FireTimer job, keyed by timer ID
+    timers: HashMap<String, Py<WrappedHandle>>,
+    // Futures returned by execute_activity() that are waiting for their ResolveActivity job, keyed by activity ID
+    activities: HashMap<String, PyObject>,
+    signal_handlers: HashMap<String, PyObject>,
+    // Signals that arrived before workflow code got to register a handler for them
+    buffered_signals: HashMap<String, Vec<Vec<WrappedPayload>>>,
+    query_handlers: HashMap<String, PyObject>,
+    random: Option<PyObject>,
+    main_task: Option<PyObject>,
+    // Set once a command that ends the run has been pushed; workflow code that keeps on running after that, e.g. until
+    // its next await after continue_as_new() or by catching asyncio.CancelledError, can only respond to queries
+    finished: bool,
+    commands: Vec<WrappedWorkflowCommand>,
+    last_timer_seq: u64,
+    last_activity_seq: u64,
+    workflow_type: Option<String>,
+    // Trace context that came with the StartWorkflow job, which every activation's span is a child of
+    trace_parent: Option<PyObject>,
+    // Trace context that gets propagated to the activities scheduled by the activation that is running
+    trace_context: Option<PyObject>,
+}
+
+impl EventLoopState {
+    fn push_command(&mut self, variant: WrappedCommandVariant) {
+        if self.finished && !matches!(variant, WrappedCommandVariant::RespondToQuery(_)) {
+            return;
+        }
+        if let WrappedCommandVariant::CompleteWorkflowExecution(_)
+            | WrappedCommandVariant::FailWorkflowExecution(_)
+            | WrappedCommandVariant::CancelWorkflowExecution(_)
+            | WrappedCommandVariant::ContinueAsNewWorkflowExecution(_) = variant {
+            self.finished = true;
+        }
+        self.commands.push(WrappedWorkflowCommand {
+            variant: Some(variant),
+        });
+    }
+}
+
+
+fn create_future(py: Python, loop_object: &PyObject) -> PyResult<PyObject> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("loop", loop_object)?;
+    Ok(py.import("asyncio")?.getattr("Future")?.call((), Some(kwargs))?.into())
+}
+
+
+fn create_task(py: Python, loop_object: &PyObject, coro: PyObject, name: Option<String>) -> PyResult<PyObject> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("loop", loop_object)?;
+    if let Some(name) = name {
+        kwargs.set_item("name", name)?;
+    }
+    Ok(py.import("asyncio")?.getattr("Task")?.call((coro,), Some(kwargs))?.into())
+}
+
+
+#[pyclass(name = "Handle")]
+pub struct WrappedHandle {
+    callback: PyObject,
+    args: Py<PyTuple>,
+    context: Option<PyObject>,
+    cancelled: bool,
+    timer_id: Option<String>,
+    when: f64,
+    state: Arc<Mutex<EventLoopState>>,
+}
+
+#[pymethods]
+impl WrappedHandle {
+    fn cancel(&mut self) {
+        if self.cancelled {
+            return;
+        }
+        self.cancelled = true;
+
+        if let Some(timer_id) = self.timer_id.take() {
+            let timer = self.state.lock().unwrap().timers.remove(&timer_id);
+            if timer.is_some() {
+                self.state.lock().unwrap().push_command(WrappedCommandVariant::CancelTimer(WrappedCancelTimer {
+                    timer_id,
+                }));
+            }
+        }
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    fn when(&self) -> f64 {
+        self.when
+    }
+}
+
+
+#[pyclass]
+struct WorkflowDoneCallback {
+    state: Arc<Mutex<EventLoopState>>,
+    data_converter: WrappedDataConverter,
+}
+
+#[pymethods]
+impl WorkflowDoneCallback {
+    #[call]
+    fn __call__(&self, py: Python, task: &PyAny) -> PyResult<()> {
+        let variant = if task.call_method0("cancelled")?.is_true()? {
+            WrappedCommandVariant::CancelWorkflowExecution(WrappedCancelWorkflowExecution {})
+        } else {
+            match task.call_method0("result").and_then(|result| self.data_converter.encode(py, result)) {
+                Ok(result) => WrappedCommandVariant::CompleteWorkflowExecution(WrappedCompleteWorkflowExecution {
+                    result: Some(result),
+                }),
+                Err(err) => WrappedCommandVariant::FailWorkflowExecution(WrappedFailWorkflowExecution {
+                    failure: Some(WrappedUserCodeFailure::from(py_err_to_user_code_failure(&err))),
+                }),
+            }
+        };
+        self.state.lock().unwrap().push_command(variant);
+        Ok(())
+    }
+}
+
+
+#[pyclass]
+struct ActivityDoneCallback {
+    state: Arc<Mutex<EventLoopState>>,
+    activity_id: String,
+}
+
+#[pymethods]
+impl ActivityDoneCallback {
+    // Activities that got resolved are gone from the state by the time their future is done, so whatever is still
+    // there got cancelled by the workflow code
+    #[call]
+    fn __call__(&self, future: &PyAny) -> PyResult<()> {
+        if !future.call_method0("cancelled")?.is_true()? {
+            return Ok(());
+        }
+        let activity = self.state.lock().unwrap().activities.remove(&self.activity_id);
+        if activity.is_some() {
+            self.state.lock().unwrap().push_command(WrappedCommandVariant::RequestCancelActivity(WrappedRequestCancelActivity {
+                activity_id: self.activity_id.clone(),
+            }));
+        }
+        Ok(())
+    }
+}
+
+
+// asyncio event loop that workflow code runs on: time only moves with activation timestamps, timers become StartTimer
+// commands and get fired by FireTimer jobs, and there's no I/O, so every activation runs the same way on replay
+#[pyclass(name = "WorkflowEventLoop")]
+pub struct WrappedWorkflowEventLoop {
+    workflow: PyObject,
+    task_queue: String,
+    data_converter: WrappedDataConverter,
+    state: Arc<Mutex<EventLoopState>>,
+}
+
+impl WrappedWorkflowEventLoop {
+    fn schedule(&self,
+                py: Python,
+                callback: PyObject,
+                args: &PyTuple,
+                context: Option<PyObject>,
+                timer_id: Option<String>,
+                when: f64) -> PyResult<Py<WrappedHandle>> {
+        Py::new(py, WrappedHandle {
+            callback,
+            args: args.into(),
+            context,
+            cancelled: false,
+            timer_id,
+            when,
+            state: self.state.clone(),
+        })
+    }
+
+    fn set_random(&self, py: Python, randomness_seed: u64) -> PyResult<()> {
+        let random: PyObject = py.import("random")?.getattr("Random")?.call1((randomness_seed,))?.into();
+        let previous_random = self.state.lock().unwrap().random.replace(random);
+        drop(previous_random);
+        Ok(())
+    }
+
+    fn deliver_signal(&self, py: Python, loop_object: &PyObject, handler: &PyObject, input: Vec<WrappedPayload>) -> PyResult<()> {
+        let result = handler.call1(py, self.data_converter.decode_to_tuple(py, input)?)?;
+        if py.import("asyncio")?.call_method1("iscoroutine", (result.clone_ref(py),))?.is_true()? {
+            create_task(py, loop_object, result, None)?;
+        }
+        Ok(())
+    }
+
+    fn start_workflow(&self, py: Python, loop_object: &PyObject, start_workflow: WrappedStartWorkflow) -> PyResult<()> {
+        self.set_random(py, start_workflow.randomness_seed)?;
+
+        let coro = self.workflow.call1(py, self.data_converter.decode_to_tuple(py, start_workflow.arguments)?)?;
+        let task = create_task(py, loop_object, coro, Some(start_workflow.workflow_id))?;
+        task.call_method1(py, "add_done_callback", (WorkflowDoneCallback {
+            state: self.state.clone(),
+            data_converter: self.data_converter.clone(),
+        },))?;
+        self.state.lock().unwrap().main_task = Some(task);
+        Ok(())
+    }
+
+    fn fire_timer(&self, py: Python, timer_id: String) -> PyResult<()> {
+        // Timers that got cancelled in the meantime are already gone
+        let timer = self.state.lock().unwrap().timers.remove(&timer_id);
+        if let Some(timer) = timer {
+            timer.borrow_mut(py).timer_id = None;
+            self.state.lock().unwrap().ready.push_back(timer);
+        }
+        Ok(())
+    }
+
+    fn respond_to_query(&self, py: Python, query_workflow: WrappedQueryWorkflow) -> PyResult<()> {
+        let handler = self.state.lock().unwrap().query_handlers.get(&query_workflow.query_type).map(|handler| handler.clone_ref(py));
+        let response = match handler {
+            None => Err(PyKeyError::new_err(format!(
+                "No handler for query type {}",
+                query_workflow.query_type
+            ))),
+            Some(handler) => self.data_converter.decode_to_tuple(py, query_workflow.arguments)
+                .and_then(|arguments| handler.call1(py, arguments))
+                .and_then(|response| self.data_converter.encode(py, response.as_ref(py))),
+        };
+        let variant = match response {
+            Ok(response) => WrappedQueryResultVariant::Succeeded(WrappedQuerySuccess {
+                response: Some(response),
+            }),
+            Err(err) => WrappedQueryResultVariant::Failed(WrappedQueryFailed {
+                failed: WrappedUserCodeFailure::from(py_err_to_user_code_failure(&err)),
+            }),
+        };
+        self.state.lock().unwrap().push_command(WrappedCommandVariant::RespondToQuery(WrappedQueryResult {
+            query_id: query_workflow.query_id,
+            variant: Some(variant),
+        }));
+        Ok(())
+    }
+
+    fn cancel_workflow(&self, py: Python) -> PyResult<()> {
+        let main_task = self.state.lock().unwrap().main_task.as_ref().map(|task| task.clone_ref(py));
+        if let Some(main_task) = main_task {
+            main_task.call_method0(py, "cancel")?;
+        }
+        Ok(())
+    }
+
+    fn signal_workflow(&self, py: Python, loop_object: &PyObject, signal_workflow: WrappedSignalWorkflow) -> PyResult<()> {
+        let handler = self.state.lock().unwrap().signal_handlers.get(&signal_workflow.signal_name).map(|handler| handler.clone_ref(py));
+        match handler {
+            Some(handler) => self.deliver_signal(py, loop_object, &handler, signal_workflow.input),
+            None => {
+                self.state.lock().unwrap().buffered_signals
+                    .entry(signal_workflow.signal_name)
+                    .or_insert_with(Vec::new)
+                    .push(signal_workflow.input);
+                Ok(())
+            }
+        }
+    }
+
+    fn resolve_activity(&self, py: Python, resolve_activity: WrappedResolveActivity) -> PyResult<()> {
+        // Activities that got cancelled by the workflow code are already gone
+        let future = match self.state.lock().unwrap().activities.remove(&resolve_activity.activity_id) {
+            None => return Ok(()),
+            Some(future) => future,
+        };
+        match resolve_activity.result.and_then(|result| result.status) {
+            Some(WrappedActivityStatus::Completed(success)) => {
+                let result = match success.result {
+                    None => py.None(),
+                    Some(result) => self.data_converter.decode(py, result)?,
+                };
+                future.call_method1(py, "set_result", (result,))?;
+            }
+            // Activity's own exception chain, as far as it can be rebuilt from the failure, becomes the cause
+            Some(WrappedActivityStatus::Failed(failure)) => {
+                let err = new_err_with_attributes::<ActivityError>(py, format!(
+                    "Activity {} failed",
+                    resolve_activity.activity_id
+                ), vec![
+                    ("activity_id", resolve_activity.activity_id.as_str().into_py(py)),
+                ]);
+                if let Some(failure) = failure.failure {
+                    err.instance(py).setattr("__cause__", user_code_failure_to_exception(py, failure)?)?;
+                }
+                future.call_method1(py, "set_exception", (err.instance(py),))?;
+            }
+            Some(WrappedActivityStatus::Canceled(_)) => {
+                future.call_method0(py, "cancel")?;
+            }
+            None => {
+                let err = new_err_with_attributes::<ActivityError>(py, format!(
+                    "Activity {} got resolved without a result",
+                    resolve_activity.activity_id
+                ), vec![
+                    ("activity_id", resolve_activity.activity_id.as_str().into_py(py)),
+                ]);
+                future.call_method1(py, "set_exception", (err.instance(py),))?;
+            }
+        }
+        Ok(())
+    }
+
+    fn process_job(&self, py: Python, loop_object: &PyObject, variant: WrappedJobVariant) -> PyResult<()> {
+        match variant {
+            WrappedJobVariant::StartWorkflow(start_workflow) => self.start_workflow(py, loop_object, start_workflow),
+            WrappedJobVariant::FireTimer(fire_timer) => self.fire_timer(py, fire_timer.timer_id),
+            WrappedJobVariant::UpdateRandomSeed(update_random_seed) => self.set_random(py, update_random_seed.randomness_seed),
+            WrappedJobVariant::QueryWorkflow(query_workflow) => self.respond_to_query(py, query_workflow),
+            WrappedJobVariant::CancelWorkflow(_) => self.cancel_workflow(py),
+            WrappedJobVariant::SignalWorkflow(signal_workflow) => self.signal_workflow(py, loop_object, signal_workflow),
+            WrappedJobVariant::ResolveActivity(resolve_activity) => self.resolve_activity(py, resolve_activity),
+            WrappedJobVariant::RemoveFromCache(_) => self.close_loop(py, loop_object),
+        }
+    }
+
+    // Workflow's trace gets picked up from the StartWorkflow job's headers; activations that are being replayed have
+    // been traced already the first time around
+    fn start_activation_span(&self, py: Python, wf_activation: &WrappedWfActivation) -> Option<TraceSpan> {
+        for job in &wf_activation.jobs {
+            if let Some(WrappedJobVariant::StartWorkflow(start_workflow)) = &job.variant {
+                let trace_parent = extract_context(py, &self.data_converter, &start_workflow.headers);
+                let previous_trace_parent = {
+                    let mut state = self.state.lock().unwrap();
+                    state.workflow_type = Some(start_workflow.workflow_type.clone());
+                    std::mem::replace(&mut state.trace_parent, trace_parent)
+                };
+                drop(previous_trace_parent);
+            }
+        }
+        if wf_activation.is_replaying {
+            return None;
+        }
+
+        let (workflow_type, trace_parent) = {
+            let state = self.state.lock().unwrap();
+            (
+                state.workflow_type.clone().unwrap_or_default(),
+                state.trace_parent.as_ref().map(|trace_parent| trace_parent.clone_ref(py)),
+            )
+        };
+        TraceSpan::start(py, &format!("RunWorkflow:{}", workflow_type), trace_parent.as_ref(), vec![
+            ("temporal.workflow_type", workflow_type.clone()),
+            ("temporal.run_id", wf_activation.run_id.clone()),
+            ("temporal.task_queue", self.task_queue.clone()),
+        ])
+    }
+
+    // Cancels whatever the workflow code still has pending and lets it run its "finally" blocks before it all gets
+    // dropped, asyncio complaining about tasks that get destroyed while pending otherwise
+    fn close_loop(&self, py: Python, loop_object: &PyObject) -> PyResult<()> {
+        let (timers, activities, running) = {
+            let mut state = self.state.lock().unwrap();
+            if state.closed {
+                return Ok(());
+            }
+            (std::mem::take(&mut state.timers), std::mem::take(&mut state.activities), state.running)
+        };
+        // They're out of the state already, so cancelling them doesn't issue CancelTimer / RequestCancelActivity
+        for timer in timers.values() {
+            timer.borrow_mut(py).cancelled = true;
+        }
+        for future in activities.values() {
+            future.call_method0(py, "cancel")?;
+        }
+
+        let asyncio = py.import("asyncio")?;
+        for task in asyncio.call_method1("all_tasks", (loop_object.clone_ref(py),))?.iter()? {
+            task?.call_method0("cancel")?;
+        }
+
+        let events = py.import("asyncio.events")?;
+        let previous_loop = events.call_method0("_get_running_loop")?;
+        if !running {
+            events.call_method1("_set_running_loop", (loop_object.clone_ref(py),))?;
+            self.state.lock().unwrap().running = true;
+        }
+        let result = self.run_until_blocked(py);
+        if !running {
+            self.state.lock().unwrap().running = false;
+            events.call_method1("_set_running_loop", (previous_loop,))?;
+        }
+
+        let leftovers = {
+            let mut state = self.state.lock().unwrap();
+            state.closed = true;
+            (
+                std::mem::take(&mut state.ready),
+                std::mem::take(&mut state.timers),
+                std::mem::take(&mut state.activities),
+                std::mem::take(&mut state.commands),
+                state.main_task.take(),
+            )
+        };
+        drop((timers, activities, leftovers));
+        result
+    }
+
+    fn run_until_blocked(&self, py: Python) -> PyResult<()> {
+        loop {
+            let handle = self.state.lock().unwrap().ready.pop_front();
+            let handle = match handle {
+                None => return Ok(()),
+                Some(handle) => handle,
+            };
+
+            let (callback, args, context) = {
+                let handle = handle.borrow(py);
+                if handle.cancelled {
+                    continue;
+                }
+                (
+                    handle.callback.clone_ref(py),
+                    handle.args.clone_ref(py),
+                    handle.context.as_ref().map(|context| context.clone_ref(py)),
+                )
+            };
+
+            match context {
+                None => {
+                    callback.call1(py, args.as_ref(py))?;
+                }
+                Some(context) => {
+                    let mut run_args = vec![callback];
+                    run_args.extend(args.as_ref(py).iter().map(|arg| PyObject::from(arg)));
+                    context.call_method1(py, "run", PyTuple::new(py, run_args))?;
+                }
+            }
+        }
+    }
+}
+
+#[pymethods]
+impl WrappedWorkflowEventLoop {
+    #[new]
+    #[args(task_queue = "String::new()", data_converter = "None")]
+    pub(crate) fn new(py: Python,
+                      workflow: PyObject,
+                      task_queue: String,
+                      data_converter: Option<WrappedDataConverter>) -> PyResult<Self> {
+        Ok(WrappedWorkflowEventLoop {
+            workflow,
+            task_queue,
+            data_converter: match data_converter {
+                None => WrappedDataConverter::new_default(py)?,
+                Some(data_converter) => data_converter,
+            },
+            state: Arc::new(Mutex::new(EventLoopState::default())),
+        })
+    }
+
+    // Applies activation's jobs and runs the workflow code until all of it is blocked, returning the commands that it
+    // has issued in the meantime
+    pub(crate) fn activate(slf: &PyCell<Self>, py: Python, wf_activation: WrappedWfActivation) -> PyResult<Vec<WrappedWorkflowCommand>> {
+        let this = slf.borrow();
+        let loop_object = slf.to_object(py);
+
+        if let Some(timestamp) = wf_activation.timestamp {
+            this.state.lock().unwrap().time = timestamp.0.timestamp() as f64 + timestamp.0.timestamp_subsec_nanos() as f64 / 1e9;
+        }
+
+        let span = this.start_activation_span(py, &wf_activation);
+        let previous_trace_context = {
+            let mut state = this.state.lock().unwrap();
+            let trace_context = match &span {
+                Some(span) => Some(span.context(py)),
+                None => state.trace_parent.as_ref().map(|trace_parent| trace_parent.clone_ref(py)),
+            };
+            std::mem::replace(&mut state.trace_context, trace_context)
+        };
+        drop(previous_trace_context);
+
+        let events = py.import("asyncio.events")?;
+        let previous_loop = events.call_method0("_get_running_loop")?;
+        events.call_method1("_set_running_loop", (loop_object.clone_ref(py),))?;
+        this.state.lock().unwrap().running = true;
+
+        let mut result = Ok(());
+        for job in wf_activation.jobs {
+            if let Some(variant) = job.variant {
+                result = this.process_job(py, &loop_object, variant);
+                if result.is_err() {
+                    break;
+                }
+            }
+        }
+        if result.is_ok() {
+            result = this.run_until_blocked(py);
+        }
+
+        this.state.lock().unwrap().running = false;
+        events.call_method1("_set_running_loop", (previous_loop,))?;
+
+        if let Some(span) = span {
+            span.end(py, result.as_ref().err());
+        }
+
+        result?;
+        Ok(std::mem::take(&mut this.state.lock().unwrap().commands))
+    }
+
+    fn time(&self) -> f64 {
+        self.state.lock().unwrap().time
+    }
+
+    fn get_debug(&self) -> bool {
+        false
+    }
+
+    fn is_running(&self) -> bool {
+        self.state.lock().unwrap().running
+    }
+
+    fn is_closed(&self) -> bool {
+        self.state.lock().unwrap().closed
+    }
+
+    pub(crate) fn close(slf: &PyCell<Self>, py: Python) -> PyResult<()> {
+        slf.borrow().close_loop(py, &slf.to_object(py))
+    }
+
+    #[args(args = "*", context = "None")]
+    fn call_soon(&self, py: Python, callback: PyObject, args: &PyTuple, context: Option<PyObject>) -> PyResult<Py<WrappedHandle>> {
+        let when = self.time();
+        let handle = self.schedule(py, callback, args, context, None, when)?;
+        self.state.lock().unwrap().ready.push_back(handle.clone_ref(py));
+        Ok(handle)
+    }
+
+    #[args(args = "*", context = "None")]
+    fn call_later(&self, py: Python, delay: f64, callback: PyObject, args: &PyTuple, context: Option<PyObject>) -> PyResult<Py<WrappedHandle>> {
+        // Timer's duration has to fit in a chrono Duration, which also keeps from_secs_f64() from panicking
+        if !delay.is_finite() || delay > chrono::Duration::max_value().num_seconds() as f64 {
+            return Err(PyValueError::new_err(format!(
+                "Delay of {} seconds doesn't fit in a workflow timer",
+                delay
+            )));
+        }
+        if delay <= 0.0 {
+            return self.call_soon(py, callback, args, context);
+        }
+
+        let start_to_fire_timeout = std_duration_to_pyo3_chrono_duration(StdDuration::from_secs_f64(delay))?;
+        let timer_id = {
+            let mut state = self.state.lock().unwrap();
+            state.last_timer_seq += 1;
+            state.last_timer_seq.to_string()
+        };
+        let when = self.time() + delay;
+        let handle = self.schedule(py, callback, args, context, Some(timer_id.clone()), when)?;
+
+        let mut state = self.state.lock().unwrap();
+        state.timers.insert(timer_id.clone(), handle.clone_ref(py));
+        state.push_command(WrappedCommandVariant::StartTimer(WrappedStartTimer {
+            timer_id,
+            start_to_fire_timeout: Some(start_to_fire_timeout),
+        }));
+        Ok(handle)
+    }
+
+    #[args(args = "*", context = "None")]
+    fn call_at(&self, py: Python, when: f64, callback: PyObject, args: &PyTuple, context: Option<PyObject>) -> PyResult<Py<WrappedHandle>> {
+        let delay = when - self.time();
+        self.call_later(py, delay, callback, args, context)
+    }
+
+    fn create_future(slf: &PyCell<Self>, py: Python) -> PyResult<PyObject> {
+        create_future(py, &slf.to_object(py))
+    }
+
+    #[args(name = "None")]
+    fn create_task(slf: &PyCell<Self>, py: Python, coro: PyObject, name: Option<String>) -> PyResult<PyObject> {
+        create_task(py, &slf.to_object(py), coro, name)
+    }
+
+    fn call_exception_handler(&self, py: Python, context: &PyDict) -> PyResult<()> {
+        let message = match context.get_item("message") {
+            None => String::from("Unhandled exception in workflow event loop"),
+            Some(message) => message.to_string(),
+        };
+        let kwargs = PyDict::new(py);
+        if let Some(exception) = context.get_item("exception") {
+            kwargs.set_item("exc_info", exception)?;
+        }
+        py.import("asyncio.log")?.getattr("logger")?.call_method("error", (message,), Some(kwargs))?;
+        Ok(())
+    }
+
+    #[getter]
+    fn get_random(&self, py: Python) -> Option<PyObject> {
+        self.state.lock().unwrap().random.as_ref().map(|random| random.clone_ref(py))
+    }
+
+    fn set_signal_handler(slf: &PyCell<Self>, py: Python, signal_name: String, handler: PyObject) -> PyResult<()> {
+        let this = slf.borrow();
+        let buffered_signals = {
+            let mut state = this.state.lock().unwrap();
+            state.signal_handlers.insert(signal_name.clone(), handler.clone_ref(py));
+            state.buffered_signals.remove(&signal_name).unwrap_or_default()
+        };
+        let loop_object = slf.to_object(py);
+        for input in buffered_signals {
+            this.deliver_signal(py, &loop_object, &handler, input)?;
+        }
+        Ok(())
+    }
+
+    fn set_query_handler(&self, query_type: String, handler: PyObject) {
+        self.state.lock().unwrap().query_handlers.insert(query_type, handler);
+    }
+
+    #[args(
+        arguments = "Vec::new()",
+        "*",
+        task_queue = "None",
+        schedule_to_close_timeout = "None",
+        schedule_to_start_timeout = "None",
+        start_to_close_timeout = "None",
+        heartbeat_timeout = "None",
+        retry_policy = "None"
+    )]
+    fn execute_activity(slf: &PyCell<Self>,
+                        py: Python,
+                        activity_type: String,
+                        arguments: Vec<PyObject>,
+                        task_queue: Option<String>,
+                        schedule_to_close_timeout: Option<pyo3_chrono::Duration>,
+                        schedule_to_start_timeout: Option<pyo3_chrono::Duration>,
+                        start_to_close_timeout: Option<pyo3_chrono::Duration>,
+                        heartbeat_timeout: Option<pyo3_chrono::Duration>,
+                        retry_policy: Option<WrappedRetryPolicy>) -> PyResult<PyObject> {
+        let this = slf.borrow();
+        let arguments = this.data_converter.encode_all(py, arguments)?;
+        let future = create_future(py, &slf.to_object(py))?;
+
+        let mut header_fields = HashMap::new();
+        let trace_context = this.state.lock().unwrap().trace_context.as_ref().map(|trace_context| trace_context.clone_ref(py));
+        if let Some(trace_context) = trace_context {
+            inject_context(py, &this.data_converter, &trace_context, &mut header_fields);
+        }
+
+        let activity_id = {
+            let mut state = this.state.lock().unwrap();
+            state.last_activity_seq += 1;
+            let activity_id = state.last_activity_seq.to_string();
+            state.activities.insert(activity_id.clone(), future.clone_ref(py));
+            state.push_command(WrappedCommandVariant::ScheduleActivity(WrappedScheduleActivity {
+                activity_id: activity_id.clone(),
+                activity_type,
+                namespace: String::new(),
+                task_queue: task_queue.unwrap_or_else(|| this.task_queue.clone()),
+                header_fields,
+                arguments,
+                schedule_to_close_timeout,
+                schedule_to_start_timeout,
+                start_to_close_timeout,
+                heartbeat_timeout,
+                retry_policy,
+                cancellation_type: 0,
+            }));
+            activity_id
+        };
+
+        future.call_method1(py, "add_done_callback", (ActivityDoneCallback {
+            state: this.state.clone(),
+            activity_id,
+        },))?;
+        Ok(future)
+    }
+
+    // Ends the run with a new one of the same workflow taking over, the main task gets cancelled so that workflow code
+    // stops at its next await
+    #[args(
+        arguments = "Vec::new()",
+        "*",
+        workflow_type = "None",
+        task_queue = "None",
+        workflow_run_timeout = "None",
+        workflow_task_timeout = "None",
+        memo = "HashMap::new()",
+        search_attributes = "HashMap::new()"
+    )]
+    fn continue_as_new(slf: &PyCell<Self>,
+                       py: Python,
+                       arguments: Vec<PyObject>,
+                       workflow_type: Option<String>,
+                       task_queue: Option<String>,
+                       workflow_run_timeout: Option<pyo3_chrono::Duration>,
+                       workflow_task_timeout: Option<pyo3_chrono::Duration>,
+                       memo: HashMap<String, PyObject>,
+                       search_attributes: HashMap<String, PyObject>) -> PyResult<()> {
+        let this = slf.borrow();
+        if this.state.lock().unwrap().finished {
+            return Err(PyRuntimeError::new_err("Workflow has finished already"));
+        }
+        let arguments = this.data_converter.encode_all(py, arguments)?;
+        let mut encoded_memo = HashMap::new();
+        for (key, value) in memo {
+            encoded_memo.insert(key, this.data_converter.encode(py, value.as_ref(py))?);
+        }
+        let mut encoded_search_attributes = HashMap::new();
+        for (key, value) in search_attributes {
+            encoded_search_attributes.insert(key, this.data_converter.encode(py, value.as_ref(py))?);
+        }
+
+        let mut header = HashMap::new();
+        let trace_context = this.state.lock().unwrap().trace_context.as_ref().map(|trace_context| trace_context.clone_ref(py));
+        if let Some(trace_context) = trace_context {
+            inject_context(py, &this.data_converter, &trace_context, &mut header);
+        }
+
+        let main_task = {
+            let mut state = this.state.lock().unwrap();
+            let workflow_type = workflow_type.unwrap_or_else(|| state.workflow_type.clone().unwrap_or_default());
+            state.push_command(WrappedCommandVariant::ContinueAsNewWorkflowExecution(WrappedContinueAsNewWorkflowExecution {
+                workflow_type,
+                task_queue: task_queue.unwrap_or_else(|| this.task_queue.clone()),
+                arguments,
+                workflow_run_timeout,
+                workflow_task_timeout,
+                memo: encoded_memo,
+                header,
+                search_attributes: encoded_search_attributes,
+            }));
+            state.main_task.as_ref().map(|task| task.clone_ref(py))
+        };
+        if let Some(main_task) = main_task {
+            main_task.call_method0(py, "cancel")?;
+        }
+        Ok(())
+    }
+}
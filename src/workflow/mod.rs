@@ -0,0 +1,2 @@
+pub(crate) mod event_loop;
+pub(crate) mod replayer;
@@ -0,0 +1,709 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use base64;
+use prost::Message;
+use prost_types::Timestamp as ProstTimestamp;
+use pyo3::prelude::*;
+use pyo3::exceptions::{
+    PyKeyError,
+    PyValueError,
+};
+use pyo3::types::{
+    PyBytes,
+    PyString,
+};
+use serde_json::Value;
+use temporal_sdk_core::protos::temporal::api::{
+    common::v1::{
+        Payload as ApiPayload,
+        Payloads as ApiPayloads,
+    },
+    failure::v1::{
+        Failure as ApiFailure,
+        failure::FailureInfo,
+    },
+    history::v1::{
+        History,
+        history_event::Attributes,
+    },
+};
+
+use crate::converter::data_converter::WrappedDataConverter;
+use crate::errors::{
+    NondeterminismError,
+    new_err_with_attributes,
+};
+use crate::protos::coresdk::{
+    activity_result::{
+        WrappedActivityResult,
+        WrappedCancelation,
+        WrappedFailure,
+        WrappedStatus as WrappedActivityStatus,
+        WrappedSuccess,
+    },
+    common::{
+        WrappedPayload,
+        WrappedUserCodeFailure,
+    },
+    workflow_activation::{
+        WrappedCancelWorkflow,
+        WrappedFireTimer,
+        WrappedResolveActivity,
+        WrappedSignalWorkflow,
+        WrappedStartWorkflow,
+        WrappedVariant as WrappedJobVariant,
+        WrappedWfActivation,
+        WrappedWfActivationJob,
+    },
+    workflow_commands::{
+        WrappedVariant as WrappedCommandVariant,
+        WrappedWorkflowCommand,
+    },
+};
+use crate::utils::prost_types_timestamp_to_utc_datetime;
+use crate::workflow::event_loop::WrappedWorkflowEventLoop;
+
+
+// Only the parts of history events that either become activation jobs or describe the commands that the workflow
+// code has issued
+enum ReplayEventKind {
+    WorkflowExecutionStarted {
+        workflow_type: String,
+        task_queue: String,
+        arguments: Vec<WrappedPayload>,
+        original_execution_run_id: String,
+    },
+    WorkflowTaskStarted,
+    WorkflowTaskCompleted,
+    TimerStarted { timer_id: String },
+    TimerFired { timer_id: String },
+    TimerCanceled { timer_id: String },
+    ActivityTaskScheduled { activity_id: String, activity_type: String },
+    ActivityTaskCancelRequested { scheduled_event_id: i64 },
+    ActivityTaskResolved { scheduled_event_id: i64, status: WrappedActivityStatus },
+    WorkflowExecutionSignaled { signal_name: String, input: Vec<WrappedPayload>, identity: String },
+    WorkflowExecutionCancelRequested,
+    WorkflowExecutionCompleted,
+    WorkflowExecutionFailed,
+    WorkflowExecutionCanceled,
+    WorkflowExecutionContinuedAsNew { workflow_type: String },
+    // Workflow task scheduling and failures, markers and whatever else that doesn't matter for the replay
+    Other,
+}
+
+
+struct ReplayEvent {
+    event_id: i64,
+    event_time: Option<ProstTimestamp>,
+    kind: ReplayEventKind,
+}
+
+
+// Command as far as the nondeterminism check is concerned, i.e. its type and whatever identifies it
+#[derive(Clone, PartialEq)]
+struct ReplayCommand {
+    command_type: &'static str,
+    attributes: Vec<(&'static str, String)>,
+}
+
+impl fmt::Display for ReplayCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let attributes = self.attributes.iter()
+            .map(|(name, value)| format!("{}={:?}", name, value))
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "{}({})", self.command_type, attributes)
+    }
+}
+
+impl ReplayCommand {
+    fn new(command_type: &'static str, attributes: Vec<(&'static str, String)>) -> Self {
+        ReplayCommand {
+            command_type,
+            attributes,
+        }
+    }
+
+    // None for commands that don't end up in history, i.e. query responses
+    fn from_workflow_command(command: &WrappedWorkflowCommand) -> Option<Self> {
+        match command.variant.as_ref()? {
+            WrappedCommandVariant::StartTimer(start_timer) => Some(ReplayCommand::new("StartTimer", vec![
+                ("timer_id", start_timer.timer_id.clone()),
+            ])),
+            WrappedCommandVariant::CancelTimer(cancel_timer) => Some(ReplayCommand::new("CancelTimer", vec![
+                ("timer_id", cancel_timer.timer_id.clone()),
+            ])),
+            WrappedCommandVariant::ScheduleActivity(schedule_activity) => Some(ReplayCommand::new("ScheduleActivity", vec![
+                ("activity_id", schedule_activity.activity_id.clone()),
+                ("activity_type", schedule_activity.activity_type.clone()),
+            ])),
+            WrappedCommandVariant::RequestCancelActivity(request_cancel_activity) => Some(ReplayCommand::new("RequestCancelActivity", vec![
+                ("activity_id", request_cancel_activity.activity_id.clone()),
+            ])),
+            WrappedCommandVariant::CompleteWorkflowExecution(_) => Some(ReplayCommand::new("CompleteWorkflowExecution", vec![])),
+            WrappedCommandVariant::FailWorkflowExecution(_) => Some(ReplayCommand::new("FailWorkflowExecution", vec![])),
+            WrappedCommandVariant::CancelWorkflowExecution(_) => Some(ReplayCommand::new("CancelWorkflowExecution", vec![])),
+            WrappedCommandVariant::ContinueAsNewWorkflowExecution(continue_as_new) => Some(ReplayCommand::new("ContinueAsNewWorkflowExecution", vec![
+                ("workflow_type", continue_as_new.workflow_type.clone()),
+            ])),
+            WrappedCommandVariant::RespondToQuery(_) => None,
+        }
+    }
+}
+
+
+// Activation jobs that a completed workflow task has been given, and the commands that its completion has recorded
+struct ReplayWorkflowTask {
+    completed_event_id: i64,
+    timestamp: Option<ProstTimestamp>,
+    jobs: Vec<WrappedJobVariant>,
+    commands: Vec<ReplayCommand>,
+}
+
+
+struct ReplayHistory {
+    workflow_type: String,
+    task_queue: String,
+    workflow_tasks: Vec<ReplayWorkflowTask>,
+}
+
+
+// Same seed as the one that Core derives from the run ID
+fn randomness_seed(original_execution_run_id: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    original_execution_run_id.hash(&mut hasher);
+    hasher.finish()
+}
+
+
+fn replay_history(events: Vec<ReplayEvent>) -> PyResult<ReplayHistory> {
+    let mut workflow_type = None;
+    let mut task_queue = String::new();
+    let mut activity_ids = HashMap::new();
+    let mut jobs = Vec::new();
+    let mut workflow_task_started_time = None;
+    let mut workflow_tasks: Vec<ReplayWorkflowTask> = Vec::new();
+
+    for event in events {
+        // Commands recorded by a workflow task completion follow it right away
+        let command = match event.kind {
+            ReplayEventKind::WorkflowExecutionStarted { workflow_type: started_workflow_type, task_queue: started_task_queue, arguments, original_execution_run_id } => {
+                workflow_type = Some(started_workflow_type.clone());
+                task_queue = started_task_queue;
+                jobs.push(WrappedJobVariant::StartWorkflow(WrappedStartWorkflow {
+                    workflow_type: started_workflow_type,
+                    workflow_id: String::new(),
+                    arguments,
+                    randomness_seed: randomness_seed(&original_execution_run_id),
+                    headers: HashMap::new(),
+                }));
+                None
+            }
+            // Activations get the time at which their workflow task has been started
+            ReplayEventKind::WorkflowTaskStarted => {
+                workflow_task_started_time = event.event_time;
+                None
+            }
+            ReplayEventKind::WorkflowTaskCompleted => {
+                workflow_tasks.push(ReplayWorkflowTask {
+                    completed_event_id: event.event_id,
+                    timestamp: workflow_task_started_time.clone(),
+                    jobs: std::mem::take(&mut jobs),
+                    commands: Vec::new(),
+                });
+                None
+            }
+            ReplayEventKind::TimerStarted { timer_id } => Some(ReplayCommand::new("StartTimer", vec![
+                ("timer_id", timer_id),
+            ])),
+            ReplayEventKind::TimerFired { timer_id } => {
+                jobs.push(WrappedJobVariant::FireTimer(WrappedFireTimer { timer_id }));
+                None
+            }
+            ReplayEventKind::TimerCanceled { timer_id } => Some(ReplayCommand::new("CancelTimer", vec![
+                ("timer_id", timer_id),
+            ])),
+            ReplayEventKind::ActivityTaskScheduled { activity_id, activity_type } => {
+                activity_ids.insert(event.event_id, activity_id.clone());
+                Some(ReplayCommand::new("ScheduleActivity", vec![
+                    ("activity_id", activity_id),
+                    ("activity_type", activity_type),
+                ]))
+            }
+            ReplayEventKind::ActivityTaskCancelRequested { scheduled_event_id } => Some(ReplayCommand::new("RequestCancelActivity", vec![
+                ("activity_id", activity_ids.get(&scheduled_event_id).cloned().unwrap_or_default()),
+            ])),
+            ReplayEventKind::ActivityTaskResolved { scheduled_event_id, status } => {
+                let activity_id = match activity_ids.get(&scheduled_event_id) {
+                    None => return Err(PyValueError::new_err(format!(
+                        "History event {} resolves activity scheduled by event {} which isn't in history",
+                        event.event_id,
+                        scheduled_event_id
+                    ))),
+                    Some(activity_id) => activity_id.clone(),
+                };
+                jobs.push(WrappedJobVariant::ResolveActivity(WrappedResolveActivity {
+                    activity_id,
+                    result: Some(WrappedActivityResult { status: Some(status) }),
+                }));
+                None
+            }
+            ReplayEventKind::WorkflowExecutionSignaled { signal_name, input, identity } => {
+                jobs.push(WrappedJobVariant::SignalWorkflow(WrappedSignalWorkflow {
+                    signal_name,
+                    input,
+                    identity,
+                }));
+                None
+            }
+            ReplayEventKind::WorkflowExecutionCancelRequested => {
+                jobs.push(WrappedJobVariant::CancelWorkflow(WrappedCancelWorkflow { details: Vec::new() }));
+                None
+            }
+            ReplayEventKind::WorkflowExecutionCompleted => Some(ReplayCommand::new("CompleteWorkflowExecution", vec![])),
+            ReplayEventKind::WorkflowExecutionFailed => Some(ReplayCommand::new("FailWorkflowExecution", vec![])),
+            ReplayEventKind::WorkflowExecutionCanceled => Some(ReplayCommand::new("CancelWorkflowExecution", vec![])),
+            ReplayEventKind::WorkflowExecutionContinuedAsNew { workflow_type } => Some(ReplayCommand::new("ContinueAsNewWorkflowExecution", vec![
+                ("workflow_type", workflow_type),
+            ])),
+            ReplayEventKind::Other => None,
+        };
+
+        if let Some(command) = command {
+            match workflow_tasks.last_mut() {
+                None => return Err(PyValueError::new_err(format!(
+                    "History event {} records a command before any workflow task got completed",
+                    event.event_id
+                ))),
+                Some(workflow_task) => workflow_task.commands.push(command),
+            }
+        }
+    }
+
+    match workflow_type {
+        None => Err(PyValueError::new_err("History doesn't start with a WorkflowExecutionStarted event")),
+        Some(workflow_type) => Ok(ReplayHistory {
+            workflow_type,
+            task_queue,
+            workflow_tasks,
+        }),
+    }
+}
+
+
+fn api_payloads(payloads: Option<ApiPayloads>) -> Vec<WrappedPayload> {
+    payloads.map(|payloads| payloads.payloads).unwrap_or_default().into_iter().map(|payload: ApiPayload| {
+        WrappedPayload {
+            metadata: payload.metadata,
+            data: payload.data,
+        }
+    }).collect()
+}
+
+
+fn api_failure(failure: ApiFailure) -> WrappedUserCodeFailure {
+    let (r#type, non_retryable) = match &failure.failure_info {
+        Some(FailureInfo::ApplicationFailureInfo(info)) => (info.r#type.clone(), info.non_retryable),
+        _ => (String::new(), false),
+    };
+    WrappedUserCodeFailure {
+        message: failure.message,
+        r#type,
+        source: failure.source,
+        stack_trace: failure.stack_trace,
+        non_retryable,
+        cause: failure.cause.map(|cause| Box::new(api_failure(*cause))),
+    }
+}
+
+
+fn activity_failed(failure: Option<ApiFailure>) -> WrappedActivityStatus {
+    WrappedActivityStatus::Failed(WrappedFailure {
+        failure: failure.map(api_failure),
+    })
+}
+
+
+fn protobuf_history_events(data: &[u8]) -> PyResult<Vec<ReplayEvent>> {
+    let history = match History::decode(data) {
+        Ok(history) => history,
+        Err(err) => return Err(PyValueError::new_err(format!(
+            "History is not a valid History protobuf message: {}",
+            err.to_string()
+        ))),
+    };
+    Ok(history.events.into_iter().map(|event| {
+        let kind = match event.attributes {
+            Some(Attributes::WorkflowExecutionStartedEventAttributes(attributes)) => ReplayEventKind::WorkflowExecutionStarted {
+                workflow_type: attributes.workflow_type.map(|workflow_type| workflow_type.name).unwrap_or_default(),
+                task_queue: attributes.task_queue.map(|task_queue| task_queue.name).unwrap_or_default(),
+                arguments: api_payloads(attributes.input),
+                original_execution_run_id: attributes.original_execution_run_id,
+            },
+            Some(Attributes::WorkflowTaskStartedEventAttributes(_)) => ReplayEventKind::WorkflowTaskStarted,
+            Some(Attributes::WorkflowTaskCompletedEventAttributes(_)) => ReplayEventKind::WorkflowTaskCompleted,
+            Some(Attributes::TimerStartedEventAttributes(attributes)) => ReplayEventKind::TimerStarted {
+                timer_id: attributes.timer_id,
+            },
+            Some(Attributes::TimerFiredEventAttributes(attributes)) => ReplayEventKind::TimerFired {
+                timer_id: attributes.timer_id,
+            },
+            Some(Attributes::TimerCanceledEventAttributes(attributes)) => ReplayEventKind::TimerCanceled {
+                timer_id: attributes.timer_id,
+            },
+            Some(Attributes::ActivityTaskScheduledEventAttributes(attributes)) => ReplayEventKind::ActivityTaskScheduled {
+                activity_id: attributes.activity_id,
+                activity_type: attributes.activity_type.map(|activity_type| activity_type.name).unwrap_or_default(),
+            },
+            Some(Attributes::ActivityTaskCancelRequestedEventAttributes(attributes)) => ReplayEventKind::ActivityTaskCancelRequested {
+                scheduled_event_id: attributes.scheduled_event_id,
+            },
+            Some(Attributes::ActivityTaskCompletedEventAttributes(attributes)) => ReplayEventKind::ActivityTaskResolved {
+                scheduled_event_id: attributes.scheduled_event_id,
+                status: WrappedActivityStatus::Completed(WrappedSuccess {
+                    result: api_payloads(attributes.result).into_iter().next(),
+                }),
+            },
+            Some(Attributes::ActivityTaskFailedEventAttributes(attributes)) => ReplayEventKind::ActivityTaskResolved {
+                scheduled_event_id: attributes.scheduled_event_id,
+                status: activity_failed(attributes.failure),
+            },
+            Some(Attributes::ActivityTaskTimedOutEventAttributes(attributes)) => ReplayEventKind::ActivityTaskResolved {
+                scheduled_event_id: attributes.scheduled_event_id,
+                status: activity_failed(attributes.failure),
+            },
+            Some(Attributes::ActivityTaskCanceledEventAttributes(attributes)) => ReplayEventKind::ActivityTaskResolved {
+                scheduled_event_id: attributes.scheduled_event_id,
+                status: WrappedActivityStatus::Canceled(WrappedCancelation {
+                    details: api_payloads(attributes.details).into_iter().next(),
+                }),
+            },
+            Some(Attributes::WorkflowExecutionSignaledEventAttributes(attributes)) => ReplayEventKind::WorkflowExecutionSignaled {
+                signal_name: attributes.signal_name,
+                input: api_payloads(attributes.input),
+                identity: attributes.identity,
+            },
+            Some(Attributes::WorkflowExecutionCancelRequestedEventAttributes(_)) => ReplayEventKind::WorkflowExecutionCancelRequested,
+            Some(Attributes::WorkflowExecutionCompletedEventAttributes(_)) => ReplayEventKind::WorkflowExecutionCompleted,
+            Some(Attributes::WorkflowExecutionFailedEventAttributes(_)) => ReplayEventKind::WorkflowExecutionFailed,
+            Some(Attributes::WorkflowExecutionCanceledEventAttributes(_)) => ReplayEventKind::WorkflowExecutionCanceled,
+            Some(Attributes::WorkflowExecutionContinuedAsNewEventAttributes(attributes)) => ReplayEventKind::WorkflowExecutionContinuedAsNew {
+                workflow_type: attributes.workflow_type.map(|workflow_type| workflow_type.name).unwrap_or_default(),
+            },
+            _ => ReplayEventKind::Other,
+        };
+        ReplayEvent {
+            event_id: event.event_id,
+            event_time: event.event_time,
+            kind,
+        }
+    }).collect())
+}
+
+
+// Exported JSON follows protobuf's JSON mapping: camelCase field names, 64-bit integers as strings and bytes as
+// base64
+fn json_string(value: &Value, key: &str) -> String {
+    value.get(key).and_then(|value| value.as_str()).unwrap_or_default().to_string()
+}
+
+
+fn json_i64(value: &Value, key: &str) -> i64 {
+    match value.get(key) {
+        Some(Value::String(string)) => string.parse().unwrap_or_default(),
+        Some(number) => number.as_i64().unwrap_or_default(),
+        None => 0,
+    }
+}
+
+
+fn json_name(value: &Value, key: &str) -> String {
+    value.get(key).map(|value| json_string(value, "name")).unwrap_or_default()
+}
+
+
+fn json_bytes(value: &Value) -> PyResult<Vec<u8>> {
+    match base64::decode(value.as_str().unwrap_or_default()) {
+        Ok(bytes) => Ok(bytes),
+        Err(err) => Err(PyValueError::new_err(format!(
+            "History has invalid base64 data: {}",
+            err.to_string()
+        ))),
+    }
+}
+
+
+fn json_payloads(value: &Value, key: &str) -> PyResult<Vec<WrappedPayload>> {
+    let payloads = match value.get(key).and_then(|payloads| payloads.get("payloads")).and_then(|payloads| payloads.as_array()) {
+        None => return Ok(Vec::new()),
+        Some(payloads) => payloads,
+    };
+    payloads.iter().map(|payload| {
+        let mut metadata = HashMap::new();
+        if let Some(payload_metadata) = payload.get("metadata").and_then(|metadata| metadata.as_object()) {
+            for (name, value) in payload_metadata {
+                metadata.insert(name.clone(), json_bytes(value)?);
+            }
+        }
+        Ok(WrappedPayload {
+            metadata,
+            data: match payload.get("data") {
+                None => Vec::new(),
+                Some(data) => json_bytes(data)?,
+            },
+        })
+    }).collect()
+}
+
+
+fn json_failure(value: &Value) -> WrappedUserCodeFailure {
+    let info = value.get("applicationFailureInfo");
+    WrappedUserCodeFailure {
+        message: json_string(value, "message"),
+        r#type: info.map(|info| json_string(info, "type")).unwrap_or_default(),
+        source: json_string(value, "source"),
+        stack_trace: json_string(value, "stackTrace"),
+        non_retryable: info.and_then(|info| info.get("nonRetryable")).and_then(|non_retryable| non_retryable.as_bool()).unwrap_or(false),
+        cause: value.get("cause").map(|cause| Box::new(json_failure(cause))),
+    }
+}
+
+
+fn json_activity_failed(attributes: &Value) -> WrappedActivityStatus {
+    WrappedActivityStatus::Failed(WrappedFailure {
+        failure: attributes.get("failure").map(json_failure),
+    })
+}
+
+
+fn json_timestamp(value: &Value, key: &str) -> PyResult<Option<ProstTimestamp>> {
+    let timestamp = match value.get(key).and_then(|timestamp| timestamp.as_str()) {
+        None => return Ok(None),
+        Some(timestamp) => timestamp,
+    };
+    match chrono::DateTime::parse_from_rfc3339(timestamp) {
+        Ok(datetime) => Ok(Some(ProstTimestamp {
+            seconds: datetime.timestamp(),
+            nanos: datetime.timestamp_subsec_nanos() as i32,
+        })),
+        Err(err) => Err(PyValueError::new_err(format!(
+            "History has invalid timestamp {}: {}",
+            timestamp,
+            err.to_string()
+        ))),
+    }
+}
+
+
+// Events get told apart by their attributes rather than by their "eventType" as the latter is either e.g.
+// "WorkflowExecutionStarted" or "EVENT_TYPE_WORKFLOW_EXECUTION_STARTED" depending on who has exported the history
+fn json_history_events(json: &str) -> PyResult<Vec<ReplayEvent>> {
+    let history: Value = match serde_json::from_str(json) {
+        Ok(history) => history,
+        Err(err) => return Err(PyValueError::new_err(format!(
+            "History is not valid JSON: {}",
+            err.to_string()
+        ))),
+    };
+    let events = match history.get("events").and_then(|events| events.as_array()) {
+        None => return Err(PyKeyError::new_err("History doesn't have an \"events\" list")),
+        Some(events) => events,
+    };
+
+    events.iter().map(|event| {
+        let event_attributes = |key: &str| event.get(key);
+        let kind = if let Some(attributes) = event_attributes("workflowExecutionStartedEventAttributes") {
+            ReplayEventKind::WorkflowExecutionStarted {
+                workflow_type: json_name(attributes, "workflowType"),
+                task_queue: json_name(attributes, "taskQueue"),
+                arguments: json_payloads(attributes, "input")?,
+                original_execution_run_id: json_string(attributes, "originalExecutionRunId"),
+            }
+        } else if event_attributes("workflowTaskStartedEventAttributes").is_some() {
+            ReplayEventKind::WorkflowTaskStarted
+        } else if event_attributes("workflowTaskCompletedEventAttributes").is_some() {
+            ReplayEventKind::WorkflowTaskCompleted
+        } else if let Some(attributes) = event_attributes("timerStartedEventAttributes") {
+            ReplayEventKind::TimerStarted { timer_id: json_string(attributes, "timerId") }
+        } else if let Some(attributes) = event_attributes("timerFiredEventAttributes") {
+            ReplayEventKind::TimerFired { timer_id: json_string(attributes, "timerId") }
+        } else if let Some(attributes) = event_attributes("timerCanceledEventAttributes") {
+            ReplayEventKind::TimerCanceled { timer_id: json_string(attributes, "timerId") }
+        } else if let Some(attributes) = event_attributes("activityTaskScheduledEventAttributes") {
+            ReplayEventKind::ActivityTaskScheduled {
+                activity_id: json_string(attributes, "activityId"),
+                activity_type: json_name(attributes, "activityType"),
+            }
+        } else if let Some(attributes) = event_attributes("activityTaskCancelRequestedEventAttributes") {
+            ReplayEventKind::ActivityTaskCancelRequested { scheduled_event_id: json_i64(attributes, "scheduledEventId") }
+        } else if let Some(attributes) = event_attributes("activityTaskCompletedEventAttributes") {
+            ReplayEventKind::ActivityTaskResolved {
+                scheduled_event_id: json_i64(attributes, "scheduledEventId"),
+                status: WrappedActivityStatus::Completed(WrappedSuccess {
+                    result: json_payloads(attributes, "result")?.into_iter().next(),
+                }),
+            }
+        } else if let Some(attributes) = event_attributes("activityTaskFailedEventAttributes") {
+            ReplayEventKind::ActivityTaskResolved {
+                scheduled_event_id: json_i64(attributes, "scheduledEventId"),
+                status: json_activity_failed(attributes),
+            }
+        } else if let Some(attributes) = event_attributes("activityTaskTimedOutEventAttributes") {
+            ReplayEventKind::ActivityTaskResolved {
+                scheduled_event_id: json_i64(attributes, "scheduledEventId"),
+                status: json_activity_failed(attributes),
+            }
+        } else if let Some(attributes) = event_attributes("activityTaskCanceledEventAttributes") {
+            ReplayEventKind::ActivityTaskResolved {
+                scheduled_event_id: json_i64(attributes, "scheduledEventId"),
+                status: WrappedActivityStatus::Canceled(WrappedCancelation {
+                    details: json_payloads(attributes, "details")?.into_iter().next(),
+                }),
+            }
+        } else if let Some(attributes) = event_attributes("workflowExecutionSignaledEventAttributes") {
+            ReplayEventKind::WorkflowExecutionSignaled {
+                signal_name: json_string(attributes, "signalName"),
+                input: json_payloads(attributes, "input")?,
+                identity: json_string(attributes, "identity"),
+            }
+        } else if event_attributes("workflowExecutionCancelRequestedEventAttributes").is_some() {
+            ReplayEventKind::WorkflowExecutionCancelRequested
+        } else if event_attributes("workflowExecutionCompletedEventAttributes").is_some() {
+            ReplayEventKind::WorkflowExecutionCompleted
+        } else if event_attributes("workflowExecutionFailedEventAttributes").is_some() {
+            ReplayEventKind::WorkflowExecutionFailed
+        } else if event_attributes("workflowExecutionCanceledEventAttributes").is_some() {
+            ReplayEventKind::WorkflowExecutionCanceled
+        } else if let Some(attributes) = event_attributes("workflowExecutionContinuedAsNewEventAttributes") {
+            ReplayEventKind::WorkflowExecutionContinuedAsNew { workflow_type: json_name(attributes, "workflowType") }
+        } else {
+            ReplayEventKind::Other
+        };
+        Ok(ReplayEvent {
+            event_id: json_i64(event, "eventId"),
+            event_time: json_timestamp(event, "eventTime")?,
+            kind,
+        })
+    }).collect()
+}
+
+
+// Side by side listing of the commands, with "-" for the ones only in history and "+" for the ones only issued by
+// the workflow code
+fn commands_diff(expected: &[ReplayCommand], actual: &[ReplayCommand]) -> String {
+    let mut lines = Vec::new();
+    for i in 0..expected.len().max(actual.len()) {
+        match (expected.get(i), actual.get(i)) {
+            (Some(expected), Some(actual)) if expected == actual => lines.push(format!("  {}", expected)),
+            (expected, actual) => {
+                if let Some(expected) = expected {
+                    lines.push(format!("- {}", expected));
+                }
+                if let Some(actual) = actual {
+                    lines.push(format!("+ {}", actual));
+                }
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+
+// Replays workflow histories against the workflow code, making sure that the code still issues the same commands as
+// the ones that got recorded in history
+#[pyclass(name = "Replayer")]
+pub struct WrappedReplayer {
+    workflows: HashMap<String, PyObject>,
+    #[pyo3(get)]
+    data_converter: WrappedDataConverter,
+}
+
+impl WrappedReplayer {
+    fn replay_events(&self, py: Python, events: Vec<ReplayEvent>) -> PyResult<()> {
+        let history = replay_history(events)?;
+        let workflow = match self.workflows.get(&history.workflow_type) {
+            None => return Err(PyKeyError::new_err(format!(
+                "Workflow type {} is not registered with the replayer",
+                history.workflow_type
+            ))),
+            Some(workflow) => workflow.clone_ref(py),
+        };
+        let event_loop = PyCell::new(py, WrappedWorkflowEventLoop::new(
+            py,
+            workflow,
+            history.task_queue,
+            Some(self.data_converter.clone()),
+        )?)?;
+
+        let result = self.replay_workflow_tasks(py, event_loop, history.workflow_tasks);
+        event_loop.call_method0("close")?;
+        result
+    }
+
+    fn replay_workflow_tasks(&self,
+                             py: Python,
+                             event_loop: &PyCell<WrappedWorkflowEventLoop>,
+                             workflow_tasks: Vec<ReplayWorkflowTask>) -> PyResult<()> {
+        for (index, workflow_task) in workflow_tasks.into_iter().enumerate() {
+            let wf_activation = WrappedWfActivation {
+                run_id: String::from("replay"),
+                timestamp: prost_types_timestamp_to_utc_datetime(workflow_task.timestamp)?,
+                is_replaying: true,
+                jobs: workflow_task.jobs.into_iter().map(|variant| WrappedWfActivationJob { variant: Some(variant) }).collect(),
+            };
+            let actual = WrappedWorkflowEventLoop::activate(event_loop, py, wf_activation)?
+                .iter()
+                .filter_map(ReplayCommand::from_workflow_command)
+                .collect::<Vec<_>>();
+
+            if actual != workflow_task.commands {
+                return Err(new_err_with_attributes::<NondeterminismError>(py, format!(
+                    "Workflow task {} (completed by history event {}) issued different commands than the ones in \
+                    history:\n{}",
+                    index + 1,
+                    workflow_task.completed_event_id,
+                    commands_diff(&workflow_task.commands, &actual)
+                ), vec![
+                    ("workflow_task", (index + 1).into_py(py)),
+                    ("event_id", workflow_task.completed_event_id.into_py(py)),
+                    ("expected_commands", workflow_task.commands.iter().map(|command| command.to_string()).collect::<Vec<_>>().into_py(py)),
+                    ("actual_commands", actual.iter().map(|command| command.to_string()).collect::<Vec<_>>().into_py(py)),
+                ]));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl WrappedReplayer {
+    #[new]
+    #[args(workflows = "HashMap::new()", "*", data_converter = "None")]
+    fn new(py: Python,
+           workflows: HashMap<String, PyObject>,
+           data_converter: Option<WrappedDataConverter>) -> PyResult<Self> {
+        Ok(WrappedReplayer {
+            workflows,
+            data_converter: match data_converter {
+                None => WrappedDataConverter::new_default(py)?,
+                Some(data_converter) => data_converter,
+            },
+        })
+    }
+
+    // History is either exported JSON, as str or as a dict, or a serialized History protobuf message, as bytes
+    fn replay(&self, py: Python, history: &PyAny) -> PyResult<()> {
+        let events = if let Ok(data) = history.downcast::<PyBytes>() {
+            protobuf_history_events(data.as_bytes())?
+        } else if let Ok(json) = history.downcast::<PyString>() {
+            json_history_events(json.to_str()?)?
+        } else {
+            let json: String = py.import("json")?.call_method1("dumps", (history,))?.extract()?;
+            json_history_events(&json)?
+        };
+        self.replay_events(py, events)
+    }
+}